@@ -19,3 +19,4 @@ mod to_graphviz;
 pub mod program;
 pub mod macros;
 pub mod program_normalization;
+pub mod program_slicing;
@@ -71,6 +71,42 @@ impl NormalizationInfo {
         }
     }
 
+    /// Normalize a position id, if it occurs in the normalized program.
+    pub fn try_normalize_position_id(&self, pos_id: u64) -> Option<u64> {
+        match self {
+            NormalizationInfo::LowProgram => Some(pos_id),
+            NormalizationInfo::LegacyProgram {
+                original_position_ids,
+            } => original_position_ids
+                .binary_search(&pos_id)
+                .ok()
+                .map(|index| index as u64),
+        }
+    }
+
+    /// Normalize a verification result, which is the inverse of
+    /// `denormalize_result`. Returns `None` if the result refers to positions
+    /// that do not occur in the normalized program.
+    pub fn try_normalize_result(&self, result: &VerificationResult) -> Option<VerificationResult> {
+        let mut result = result.clone();
+        if let VerificationResult::Failure(ref mut ver_errors) = result {
+            for ver_error in ver_errors.iter_mut() {
+                for pos in [
+                    &mut ver_error.pos_id,
+                    &mut ver_error.offending_pos_id,
+                    &mut ver_error.reason_pos_id,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    let pos_id = pos.parse().ok()?;
+                    *pos = self.try_normalize_position_id(pos_id)?.to_string();
+                }
+            }
+        }
+        Some(result)
+    }
+
     /// Denormalize a position.
     pub fn denormalize_position(&self, pos: Position) -> Position {
         Position::new(
@@ -0,0 +1,451 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Splitting of vir::legacy programs into slices that can be verified
+//! independently of each other: one slice for the functions and predicates of
+//! the program, and one slice for each method. The slice of a method contains
+//! the method together with the transitive closure of the functions,
+//! predicates, domains, fields and methods that it depends on, which is
+//! everything that can influence its verification.
+
+use crate::vir::{
+    BodylessMethod, CfgMethod, Domain, DomainFunc, Expr, ExprWalker, Field, Function, LocalVar,
+    MaybeEnumVariantIndex, PermAmount, Position, Predicate, Program, StmtWalker, Successor, Type,
+};
+use fxhash::FxHashSet;
+
+pub struct ProgramSlice {
+    /// A program containing the verified items of the slice and their
+    /// dependencies.
+    pub program: Program,
+    /// The methods of the sliced program that are verified by this slice.
+    pub methods: Vec<String>,
+    /// The positions of the verified items of the slice. A verification error
+    /// that is reported at one of these positions belongs to this slice.
+    pub own_positions: FxHashSet<u64>,
+}
+
+/// Splits the program into slices. The first slice verifies the functions and
+/// predicates, the following ones verify the methods, in order.
+pub fn slice_program(program: &Program) -> Vec<ProgramSlice> {
+    let mut slices = Vec::with_capacity(program.methods.len() + 1);
+
+    let mut dependencies = Dependencies::default();
+    let mut own_positions = FxHashSet::default();
+    for function in &program.functions {
+        dependencies.functions.insert(function.name.clone());
+        function.visit_expressions(|e| {
+            e.visit_positions(|p| {
+                own_positions.insert(p.id());
+            })
+        });
+    }
+    for predicate in &program.viper_predicates {
+        dependencies.predicates.insert(predicate.name().to_string());
+        predicate.visit_expressions(|e| {
+            e.visit_positions(|p| {
+                own_positions.insert(p.id());
+            })
+        });
+    }
+    dependencies.close_over(program);
+    slices.push(ProgramSlice {
+        program: dependencies.restrict(program, program.name.clone()),
+        methods: vec![],
+        own_positions,
+    });
+
+    for method in &program.methods {
+        let mut dependencies = Dependencies::default();
+        dependencies.methods.insert(method.name());
+        dependencies.close_over(program);
+        let mut own_positions = FxHashSet::default();
+        method.walk_expressions(|e| {
+            e.visit_positions(|p| {
+                own_positions.insert(p.id());
+            })
+        });
+        method.walk_statements(|s| {
+            s.visit_positions(|p| {
+                own_positions.insert(p.id());
+            })
+        });
+        let slice_program = dependencies.restrict(program, method.name());
+        slices.push(ProgramSlice {
+            methods: slice_program.methods.iter().map(|m| m.name()).collect(),
+            program: slice_program,
+            own_positions,
+        });
+    }
+
+    slices
+}
+
+/// The names of the items that are used by some part of a program.
+#[derive(Default)]
+struct Dependencies {
+    functions: FxHashSet<String>,
+    predicates: FxHashSet<String>,
+    domains: FxHashSet<String>,
+    fields: FxHashSet<String>,
+    /// Both methods with a body and bodyless methods.
+    methods: FxHashSet<String>,
+}
+
+impl Dependencies {
+    /// Adds the dependencies of the items that are already used, until reaching
+    /// a fixed point.
+    fn close_over(&mut self, program: &Program) {
+        // A domain whose axioms mention a used domain constrains it, even if
+        // it is not used itself.
+        let axiom_dependencies: Vec<_> = program
+            .domains
+            .iter()
+            .map(|domain| {
+                let mut dependencies = Dependencies::default();
+                for axiom in &domain.axioms {
+                    ExprWalker::walk(&mut dependencies, &axiom.expr);
+                }
+                dependencies.domains.remove(&domain.name);
+                dependencies.domains
+            })
+            .collect();
+
+        let mut walked = Dependencies::default();
+        loop {
+            let mut changed = false;
+            for function in &program.functions {
+                if self.functions.contains(&function.name)
+                    && walked.functions.insert(function.name.clone())
+                {
+                    self.walk_function(function);
+                    changed = true;
+                }
+            }
+            for predicate in &program.viper_predicates {
+                if self.predicates.contains(predicate.name())
+                    && walked.predicates.insert(predicate.name().to_string())
+                {
+                    self.walk_predicate(predicate);
+                    changed = true;
+                }
+            }
+            for method in &program.builtin_methods {
+                if self.methods.contains(&method.name) && walked.methods.insert(method.name.clone())
+                {
+                    self.walk_builtin_method(method);
+                    changed = true;
+                }
+            }
+            for method in &program.methods {
+                if self.methods.contains(&method.method_name)
+                    && walked.methods.insert(method.name())
+                {
+                    self.walk_method(method);
+                    changed = true;
+                }
+            }
+            for (domain, axiom_domains) in program.domains.iter().zip(&axiom_dependencies) {
+                if !self.domains.contains(&domain.name)
+                    && axiom_domains.iter().any(|name| self.domains.contains(name))
+                {
+                    self.domains.insert(domain.name.clone());
+                }
+                if self.domains.contains(&domain.name) && walked.domains.insert(domain.name.clone())
+                {
+                    self.walk_domain(domain);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Builds a program with the used items of the given one.
+    fn restrict(&self, program: &Program, name: String) -> Program {
+        Program {
+            name,
+            domains: program
+                .domains
+                .iter()
+                .filter(|domain| self.domains.contains(&domain.name))
+                .cloned()
+                .collect(),
+            fields: program
+                .fields
+                .iter()
+                .filter(|field| self.fields.contains(&field.name))
+                .cloned()
+                .collect(),
+            builtin_methods: program
+                .builtin_methods
+                .iter()
+                .filter(|method| self.methods.contains(&method.name))
+                .cloned()
+                .collect(),
+            methods: program
+                .methods
+                .iter()
+                .filter(|method| self.methods.contains(&method.method_name))
+                .cloned()
+                .collect(),
+            functions: program
+                .functions
+                .iter()
+                .filter(|function| self.functions.contains(&function.name))
+                .cloned()
+                .collect(),
+            viper_predicates: program
+                .viper_predicates
+                .iter()
+                .filter(|predicate| self.predicates.contains(predicate.name()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn walk_function(&mut self, function: &Function) {
+        for arg in &function.formal_args {
+            ExprWalker::walk_local_var(self, arg);
+        }
+        ExprWalker::walk_type(self, &function.return_type);
+        function.visit_expressions(|e| ExprWalker::walk(self, e));
+    }
+
+    fn walk_predicate(&mut self, predicate: &Predicate) {
+        ExprWalker::walk(self, &predicate.self_place());
+        if let Some(body) = predicate.body() {
+            ExprWalker::walk(self, &body);
+        }
+    }
+
+    fn walk_builtin_method(&mut self, method: &BodylessMethod) {
+        for var in method.formal_args.iter().chain(&method.formal_returns) {
+            ExprWalker::walk_local_var(self, var);
+        }
+        for expr in method.pres.iter().chain(&method.posts) {
+            ExprWalker::walk(self, expr);
+        }
+    }
+
+    fn walk_method(&mut self, method: &CfgMethod) {
+        for var in method.formal_returns.iter().chain(&method.local_vars) {
+            ExprWalker::walk_local_var(self, var);
+        }
+        method.walk_statements(|stmt| StmtWalker::walk(self, stmt));
+        method.walk_successors(|successor| {
+            if let Successor::GotoSwitch(targets, _) = successor {
+                for (guard, _) in targets {
+                    ExprWalker::walk(self, guard);
+                }
+            }
+        });
+    }
+
+    fn walk_domain(&mut self, domain: &Domain) {
+        for typ in &domain.type_vars {
+            ExprWalker::walk_type(self, typ);
+        }
+        for function in &domain.functions {
+            for arg in &function.formal_args {
+                ExprWalker::walk_local_var(self, arg);
+            }
+            ExprWalker::walk_type(self, &function.return_type);
+        }
+        for axiom in &domain.axioms {
+            ExprWalker::walk(self, &axiom.expr);
+        }
+    }
+}
+
+impl ExprWalker for Dependencies {
+    fn walk_type(&mut self, typ: &Type) {
+        match typ {
+            Type::Seq(elem_type) => self.walk_type(elem_type),
+            Type::Map(key_type, val_type) => {
+                self.walk_type(key_type);
+                self.walk_type(val_type);
+            }
+            Type::TypedRef(name) => {
+                self.predicates.insert(name.clone());
+            }
+            Type::Domain(name) => {
+                self.domains.insert(name.clone());
+            }
+            Type::Snapshot(name) => {
+                // See the lowering of snapshot types to Viper.
+                self.domains.insert(format!("Snap${}", name));
+            }
+            _ => {}
+        }
+    }
+
+    fn walk_variant(&mut self, base: &Expr, variant: &Field, _pos: &Position) {
+        self.fields.insert(variant.name.clone());
+        ExprWalker::walk(self, base);
+        self.walk_type(&variant.typ);
+    }
+
+    fn walk_field(&mut self, receiver: &Expr, field: &Field, _pos: &Position) {
+        self.fields.insert(field.name.clone());
+        ExprWalker::walk(self, receiver);
+        self.walk_type(&field.typ);
+    }
+
+    fn walk_predicate_access_predicate(
+        &mut self,
+        name: &str,
+        arg: &Expr,
+        _perm_amount: PermAmount,
+        _pos: &Position,
+    ) {
+        self.predicates.insert(name.to_string());
+        ExprWalker::walk(self, arg);
+    }
+
+    fn walk_unfolding(
+        &mut self,
+        name: &str,
+        args: &[Expr],
+        body: &Expr,
+        _perm: PermAmount,
+        _variant: &MaybeEnumVariantIndex,
+        _pos: &Position,
+    ) {
+        self.predicates.insert(name.to_string());
+        for arg in args {
+            ExprWalker::walk(self, arg);
+        }
+        ExprWalker::walk(self, body);
+    }
+
+    fn walk_func_app(
+        &mut self,
+        name: &str,
+        args: &[Expr],
+        formal_args: &[LocalVar],
+        return_type: &Type,
+        _pos: &Position,
+    ) {
+        self.functions.insert(name.to_string());
+        for arg in args {
+            ExprWalker::walk(self, arg);
+        }
+        for arg in formal_args {
+            ExprWalker::walk_local_var(self, arg);
+        }
+        self.walk_type(return_type);
+    }
+
+    fn walk_domain_func_app(&mut self, func: &DomainFunc, args: &[Expr], _pos: &Position) {
+        self.domains.insert(func.domain_name.clone());
+        for arg in args {
+            ExprWalker::walk(self, arg);
+        }
+        for arg in &func.formal_args {
+            ExprWalker::walk_local_var(self, arg);
+        }
+        self.walk_type(&func.return_type);
+    }
+}
+
+impl StmtWalker for Dependencies {
+    fn walk_expr(&mut self, expr: &Expr) {
+        ExprWalker::walk(self, expr);
+    }
+
+    fn walk_local_var(&mut self, local_var: &LocalVar) {
+        ExprWalker::walk_local_var(self, local_var);
+    }
+
+    fn walk_method_call(&mut self, method_name: &str, args: &[Expr], targets: &[LocalVar]) {
+        self.methods.insert(method_name.to_string());
+        for arg in args {
+            StmtWalker::walk_expr(self, arg);
+        }
+        for target in targets {
+            StmtWalker::walk_local_var(self, target);
+        }
+    }
+
+    fn walk_fold(
+        &mut self,
+        predicate_name: &str,
+        args: &[Expr],
+        _perm: &PermAmount,
+        _variant: &MaybeEnumVariantIndex,
+        _pos: &Position,
+    ) {
+        self.predicates.insert(predicate_name.to_string());
+        for arg in args {
+            StmtWalker::walk_expr(self, arg);
+        }
+    }
+
+    fn walk_unfold(
+        &mut self,
+        predicate_name: &str,
+        args: &[Expr],
+        _perm: &PermAmount,
+        _variant: &MaybeEnumVariantIndex,
+    ) {
+        self.predicates.insert(predicate_name.to_string());
+        for arg in args {
+            StmtWalker::walk_expr(self, arg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::Stmt;
+
+    fn function(name: &str) -> Function {
+        Function {
+            name: name.to_string(),
+            formal_args: vec![],
+            return_type: Type::Bool,
+            pres: vec![],
+            posts: vec![],
+            body: Some(true.into()),
+        }
+    }
+
+    fn method(name: &str, assertion: Expr) -> CfgMethod {
+        let mut method = CfgMethod::new(name.to_string(), vec![], vec![]);
+        method.add_block("start", vec![Stmt::Assert(assertion, Position::default())]);
+        method
+    }
+
+    #[test]
+    fn method_slices_only_contain_dependencies() {
+        let program = Program {
+            name: "program".to_string(),
+            domains: vec![],
+            fields: vec![],
+            builtin_methods: vec![],
+            methods: vec![
+                method("calls_f", function("f").apply(vec![])),
+                method("calls_nothing", true.into()),
+            ],
+            functions: vec![function("f"), function("g")],
+            viper_predicates: vec![],
+        };
+
+        let slices = slice_program(&program);
+
+        assert_eq!(slices.len(), 3);
+        assert!(slices[0].methods.is_empty());
+        assert_eq!(slices[0].program.functions.len(), 2);
+        assert_eq!(slices[1].methods, vec!["calls_f".to_string()]);
+        assert_eq!(slices[1].program.functions, vec![function("f")]);
+        assert_eq!(slices[2].methods, vec!["calls_nothing".to_string()]);
+        assert!(slices[2].program.functions.is_empty());
+    }
+}
@@ -60,6 +60,13 @@ pub use prusti_contracts_proc_macros::terminates;
 /// A macro to annotate body variant of a loop to prove termination
 pub use prusti_contracts_proc_macros::body_variant;
 
+/// A macro to declare a lemma: a terminating ghost function whose
+/// postcondition can be used after applying it with `prusti_apply!`
+pub use prusti_contracts_proc_macros::lemma;
+
+/// A macro to apply a lemma in a ghost statement
+pub use prusti_contracts_proc_macros::prusti_apply;
+
 #[cfg(not(feature = "prusti"))]
 mod private {
     use core::marker::PhantomData;
@@ -94,6 +101,20 @@ mod private {
         _val_phantom: PhantomData<V>,
     }
 
+    /// A set type
+    #[non_exhaustive]
+    #[derive(PartialEq, Eq, Copy, Clone)]
+    pub struct Set<T> {
+        _phantom: PhantomData<T>,
+    }
+
+    /// A multiset type
+    #[non_exhaustive]
+    #[derive(PartialEq, Eq, Copy, Clone)]
+    pub struct Multiset<T> {
+        _phantom: PhantomData<T>,
+    }
+
     /// a mathematical (unbounded) integer type
     /// it should not be constructed from running rust code, hence the private unit inside
     pub struct Int(());
@@ -108,6 +129,8 @@ mod private {
 #[cfg(feature = "prusti")]
 pub mod core_spec;
 
+pub mod sorting;
+
 #[cfg(feature = "prusti")]
 mod private {
     use core::{marker::PhantomData, ops::*};
@@ -193,6 +216,24 @@ mod private {
         pub fn len(self) -> Int {
             panic!()
         }
+        pub fn update(self, _index: usize, _val: T) -> Self {
+            panic!()
+        }
+        pub fn take(self, _count: usize) -> Self {
+            panic!()
+        }
+        pub fn drop(self, _count: usize) -> Self {
+            panic!()
+        }
+        pub fn subsequence(self, _from: usize, _to: usize) -> Self {
+            panic!()
+        }
+        pub fn contains(self, _val: T) -> bool {
+            panic!()
+        }
+        pub fn index_of(self, _val: T) -> Int {
+            panic!()
+        }
     }
 
     #[macro_export]
@@ -222,6 +263,41 @@ mod private {
         }
     }
 
+    extern crate alloc;
+
+    /// Types whose contents can be viewed as a mathematical sequence.
+    pub trait AsSeq<T: Copy> {
+        /// The sequence of the elements, in order.
+        #[pure]
+        fn as_seq(&self) -> Seq<T>;
+    }
+
+    impl<T: Copy> AsSeq<T> for [T] {
+        #[pure]
+        #[trusted]
+        fn as_seq(&self) -> Seq<T> {
+            panic!()
+        }
+    }
+
+    impl<T: Copy, const N: usize> AsSeq<T> for [T; N] {
+        #[pure]
+        #[trusted]
+        fn as_seq(&self) -> Seq<T> {
+            panic!()
+        }
+    }
+
+    /// The sequence of a vector is related to its slice, and thus to its
+    /// length and elements, by the specifications in `prusti-std`.
+    impl<T: Copy> AsSeq<T> for alloc::vec::Vec<T> {
+        #[pure]
+        #[trusted]
+        fn as_seq(&self) -> Seq<T> {
+            panic!()
+        }
+    }
+
     /// A map type
     #[non_exhaustive]
     #[derive(PartialEq, Eq, Copy, Clone)]
@@ -251,6 +327,15 @@ mod private {
         }
     }
 
+    impl<K: Copy, V: Copy> Map<K, V> {
+        pub fn keys(self) -> Set<K> {
+            panic!()
+        }
+        pub fn values(self) -> Set<V> {
+            panic!()
+        }
+    }
+
     #[macro_export]
     macro_rules! map {
         ($($key:expr => $val:expr),*) => {
@@ -271,6 +356,103 @@ mod private {
         }
     }
 
+    /// A set type
+    #[non_exhaustive]
+    #[derive(PartialEq, Eq, Copy, Clone)]
+    pub struct Set<T: Copy> {
+        _phantom: PhantomData<T>,
+    }
+
+    impl<T: Copy> Set<T> {
+        pub fn empty() -> Self {
+            panic!()
+        }
+        pub fn single(_: T) -> Self {
+            panic!()
+        }
+        pub fn union(self, _: Self) -> Self {
+            panic!()
+        }
+        pub fn intersection(self, _: Self) -> Self {
+            panic!()
+        }
+        pub fn difference(self, _: Self) -> Self {
+            panic!()
+        }
+        pub fn subset(self, _: Self) -> bool {
+            panic!()
+        }
+        pub fn contains(self, _: T) -> bool {
+            panic!()
+        }
+        pub fn len(self) -> Int {
+            panic!()
+        }
+    }
+
+    #[macro_export]
+    macro_rules! set {
+        ($val:expr) => {
+            $crate::Set::single($val)
+        };
+        ($($val:expr),*) => {
+            $crate::Set::empty()
+            $(
+                .union(set![$val])
+            )*
+        };
+    }
+
+    /// A multiset type
+    #[non_exhaustive]
+    #[derive(PartialEq, Eq, Copy, Clone)]
+    pub struct Multiset<T: Copy> {
+        _phantom: PhantomData<T>,
+    }
+
+    impl<T: Copy> Multiset<T> {
+        pub fn empty() -> Self {
+            panic!()
+        }
+        pub fn single(_: T) -> Self {
+            panic!()
+        }
+        pub fn union(self, _: Self) -> Self {
+            panic!()
+        }
+        pub fn intersection(self, _: Self) -> Self {
+            panic!()
+        }
+        pub fn difference(self, _: Self) -> Self {
+            panic!()
+        }
+        pub fn subset(self, _: Self) -> bool {
+            panic!()
+        }
+        pub fn contains(self, _: T) -> bool {
+            panic!()
+        }
+        pub fn count(self, _: T) -> Int {
+            panic!()
+        }
+        pub fn len(self) -> Int {
+            panic!()
+        }
+    }
+
+    #[macro_export]
+    macro_rules! multiset {
+        ($val:expr) => {
+            $crate::Multiset::single($val)
+        };
+        ($($val:expr),*) => {
+            $crate::Multiset::empty()
+            $(
+                .union(multiset![$val])
+            )*
+        };
+    }
+
     #[non_exhaustive]
     #[derive(PartialEq, Eq, Copy, Clone)]
     pub struct Ghost<T> {
@@ -0,0 +1,156 @@
+//! Predicates and lemmas for specifying sorting and searching algorithms on
+//! slices of integers.
+//!
+//! For every primitive integer type there is a module of the same name, for
+//! example `prusti_contracts::sorting::i32`, which provides:
+//!
+//! * `sorted(s)` and `sorted_range(s, from, to)`: the elements of `s` (in the
+//!   index range `from..to`) are in non-decreasing order;
+//! * `all_in_range(s, from, to, lo, hi)`: the elements of `s` in the index
+//!   range `from..to` lie between `lo` and `hi` (inclusive);
+//! * `count(s, x, to)`: the number of occurrences of `x` in `s[..to]`;
+//! * `permutation_of(a, b)`: `a` and `b` have the same elements with the same
+//!   number of occurrences;
+//! * lemmas, which are pure functions returning `true` whose postconditions
+//!   state a property of the predicates above. A lemma is used by asserting
+//!   it, for example `prusti_assert!(sorted_below(s, mid, x))`.
+
+macro_rules! sorting_specs {
+    ($($ty:ident),*) => {$(
+        #[allow(unused_variables)]
+        pub mod $ty {
+            use crate::*;
+
+            type T = core::primitive::$ty;
+
+            // The elements of `s` in the index range `from..to` are in
+            // non-decreasing order.
+            predicate! {
+                pub fn sorted_range(s: &[T], from: usize, to: usize) -> bool {
+                    forall(
+                        |i: usize, j: usize| (from <= i && i < j && j < to && to <= s.len())
+                            ==> s[i] <= s[j],
+                        triggers = [(s[i], s[j])]
+                    )
+                }
+            }
+
+            // The elements of `s` are in non-decreasing order.
+            predicate! {
+                pub fn sorted(s: &[T]) -> bool {
+                    sorted_range(s, 0, s.len())
+                }
+            }
+
+            // The elements of `s` in the index range `from..to` lie
+            // between `lo` and `hi` (inclusive).
+            predicate! {
+                pub fn all_in_range(s: &[T], from: usize, to: usize, lo: T, hi: T) -> bool {
+                    forall(
+                        |i: usize| (from <= i && i < to && to <= s.len())
+                            ==> (lo <= s[i] && s[i] <= hi),
+                        triggers = [(s[i],)]
+                    )
+                }
+            }
+
+            /// The number of occurrences of `x` in `s[..to]`.
+            #[pure]
+            #[requires(to <= s.len())]
+            #[ensures(result <= to)]
+            pub fn count(s: &[T], x: T, to: usize) -> usize {
+                if to == 0 {
+                    0
+                } else if s[to - 1] == x {
+                    count(s, x, to - 1) + 1
+                } else {
+                    count(s, x, to - 1)
+                }
+            }
+
+            // `a` and `b` have the same elements with the same number of
+            // occurrences.
+            predicate! {
+                pub fn permutation_of(a: &[T], b: &[T]) -> bool {
+                    a.len() == b.len()
+                        && forall(
+                            |x: T| count(a, x, a.len()) == count(b, x, b.len()),
+                            triggers = [(count(a, x, a.len()),), (count(b, x, b.len()),)]
+                        )
+                }
+            }
+
+            /// Extends a sorted range by one element.
+            #[pure]
+            #[requires(from <= to && to < s.len())]
+            #[requires(sorted_range(s, from, to))]
+            #[requires(from < to ==> s[to - 1] <= s[to])]
+            #[ensures(sorted_range(s, from, to + 1))]
+            pub fn sorted_range_extend(s: &[T], from: usize, to: usize) -> bool {
+                true
+            }
+
+            /// In a sorted slice, all elements up to an element smaller than
+            /// `x` are smaller than `x`.
+            #[pure]
+            #[requires(sorted(s) && m < s.len() && s[m] < x)]
+            #[ensures(forall(|i: usize| i <= m ==> s[i] < x, triggers = [(s[i],)]))]
+            pub fn sorted_below(s: &[T], m: usize, x: T) -> bool {
+                true
+            }
+
+            /// In a sorted slice, all elements from an element greater than
+            /// `x` onwards are greater than `x`.
+            #[pure]
+            #[requires(sorted(s) && m < s.len() && x < s[m])]
+            #[ensures(forall(|i: usize| (m <= i && i < s.len()) ==> x < s[i], triggers = [(s[i],)]))]
+            pub fn sorted_above(s: &[T], m: usize, x: T) -> bool {
+                true
+            }
+
+            /// Every slice is a permutation of itself.
+            #[pure]
+            #[ensures(permutation_of(s, s))]
+            pub fn permutation_refl(s: &[T]) -> bool {
+                true
+            }
+
+            /// Being a permutation is symmetric.
+            #[pure]
+            #[requires(permutation_of(a, b))]
+            #[ensures(permutation_of(b, a))]
+            pub fn permutation_sym(a: &[T], b: &[T]) -> bool {
+                true
+            }
+
+            /// Being a permutation is transitive.
+            #[pure]
+            #[requires(permutation_of(a, b) && permutation_of(b, c))]
+            #[ensures(permutation_of(a, c))]
+            pub fn permutation_trans(a: &[T], b: &[T], c: &[T]) -> bool {
+                true
+            }
+
+            /// Swapping two elements yields a permutation.
+            ///
+            /// This lemma is a trusted axiom: proving it needs an induction
+            /// over `count`, i.e. a recursive `#[lemma]`, which is supported
+            /// only with `unsafe_core_proof`. See the "Trusted axioms" section
+            /// of the user guide on predicates.
+            // The other lemmas are verified by the `sorting/lemmas.rs` test of
+            // `prusti-tests`, since this crate is compiled without verification.
+            #[pure]
+            #[trusted]
+            #[requires(a.len() == b.len() && i < a.len() && j < a.len())]
+            #[requires(b[i] == a[j] && b[j] == a[i])]
+            #[requires(forall(|k: usize| (k < a.len() && k != i && k != j) ==> b[k] == a[k],
+                triggers = [(b[k],)]))]
+            #[ensures(permutation_of(a, b))]
+            pub fn swap_permutation(a: &[T], b: &[T], i: usize, j: usize) -> bool {
+                true
+            }
+        }
+    )*};
+}
+
+sorting_specs!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
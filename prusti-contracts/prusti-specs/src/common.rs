@@ -436,19 +436,7 @@ pub(crate) fn add_phantom_data_for_generic_params(item_struct: &mut syn::ItemStr
     };
 
     for generic_param in item_struct.generics.params.iter() {
-        let ty: Option<syn::Type> = match generic_param {
-            syn::GenericParam::Type(type_param) => {
-                let ty_ident = &type_param.ident;
-                let ty: syn::Type = parse_quote!(::core::marker::PhantomData<#ty_ident>);
-                Some(ty)
-            }
-            syn::GenericParam::Lifetime(lt_def) => {
-                let lt = &lt_def.lifetime;
-                let ty: syn::Type = parse_quote!(&#lt ::core::marker::PhantomData<()>);
-                Some(ty)
-            }
-            _ => None,
-        };
+        let ty = phantom_data_for_generic_param(generic_param);
 
         if ty.is_none() {
             continue;
@@ -482,6 +470,54 @@ pub(crate) fn add_phantom_data_for_generic_params(item_struct: &mut syn::ItemStr
     }
 }
 
+/// Add a variant holding `PhantomData` markers for each type parameter to
+/// silence errors about unused type parameters. Does nothing for enums
+/// without type parameters.
+/// Given
+/// ```text
+/// enum Foo<A,B> {
+///     // ... variants ...
+/// }
+/// ```
+/// Result
+/// ```text
+/// enum Foo<A,B> {
+///     // ... variants ...
+///     PrustiInjectedPhantomVariant(
+///         ::core::marker::PhantomData<A>,
+///         ::core::marker::PhantomData<B>
+///     )
+/// }
+/// ```
+pub(crate) fn add_phantom_variant_for_generic_params(item_enum: &mut syn::ItemEnum) {
+    let field_types: Vec<syn::Type> = item_enum
+        .generics
+        .params
+        .iter()
+        .filter_map(phantom_data_for_generic_param)
+        .collect();
+    if field_types.is_empty() {
+        return;
+    }
+    item_enum
+        .variants
+        .push(parse_quote!(PrustiInjectedPhantomVariant(#(#field_types),*)));
+}
+
+fn phantom_data_for_generic_param(generic_param: &syn::GenericParam) -> Option<syn::Type> {
+    match generic_param {
+        syn::GenericParam::Type(type_param) => {
+            let ty_ident = &type_param.ident;
+            Some(parse_quote!(::core::marker::PhantomData<#ty_ident>))
+        }
+        syn::GenericParam::Lifetime(lt_def) => {
+            let lt = &lt_def.lifetime;
+            Some(parse_quote!(&#lt ::core::marker::PhantomData<()>))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,7 +31,7 @@ use std::convert::TryInto;
 use syn::{spanned::Spanned, visit::Visit};
 
 use crate::{
-    common::{merge_generics, RewritableReceiver, SelfTypeRewriter},
+    common::{merge_generics, HasAttributes, HasSignature, RewritableReceiver, SelfTypeRewriter},
     predicate::{is_predicate_macro, ParsedPredicate},
     specifications::preparser::{parse_ghost_constraint, parse_prusti, NestedSpec},
 };
@@ -78,7 +78,8 @@ fn extract_prusti_attributes(
                     SpecAttributeKind::Pure
                     | SpecAttributeKind::Terminates
                     | SpecAttributeKind::Trusted
-                    | SpecAttributeKind::Predicate => {
+                    | SpecAttributeKind::Predicate
+                    | SpecAttributeKind::Lemma => {
                         assert!(attr.tokens.is_empty(), "Unexpected shape of an attribute.");
                         attr.tokens
                     }
@@ -127,6 +128,20 @@ pub fn rewrite_prusti_attributes(
         .to_compile_error();
     }
 
+    if prusti_attributes
+        .iter()
+        .any(|(ak, _)| ak == &SpecAttributeKind::Lemma)
+        && prusti_attributes
+            .iter()
+            .any(|(ak, _)| ak == &SpecAttributeKind::Pure)
+    {
+        return syn::Error::new(
+            item.span(),
+            "`#[lemma]` is incompatible with `#[pure]`",
+        )
+        .to_compile_error();
+    }
+
     let (generated_spec_items, generated_attributes) =
         handle_result!(generate_spec_and_assertions(prusti_attributes, &item));
 
@@ -148,6 +163,19 @@ fn generate_spec_and_assertions(
     let mut generated_items = vec![];
     let mut generated_attributes = vec![];
 
+    // `#[terminates]` is not extracted by `extract_prusti_attributes`, so it
+    // may also still be one of the attributes of the item.
+    let has_termination_measure = prusti_attributes
+        .iter()
+        .any(|(ak, _)| ak == &SpecAttributeKind::Terminates)
+        || item.attrs().iter().any(|attr| {
+            attr.path
+                .segments
+                .last()
+                .map(|segment| segment.ident == "terminates")
+                .unwrap_or(false)
+        });
+
     for (attr_kind, attr_tokens) in prusti_attributes.drain(..) {
         let rewriting_result = match attr_kind {
             SpecAttributeKind::Requires => generate_for_requires(attr_tokens, item),
@@ -165,6 +193,9 @@ fn generate_spec_and_assertions(
             SpecAttributeKind::GhostConstraint => ghost_constraints::generate(attr_tokens, item),
             SpecAttributeKind::Model => unreachable!(),
             SpecAttributeKind::PrintCounterexample => unreachable!(),
+            SpecAttributeKind::Lemma => {
+                generate_for_lemma(attr_tokens, item, has_termination_measure)
+            }
         };
         let (new_items, new_attributes) = rewriting_result?;
         generated_items.extend(new_items);
@@ -293,6 +324,39 @@ fn do_generate_for_pure(item: &untyped::AnyFnItem) -> GeneratedResult {
     ))
 }
 
+/// Generate spec items and attributes to typecheck and later retrieve "lemma" annotations.
+///
+/// Lemmas have to terminate, so a lemma without a `#[terminates]` attribute
+/// gets the default termination measure.
+fn generate_for_lemma(
+    attr: TokenStream,
+    item: &untyped::AnyFnItem,
+    has_termination_measure: bool,
+) -> GeneratedResult {
+    if !attr.is_empty() {
+        return Err(syn::Error::new(
+            attr.span(),
+            "the `#[lemma]` attribute does not take parameters",
+        ));
+    }
+    if let syn::ReturnType::Type(_, ty) = &item.sig().output {
+        return Err(syn::Error::new(
+            ty.span(),
+            "lemmas cannot return a value; state their result in a postcondition",
+        ));
+    }
+
+    let (generated_items, mut generated_attributes) = if has_termination_measure {
+        (vec![], vec![])
+    } else {
+        generate_for_terminates(TokenStream::new(), item)?
+    };
+    generated_attributes.push(parse_quote_spanned! {item.span()=>
+        #[prusti::lemma]
+    });
+    Ok((generated_items, generated_attributes))
+}
+
 /// Generate spec items and attributes to typecheck and later retrieve "trusted" annotations.
 fn generate_for_trusted(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
     if !attr.is_empty() {
@@ -822,12 +886,13 @@ fn extract_prusti_attributes_for_types(
                     SpecAttributeKind::Invariant => unreachable!("invariant on type"),
                     SpecAttributeKind::Predicate => unreachable!("predicate on type"),
                     SpecAttributeKind::Terminates => unreachable!("terminates on type"),
-                    SpecAttributeKind::Trusted |
-                    SpecAttributeKind::Model => {
+                    SpecAttributeKind::Lemma => unreachable!("lemma on type"),
+                    SpecAttributeKind::Trusted => {
                         assert!(attr.tokens.is_empty(), "Unexpected shape of an attribute.");
                         attr.tokens
                     }
-                    SpecAttributeKind::PrintCounterexample => {
+                    SpecAttributeKind::Model if attr.tokens.is_empty() => attr.tokens,
+                    SpecAttributeKind::Model | SpecAttributeKind::PrintCounterexample => {
                         // We need to drop the surrounding parenthesis to make the
                         // tokens identical to the ones passed by the native procedural
                         // macro call.
@@ -867,6 +932,7 @@ fn generate_spec_and_assertions_for_types(
             SpecAttributeKind::Invariant => unreachable!(),
             SpecAttributeKind::GhostConstraint => unreachable!(),
             SpecAttributeKind::Terminates => unreachable!(),
+            SpecAttributeKind::Lemma => unreachable!(),
             SpecAttributeKind::Trusted => generate_for_trusted_for_types(attr_tokens, item),
             SpecAttributeKind::Model => generate_for_model(attr_tokens, item),
             SpecAttributeKind::PrintCounterexample => generate_for_print_counterexample(attr_tokens, item),
@@ -883,7 +949,7 @@ fn generate_spec_and_assertions_for_types(
 fn generate_for_model(attr: TokenStream, item: &mut syn::DeriveInput) -> GeneratedResult {
     match syn::Item::from(item.clone()) {
         syn::Item::Struct(item_struct) => {
-            match type_model::rewrite(item_struct){
+            match type_model::rewrite_struct(attr, item_struct){
                 Ok(result) => {
                     match result.first() {
                         Some(syn::Item::Struct(new_item)) => {
@@ -896,9 +962,23 @@ fn generate_for_model(attr: TokenStream, item: &mut syn::DeriveInput) -> Generat
                 Err(err) => Err(err),
             }
         }
+        syn::Item::Enum(item_enum) => {
+            match type_model::rewrite_enum(attr, item_enum){
+                Ok(result) => {
+                    match result.first() {
+                        Some(syn::Item::Enum(new_item)) => {
+                            *item = syn::DeriveInput::from(new_item.clone()); //the internal model replaces the original enum
+                            Ok((result[1..].to_vec(), vec![]))
+                        }
+                        _ => unreachable!(),
+                    }
+                },
+                Err(err) => Err(err),
+            }
+        }
         _ => Err(syn::Error::new(
             attr.span(),
-            "Only structs can be attributed with a type model",
+            "Only structs and enums can be attributed with a type model",
         )),
     }
 }
@@ -941,7 +1021,7 @@ pub fn type_model(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     } else {
         syn::Error::new(
             attr.span(),
-            "Only structs can be attributed with a type model",
+            "Only structs and enums can be attributed with a type model",
         )
         .to_compile_error()
     }
@@ -958,6 +1038,21 @@ pub fn print_counterexample(attr: TokenStream, tokens: TokenStream) -> TokenStre
         .to_compile_error()
     }
 }
+/// Applies a lemma by calling it in a ghost block.
+pub fn prusti_apply(tokens: TokenStream) -> TokenStream {
+    let call: syn::Expr = handle_result!(syn::parse2(tokens));
+    if !matches!(call, syn::Expr::Call(_) | syn::Expr::MethodCall(_)) {
+        return syn::Error::new(
+            call.span(),
+            "`prusti_apply!` expects a lemma call, such as `prusti_apply!(lemma(args))`",
+        )
+        .to_compile_error();
+    }
+    ghost(quote_spanned! {call.span()=>
+        #call;
+    })
+}
+
 pub fn ghost(tokens: TokenStream) -> TokenStream {
     let mut rewriter = rewriter::AstRewriter::new();
     let callsite_span = Span::call_site();
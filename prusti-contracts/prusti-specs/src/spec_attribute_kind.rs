@@ -17,6 +17,7 @@ pub enum SpecAttributeKind {
     GhostConstraint = 9,
     Terminates = 10,
     PrintCounterexample = 11,
+    Lemma = 12,
 }
 
 impl TryFrom<String> for SpecAttributeKind {
@@ -35,6 +36,7 @@ impl TryFrom<String> for SpecAttributeKind {
             "ghost_constraint" => Ok(SpecAttributeKind::GhostConstraint),
             "model" => Ok(SpecAttributeKind::Model),
             "print_counterexample" => Ok(SpecAttributeKind::PrintCounterexample),
+            "lemma" => Ok(SpecAttributeKind::Lemma),
             _ => Err(name),
         }
     }
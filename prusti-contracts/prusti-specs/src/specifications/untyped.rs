@@ -1,7 +1,7 @@
 use proc_macro2::{TokenStream};
 use quote::ToTokens;
 use syn::Signature;
-use crate::common::HasSignature;
+use crate::common::{HasAttributes, HasSignature};
 
 pub use super::common::{SpecType, SpecificationId};
 pub use super::preparser::Arg;
@@ -85,6 +85,16 @@ impl HasSignature for AnyFnItem {
     }
 }
 
+impl HasAttributes for AnyFnItem {
+    fn attrs(&self) -> &Vec<syn::Attribute> {
+        match self {
+            Self::Fn(item) => &item.attrs,
+            Self::ImplMethod(item) => &item.attrs,
+            Self::TraitMethod(item) => &item.attrs,
+        }
+    }
+}
+
 impl ToTokens for AnyFnItem {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
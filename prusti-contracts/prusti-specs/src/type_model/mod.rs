@@ -2,13 +2,17 @@
 //!
 //! Usage documentation can be found in the corresponding macro definition.
 //!
-//! Given a `#[model]` attributed type `T`, this logic creates the following three items:
-//! * A struct `M` which holds the model's fields
+//! Given a `#[model]` attributed struct or enum `T`, this logic creates the following items:
+//! * A struct (or enum) `M` which holds the model's fields (or variants)
 //! * A trait which provides a `model` method to be used in specifications
 //! * An implementation of the aforementioned trait for `T`.
-//!   The implementation is `unimplemented!()`, `#[pure]` and `#[trusted]`
+//!   The implementation is `unimplemented!()`, `#[pure]` and `#[trusted]`, unless an
+//!   abstraction function is given with `#[model(abstraction = f)]`. Then the implementation
+//!   is `#[pure]` and calls `f`, so that the model is verified against the fields of `T`
+//! * For enums and models with an abstraction function, a type alias `TModel` for `M`,
+//!   to name the model in specifications and in the abstraction function
 //!
-//! The model struct `M` must be copyable.
+//! The model type `M` must be copyable.
 //!
 //! # Note
 //! This macro always generates a trait with a `model` method on the fly for every modelled type.
@@ -16,7 +20,7 @@
 
 use super::parse_quote_spanned;
 use crate::{
-    common::add_phantom_data_for_generic_params,
+    common::{add_phantom_data_for_generic_params, add_phantom_variant_for_generic_params, HasGenerics},
     user_provided_type_params::{
         UserAnnotatedTypeParam, UserAnnotatedTypeParamParser, UserAnnotatedTypeParamParserError,
     }, SPECS_VERSION,
@@ -27,11 +31,26 @@ use syn::{parse_quote, punctuated::Punctuated, spanned::Spanned};
 use uuid::Uuid;
 
 /// See module level documentation
-pub fn rewrite(item_struct: syn::ItemStruct) -> syn::Result<Vec<syn::Item>> {
-    let res = rewrite_internal(item_struct);
+pub fn rewrite_struct(
+    attr: TokenStream,
+    item_struct: syn::ItemStruct,
+) -> syn::Result<Vec<syn::Item>> {
+    let args = syn::parse2(attr)?;
+    into_items(rewrite_model(ModelItem::Struct(item_struct), args))
+}
+
+/// See module level documentation
+pub fn rewrite_enum(attr: TokenStream, item_enum: syn::ItemEnum) -> syn::Result<Vec<syn::Item>> {
+    let args = syn::parse2(attr)?;
+    into_items(rewrite_model(ModelItem::Enum(item_enum), args))
+}
+
+fn into_items(res: TypeModelGenerationResult<TypeModel>) -> syn::Result<Vec<syn::Item>> {
     match res {
         Ok(result) => {
-            Ok(vec![syn::Item::Struct(result.model_struct), syn::Item::Trait(result.to_model_trait), syn::Item::Impl(result.model_impl)])
+            let mut items = vec![result.model_type.into(), syn::Item::Trait(result.to_model_trait), syn::Item::Impl(result.model_impl)];
+            items.extend(result.model_type_alias.map(syn::Item::Type));
+            Ok(items)
         },
         Err(err) => Err(err.into()),
     }
@@ -39,49 +58,147 @@ pub fn rewrite(item_struct: syn::ItemStruct) -> syn::Result<Vec<syn::Item>> {
 
 type TypeModelGenerationResult<R> = Result<R, TypeModelGenerationError>;
 
-fn rewrite_internal(item_struct: syn::ItemStruct) -> TypeModelGenerationResult<TypeModel> {
-    let idents = GeneratedIdents::generate(&item_struct);
+fn rewrite_model(model_item: ModelItem, args: ModelArgs) -> TypeModelGenerationResult<TypeModel> {
+    let idents = GeneratedIdents::generate(&model_item);
 
-    let model_struct = ModelStruct::create(&item_struct, &idents)?;
-    let to_model_trait = ToModelTrait::create(&item_struct, &model_struct, &idents);
-    let model_impl = create_model_impl(&item_struct, &model_struct, &to_model_trait)?;
+    let model_type = ModelType::create(&model_item, &idents)?;
+    let to_model_trait = ToModelTrait::create(&model_item, &model_type, &idents);
+    let model_impl = create_model_impl(&model_item, &model_type, &to_model_trait, &args)?;
+    let model_type_alias = model_type.create_alias(&idents, &args);
 
     Ok(TypeModel {
-        model_struct: model_struct.item,
+        model_type: model_type.item,
         to_model_trait: to_model_trait.item,
         model_impl,
+        model_type_alias,
     })
 }
 
-struct ModelStruct {
-    item: syn::ItemStruct,
+/// The arguments of the `#[model]` attribute, e.g. `#[model(abstraction = foo_model)]`
+#[derive(Default)]
+struct ModelArgs {
+    /// A `#[pure]` function computing the model from the modelled type.
+    /// Without it, the model is trusted.
+    abstraction: Option<syn::Path>,
+}
 
-    /// The path to the generated struct, e.g. to be used as a return type
+impl syn::parse::Parse for ModelArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = ModelArgs::default();
+        if input.is_empty() {
+            return Ok(args);
+        }
+        let name: Ident = input.parse()?;
+        if name != "abstraction" {
+            return Err(syn::Error::new(
+                name.span(),
+                "Unknown argument of the type model, expected `abstraction = <pure function>`",
+            ));
+        }
+        let _eq: syn::Token![=] = input.parse()?;
+        args.abstraction = Some(input.parse()?);
+        if !input.is_empty() {
+            return Err(input.error("Unexpected tokens after the abstraction function"));
+        }
+        Ok(args)
+    }
+}
+
+/// A struct or an enum: either the `#[model]` attributed item, which is named after the
+/// modelled type, or the generated model type.
+enum ModelItem {
+    Struct(syn::ItemStruct),
+    Enum(syn::ItemEnum),
+}
+
+impl ModelItem {
+    fn ident(&self) -> &syn::Ident {
+        match self {
+            ModelItem::Struct(item_struct) => &item_struct.ident,
+            ModelItem::Enum(item_enum) => &item_enum.ident,
+        }
+    }
+}
+
+impl HasGenerics for ModelItem {
+    fn generics(&self) -> &syn::Generics {
+        match self {
+            ModelItem::Struct(item_struct) => &item_struct.generics,
+            ModelItem::Enum(item_enum) => &item_enum.generics,
+        }
+    }
+    fn generics_mut(&mut self) -> &mut syn::Generics {
+        match self {
+            ModelItem::Struct(item_struct) => &mut item_struct.generics,
+            ModelItem::Enum(item_enum) => &mut item_enum.generics,
+        }
+    }
+}
+
+impl ToTokens for ModelItem {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ModelItem::Struct(item_struct) => item_struct.to_tokens(tokens),
+            ModelItem::Enum(item_enum) => item_enum.to_tokens(tokens),
+        }
+    }
+}
+
+impl From<ModelItem> for syn::Item {
+    fn from(model_item: ModelItem) -> Self {
+        match model_item {
+            ModelItem::Struct(item_struct) => syn::Item::Struct(item_struct),
+            ModelItem::Enum(item_enum) => syn::Item::Enum(item_enum),
+        }
+    }
+}
+
+struct ModelType {
+    item: ModelItem,
+
+    /// The path to the generated type, e.g. to be used as a return type
     path: syn::Path,
 }
 
-impl ModelStruct {
+impl ModelType {
     fn create(
-        item_struct: &syn::ItemStruct,
+        model_item: &ModelItem,
         idents: &GeneratedIdents,
     ) -> TypeModelGenerationResult<Self> {
-        if item_struct.fields.is_empty() {
-            return Err(TypeModelGenerationError::MissingStructFields(
-                item_struct.span(),
-            ));
-        }
-
-        let model_struct_ident = &idents.model_struct_ident;
-        let mut model_struct: syn::ItemStruct = parse_quote_spanned! {item_struct.span()=>
-            #[derive(Copy, Clone)]
-            #[allow(non_camel_case_types)]
-            struct #model_struct_ident {}
+        let model_type_ident = &idents.model_type_ident;
+        let mut item = match model_item {
+            ModelItem::Struct(item_struct) => {
+                if item_struct.fields.is_empty() {
+                    return Err(TypeModelGenerationError::MissingStructFields(
+                        item_struct.span(),
+                    ));
+                }
+                let model_struct: syn::ItemStruct = parse_quote_spanned! {item_struct.span()=>
+                    #[derive(Copy, Clone)]
+                    #[allow(non_camel_case_types)]
+                    struct #model_type_ident {}
+                };
+                ModelItem::Struct(model_struct)
+            }
+            ModelItem::Enum(item_enum) => {
+                if item_enum.variants.is_empty() {
+                    return Err(TypeModelGenerationError::MissingEnumVariants(
+                        item_enum.span(),
+                    ));
+                }
+                let model_enum: syn::ItemEnum = parse_quote_spanned! {item_enum.span()=>
+                    #[derive(Copy, Clone)]
+                    #[allow(non_camel_case_types)]
+                    enum #model_type_ident {}
+                };
+                ModelItem::Enum(model_enum)
+            }
         };
 
-        let params = item_struct
+        let params = model_item
             .parse_user_annotated_type_params()
             .map_err(TypeModelGenerationError::NonParsableTypeParam)?;
-        model_struct.generics.params.extend(
+        item.generics_mut().params.extend(
             params
                 .iter()
                 .filter_map(UserAnnotatedTypeParam::as_generic_type_param)
@@ -89,11 +206,20 @@ impl ModelStruct {
                 .map(syn::GenericParam::Type),
         );
 
-        model_struct.fields = item_struct.fields.clone();
-        add_phantom_data_for_generic_params(&mut model_struct);
+        match (&mut item, model_item) {
+            (ModelItem::Struct(model_struct), ModelItem::Struct(item_struct)) => {
+                model_struct.fields = item_struct.fields.clone();
+                add_phantom_data_for_generic_params(model_struct);
+            }
+            (ModelItem::Enum(model_enum), ModelItem::Enum(item_enum)) => {
+                model_enum.variants = item_enum.variants.clone();
+                add_phantom_variant_for_generic_params(model_enum);
+            }
+            _ => unreachable!(),
+        }
 
-        let generic_idents = model_struct
-            .generics
+        let generic_idents = item
+            .generics()
             .params
             .iter()
             .filter_map(|generic_param| match generic_param {
@@ -101,14 +227,40 @@ impl ModelStruct {
                 _ => None,
             });
         let model_path: syn::Path = parse_quote!(
-            #model_struct_ident < #(#generic_idents),* >
+            #model_type_ident < #(#generic_idents),* >
         );
 
         Ok(Self {
-            item: model_struct,
+            item,
             path: model_path,
         })
     }
+
+    /// Creates a type alias with a predictable name for a model enum, so that its
+    /// variants can be named in specifications, e.g. `FooModel::Variant`, and for a
+    /// model with an abstraction function, which needs to construct the model
+    fn create_alias(&self, idents: &GeneratedIdents, args: &ModelArgs) -> Option<syn::ItemType> {
+        if matches!(self.item, ModelItem::Struct(_)) && args.abstraction.is_none() {
+            return None;
+        }
+        let alias_ident = &idents.model_type_alias_ident;
+        // Bounds are not enforced in type aliases, thus only the parameters are repeated
+        let generic_idents: Vec<&Ident> = self
+            .item
+            .generics()
+            .params
+            .iter()
+            .filter_map(|generic_param| match generic_param {
+                syn::GenericParam::Type(type_param) => Some(&type_param.ident),
+                _ => None,
+            })
+            .collect();
+        let model_path = &self.path;
+        Some(parse_quote_spanned! {self.item.span()=>
+            #[allow(non_camel_case_types)]
+            type #alias_ident < #(#generic_idents),* > = #model_path;
+        })
+    }
 }
 
 struct ToModelTrait {
@@ -120,12 +272,12 @@ struct ToModelTrait {
 
 impl ToModelTrait {
     fn create(
-        item_struct: &syn::ItemStruct,
-        model_struct: &ModelStruct,
+        model_item: &ModelItem,
+        model_type: &ModelType,
         idents: &GeneratedIdents,
     ) -> Self {
         let generic_params: Vec<syn::GenericParam> =
-            model_struct.item.generics.params.iter().cloned().collect();
+            model_type.item.generics().params.iter().cloned().collect();
         let generic_params_idents: Vec<Ident> = generic_params
             .iter()
             .filter_map(|generic_param| match generic_param {
@@ -134,10 +286,10 @@ impl ToModelTrait {
             })
             .collect();
 
-        let model_path = &model_struct.path;
+        let model_path = &model_type.path;
         
         let to_model_trait_ident = &idents.to_model_trait_ident;
-        let item = parse_quote_spanned! {item_struct.span()=>
+        let item = parse_quote_spanned! {model_item.span()=>
             #[allow(non_camel_case_types)]
             trait #to_model_trait_ident<#(#generic_params),*> {
                 #[pure]
@@ -156,14 +308,15 @@ impl ToModelTrait {
 }
 
 fn create_model_impl(
-    item_struct: &syn::ItemStruct,
-    model_struct: &ModelStruct,
+    model_item: &ModelItem,
+    model_type: &ModelType,
     to_model_trait: &ToModelTrait,
+    args: &ModelArgs,
 ) -> TypeModelGenerationResult<syn::ItemImpl> {
-    let ident = &item_struct.ident;
+    let ident = model_item.ident();
 
     let mut rewritten_generics: Vec<syn::GenericParam> = Vec::new();
-    for param in &item_struct.generics.params {
+    for param in &model_item.generics().params {
         match param {
             syn::GenericParam::Lifetime(_) => rewritten_generics.push(parse_quote!('_)),
             syn::GenericParam::Type(type_param) => {
@@ -181,24 +334,48 @@ fn create_model_impl(
     }
 
     let generic_params: Vec<syn::GenericParam> =
-        model_struct.item.generics.params.iter().cloned().collect();
+        model_type.item.generics().params.iter().cloned().collect();
 
     let impl_path: syn::Path = parse_quote!(
         #ident < #(#rewritten_generics),* >
     );
 
     let to_model_trait_path = &to_model_trait.path;
-    let model_struct_path = &model_struct.path;
+    let model_type_path = &model_type.path;
     let to_model_trait_str = &to_model_trait.item.ident.to_string();
 
-    Ok(parse_quote_spanned! {item_struct.span()=>
+    if let Some(abstraction) = &args.abstraction {
+        // The generated model of a `#[generic]` type parameter contains phantom data, which
+        // the abstraction function cannot construct
+        if generic_params
+            .iter()
+            .any(|param| matches!(param, syn::GenericParam::Type(_)))
+        {
+            return Err(TypeModelGenerationError::AbstractionOfGenericModel(
+                abstraction.span(),
+            ));
+        }
+        return Ok(parse_quote_spanned! {model_item.span()=>
+            #[prusti::type_models_to_model_impl]
+            #[prusti::specs_version = #SPECS_VERSION]
+            impl #to_model_trait_path for #impl_path {
+                #[pure]
+                #[prusti::type_models_to_model_fn = #to_model_trait_str]
+                fn model(&self) -> #model_type_path {
+                    #abstraction(self)
+                }
+            }
+        });
+    }
+
+    Ok(parse_quote_spanned! {model_item.span()=>
         #[prusti::type_models_to_model_impl]
         #[prusti::specs_version = #SPECS_VERSION]
         impl<#(#generic_params),*> #to_model_trait_path for #impl_path {
             #[trusted]
             #[pure]
             #[prusti::type_models_to_model_fn = #to_model_trait_str]
-            fn model(&self) -> #model_struct_path {
+            fn model(&self) -> #model_type_path {
                 unimplemented!("Models can only be used in specifications")
             }
         }
@@ -207,15 +384,18 @@ fn create_model_impl(
 
 /// [syn::Ident]s which are used for the generated items
 struct GeneratedIdents {
-    model_struct_ident: syn::Ident,
+    model_type_ident: syn::Ident,
     to_model_trait_ident: syn::Ident,
+    /// The name of the type alias of model enums and of models with an abstraction
+    /// function, which users can name
+    model_type_alias_ident: syn::Ident,
 }
 
 impl GeneratedIdents {
-    fn generate(item_struct: &syn::ItemStruct) -> Self {
-        let mut name = item_struct.ident.to_string();
+    fn generate(model_item: &ModelItem) -> Self {
+        let mut name = model_item.ident().to_string();
 
-        for param in item_struct.generics.params.iter() {
+        for param in model_item.generics().params.iter() {
             if let syn::GenericParam::Type(ty_param) = param {
                 name.push_str(ty_param.ident.to_string().as_str());
             }
@@ -224,13 +404,17 @@ impl GeneratedIdents {
         let uuid = Uuid::new_v4().simple();
 
         GeneratedIdents {
-            model_struct_ident: Ident::new(
+            model_type_ident: Ident::new(
                 format!("Prusti{}Model_{}", name, uuid).as_str(),
-                item_struct.ident.span(),
+                model_item.ident().span(),
             ),
             to_model_trait_ident: Ident::new(
                 format!("Prusti{}ToModel_{}", name, uuid).as_str(),
-                item_struct.ident.span(),
+                model_item.ident().span(),
+            ),
+            model_type_alias_ident: Ident::new(
+                format!("{}Model", name).as_str(),
+                model_item.ident().span(),
             ),
         }
     }
@@ -243,9 +427,15 @@ enum TypeModelGenerationError {
     /// Thrown when the model contains no fields
     MissingStructFields(proc_macro2::Span),
 
+    /// Thrown when the model is an enum without variants
+    MissingEnumVariants(proc_macro2::Span),
+
     /// Thrown when using a const type param in the model
     ConstParamDisallowed(proc_macro2::Span),
 
+    /// Thrown when using an abstraction function for a model with `#[generic]` type params
+    AbstractionOfGenericModel(proc_macro2::Span),
+
     /// Thrown when user annotated generics could not be parsed
     NonParsableTypeParam(UserAnnotatedTypeParamParserError),
 }
@@ -256,9 +446,16 @@ impl std::convert::From<TypeModelGenerationError> for syn::Error {
             TypeModelGenerationError::MissingStructFields(span) => {
                 syn::Error::new(span, "Type model must have at least one field")
             }
+            TypeModelGenerationError::MissingEnumVariants(span) => {
+                syn::Error::new(span, "Type model must have at least one variant")
+            }
             TypeModelGenerationError::ConstParamDisallowed(span) => {
                 syn::Error::new(span, "Const generics are disallowed for models")
             }
+            TypeModelGenerationError::AbstractionOfGenericModel(span) => syn::Error::new(
+                span,
+                "Abstraction functions are not supported for models with #[generic] type parameters",
+            ),
             TypeModelGenerationError::NonParsableTypeParam(parse_err) => parse_err.into(),
         }
     }
@@ -266,22 +463,27 @@ impl std::convert::From<TypeModelGenerationError> for syn::Error {
 
 /// Type to represent generated code during expansion of the `#[model]` macro
 struct TypeModel {
-    /// The struct which represents the model
-    model_struct: syn::ItemStruct,
+    /// The struct or enum which represents the model
+    model_type: ModelItem,
 
     /// A trait which will be implemented on the modelled type
-    /// to return the [TypeModel::model_struct]
+    /// to return the [TypeModel::model_type]
     to_model_trait: syn::ItemTrait,
 
     /// The implementation of the [TypeModel::model_trait] on the modelled type.
     model_impl: syn::ItemImpl,
+
+    /// A type alias to name the [TypeModel::model_type] if it is an enum or computed by an
+    /// abstraction function
+    model_type_alias: Option<syn::ItemType>,
 }
 
 impl ToTokens for TypeModel {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         self.to_model_trait.to_tokens(tokens);
-        self.model_struct.to_tokens(tokens);
+        self.model_type.to_tokens(tokens);
         self.model_impl.to_tokens(tokens);
+        self.model_type_alias.to_tokens(tokens);
     }
 }
 
@@ -289,6 +491,14 @@ impl ToTokens for TypeModel {
 mod tests {
     use super::*;
 
+    fn rewrite_internal(item_struct: syn::ItemStruct) -> TypeModelGenerationResult<TypeModel> {
+        rewrite_model(ModelItem::Struct(item_struct), ModelArgs::default())
+    }
+
+    fn rewrite_enum_internal(item_enum: syn::ItemEnum) -> TypeModelGenerationResult<TypeModel> {
+        rewrite_model(ModelItem::Enum(item_enum), ModelArgs::default())
+    }
+
     #[test]
     fn rewritten_model_to_tokens() {
         let to_model_trait: syn::ItemTrait = parse_quote!(
@@ -301,8 +511,9 @@ mod tests {
 
         let rewritten_model = TypeModel {
             to_model_trait: to_model_trait.clone(),
-            model_struct: model_struct.clone(),
+            model_type: ModelItem::Struct(model_struct.clone()),
             model_impl: trait_impl.clone(),
+            model_type_alias: None,
         };
         let actual_ts = rewritten_model.into_token_stream();
 
@@ -346,7 +557,7 @@ mod tests {
                 fld2: i32,
             }
         );
-        assert_eq_tokenizable(model.model_struct, expected);
+        assert_eq_tokenizable(model.model_type, expected);
     }
 
     #[test]
@@ -364,7 +575,97 @@ mod tests {
             #[allow(non_camel_case_types)]
             struct #model_ident(i32, u32, usize);
         );
-        assert_eq_tokenizable(model.model_struct, expected);
+        assert_eq_tokenizable(model.model_type, expected);
+    }
+
+    #[test]
+    fn err_when_no_variants() {
+        let input = parse_quote!(
+            enum Foo {}
+        );
+        let result = rewrite_enum_internal(input);
+        assert!(matches!(
+            result,
+            Err(TypeModelGenerationError::MissingEnumVariants(_))
+        ));
+    }
+
+    #[test]
+    fn ok_generates_model_enum_def() {
+        let input = parse_quote!(
+            enum Foo {
+                Idle,
+                Running { steps: usize },
+                Failed(i32),
+            }
+        );
+
+        let model = expect_ok(rewrite_enum_internal(input));
+
+        let model_ident = check_model_ident(&model, "PrustiFooModel");
+        let trait_ident = check_trait_ident(&model, "PrustiFooToModel");
+        let trait_ident_str = trait_ident.to_string();
+
+        let expected_enum: syn::ItemEnum = parse_quote!(
+            #[derive(Copy, Clone)]
+            #[allow(non_camel_case_types)]
+            enum #model_ident {
+                Idle,
+                Running { steps: usize },
+                Failed(i32),
+            }
+        );
+        let expected_impl: syn::ItemImpl = parse_quote!(
+            #[prusti::type_models_to_model_impl]
+            #[prusti::specs_version = #SPECS_VERSION]
+            impl #trait_ident<> for Foo <> {
+                #[trusted]
+                #[pure]
+                #[prusti::type_models_to_model_fn = #trait_ident_str]
+                fn model(&self) -> #model_ident<> {
+                    unimplemented!("Models can only be used in specifications")
+                }
+            }
+        );
+
+        let expected_alias: syn::ItemType = parse_quote!(
+            #[allow(non_camel_case_types)]
+            type FooModel<> = #model_ident<>;
+        );
+
+        assert_eq_tokenizable(model.model_type, expected_enum);
+        assert_eq_tokenizable(model.model_impl, expected_impl);
+        assert_eq_tokenizable(model.model_type_alias, Some(expected_alias));
+    }
+
+    #[test]
+    fn ok_generates_model_enum_def_with_generic_params() {
+        let input = parse_quote!(
+            enum Foo<#[generic] T, #[concrete] i32> {
+                Empty,
+                Full(T),
+            }
+        );
+
+        let model = expect_ok(rewrite_enum_internal(input));
+
+        let model_ident = check_model_ident(&model, "PrustiFooTi32Model");
+
+        let expected: syn::ItemEnum = parse_quote!(
+            #[derive(Copy, Clone)]
+            #[allow(non_camel_case_types)]
+            enum #model_ident<T> {
+                Empty,
+                Full(T),
+                PrustiInjectedPhantomVariant(::core::marker::PhantomData<T>)
+            }
+        );
+        let expected_alias: syn::ItemType = parse_quote!(
+            #[allow(non_camel_case_types)]
+            type FooTi32Model<T> = #model_ident<T>;
+        );
+        assert_eq_tokenizable(model.model_type, expected);
+        assert_eq_tokenizable(model.model_type_alias, Some(expected_alias));
     }
 
     #[test]
@@ -394,6 +695,74 @@ mod tests {
         assert_eq_tokenizable(expected, model.model_impl);
     }
 
+    #[test]
+    fn ok_generates_impl_with_abstraction() {
+        let input = parse_quote!(
+            struct Foo {
+                fld: usize,
+            }
+        );
+        let args: ModelArgs = parse_quote!(abstraction = foo_model);
+        let model = expect_ok(rewrite_model(ModelItem::Struct(input), args));
+
+        let model_ident = check_model_ident(&model, "PrustiFooModel");
+        let trait_ident = check_trait_ident(&model, "PrustiFooToModel");
+        let trait_ident_str = trait_ident.to_string();
+
+        let expected_impl: syn::ItemImpl = parse_quote!(
+            #[prusti::type_models_to_model_impl]
+            #[prusti::specs_version = #SPECS_VERSION]
+            impl #trait_ident<> for Foo <> {
+                #[pure]
+                #[prusti::type_models_to_model_fn = #trait_ident_str]
+                fn model(&self) -> #model_ident<> {
+                    foo_model(self)
+                }
+            }
+        );
+        let expected_alias: syn::ItemType = parse_quote!(
+            #[allow(non_camel_case_types)]
+            type FooModel<> = #model_ident<>;
+        );
+
+        assert_eq_tokenizable(model.model_impl, expected_impl);
+        assert_eq_tokenizable(model.model_type_alias, Some(expected_alias));
+    }
+
+    #[test]
+    fn ok_parses_model_args() {
+        let args: ModelArgs = syn::parse2(TokenStream::new()).unwrap();
+        assert!(args.abstraction.is_none());
+
+        let args: ModelArgs = parse_quote!(abstraction = crate::models::foo_model);
+        assert_eq_tokenizable(
+            args.abstraction,
+            Some(quote::quote!(crate::models::foo_model)),
+        );
+    }
+
+    #[test]
+    fn err_invalid_model_args() {
+        assert!(syn::parse2::<ModelArgs>(quote::quote!(abstractions = foo_model)).is_err());
+        assert!(syn::parse2::<ModelArgs>(quote::quote!(abstraction)).is_err());
+        assert!(syn::parse2::<ModelArgs>(quote::quote!(abstraction = foo_model, bar)).is_err());
+    }
+
+    #[test]
+    fn err_abstraction_of_generic_model() {
+        let input = parse_quote!(
+            struct Foo<#[generic] T> {
+                fld: T,
+            }
+        );
+        let args: ModelArgs = parse_quote!(abstraction = foo_model);
+        let result = rewrite_model(ModelItem::Struct(input), args);
+        assert!(matches!(
+            result,
+            Err(TypeModelGenerationError::AbstractionOfGenericModel(_))
+        ));
+    }
+
     #[test]
     fn ok_uses_inferred_lifetime() {
         let input: syn::ItemStruct = parse_quote!(
@@ -423,7 +792,7 @@ mod tests {
             }
         );
 
-        assert_eq_tokenizable(model.model_struct, expected_struct);
+        assert_eq_tokenizable(model.model_type, expected_struct);
         assert_eq_tokenizable(model.model_impl, expected_impl);
     }
 
@@ -467,7 +836,7 @@ mod tests {
             }
         );
 
-        assert_eq_tokenizable(model.model_struct, expected_struct);
+        assert_eq_tokenizable(model.model_type, expected_struct);
         assert_eq_tokenizable(model.to_model_trait, expected_trait);
         assert_eq_tokenizable(model.model_impl, expected_impl);
     }
@@ -535,7 +904,7 @@ mod tests {
     }
 
     fn check_model_ident(model: &TypeModel, expected_prefix: &str) -> Ident {
-        let ident = &model.model_struct.ident;
+        let ident = model.model_type.ident();
         assert!(ident.to_string().starts_with(expected_prefix));
         ident.clone()
     }
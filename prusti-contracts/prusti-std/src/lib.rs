@@ -1,5 +1,12 @@
+#![cfg_attr(feature = "prusti", feature(allocator_api))]
+
 use prusti_contracts::*;
 
+mod option;
+mod slice;
+mod string;
+mod vec;
+
 #[extern_spec]
 impl<K, V, S> ::std::collections::hash_map::HashMap<K, V, S>
 where
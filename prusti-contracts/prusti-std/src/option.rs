@@ -0,0 +1,70 @@
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> ::core::option::Option<T> {
+    #[pure]
+    #[ensures(result == matches!(self, Some(_)))]
+    pub fn is_some(&self) -> bool;
+
+    #[pure]
+    #[ensures(result == matches!(self, None))]
+    pub fn is_none(&self) -> bool;
+
+    #[requires(self.is_some())]
+    #[ensures(old(self) === Some(result))]
+    pub fn unwrap(self) -> T;
+
+    #[requires(self.is_some())]
+    #[ensures(old(self) === Some(result))]
+    pub fn expect(self, msg: &str) -> T;
+
+    #[ensures(old(self.is_some()) ==> old(self) === Some(result))]
+    #[ensures(old(self.is_none()) ==> result === default)]
+    pub fn unwrap_or(self, default: T) -> T;
+
+    #[ensures(old(self.is_some()) ==> old(self) === Some(result))]
+    pub fn unwrap_or_else<F>(self, f: F) -> T
+    where
+        F: FnOnce() -> T;
+
+    // The value of the result is given by the closure, which has no
+    // specification that could be referred to here.
+    #[ensures(result.is_some() == old(self.is_some()))]
+    pub fn map<U, F>(self, f: F) -> ::core::option::Option<U>
+    where
+        F: FnOnce(T) -> U;
+
+    #[ensures(old(self.is_none()) ==> result === default)]
+    pub fn map_or<U, F>(self, default: U, f: F) -> U
+    where
+        F: FnOnce(T) -> U;
+
+    #[ensures(old(self.is_some()) ==> result === old(self))]
+    #[ensures(old(self.is_none()) ==> result === optb)]
+    pub fn or(self, optb: ::core::option::Option<T>) -> ::core::option::Option<T>;
+
+    #[ensures(old(self.is_none()) ==> result.is_none())]
+    pub fn and_then<U, F>(self, f: F) -> ::core::option::Option<U>
+    where
+        F: FnOnce(T) -> ::core::option::Option<U>;
+
+    #[ensures(result.is_ok() == old(self.is_some()))]
+    pub fn ok_or<E>(self, err: E) -> ::core::result::Result<T, E>;
+
+    #[ensures(result.is_some() == self.is_some())]
+    pub fn as_ref(&self) -> ::core::option::Option<&T>;
+
+    #[ensures(result === old(snap(self)))]
+    #[ensures(self.is_none())]
+    pub fn take(&mut self) -> ::core::option::Option<T>;
+
+    #[ensures(result === old(snap(self)))]
+    #[ensures(self.is_some())]
+    pub fn replace(&mut self, value: T) -> ::core::option::Option<T>;
+}
+
+#[extern_spec]
+impl<T: ::core::default::Default> ::core::option::Option<T> {
+    #[ensures(old(self.is_some()) ==> old(self) === Some(result))]
+    pub fn unwrap_or_default(self) -> T;
+}
@@ -0,0 +1,69 @@
+use prusti_contracts::*;
+
+/// The indices with which `get` returns an element of a slice, i.e. `usize`.
+/// The postconditions of `get` only apply to such indices, since they refer to
+/// the index as a `usize`.
+pub trait UsizeSliceIndex<T>: ::core::slice::SliceIndex<[T], Output = T> {
+    #[pure]
+    fn as_usize(&self) -> usize;
+}
+
+#[refine_trait_spec]
+impl<T> UsizeSliceIndex<T> for usize {
+    #[pure]
+    #[trusted]
+    #[ensures(result == *self)]
+    fn as_usize(&self) -> usize {
+        *self
+    }
+}
+
+// The length of a slice and indexing into it are supported natively, so
+// `len` and `index` need no specification.
+#[extern_spec]
+impl<T> [T] {
+    // Ghost constraints are enabled for `prusti-std` by its `Prusti.toml`, and
+    // need to be enabled when verifying the calls to `get`.
+    #[ghost_constraint(I: UsizeSliceIndex<T>, [
+        ensures(index.as_usize() < self.len() ==> result === Some(&self[index.as_usize()])),
+        ensures(index.as_usize() >= self.len() ==> result.is_none())
+    ])]
+    pub fn get<I>(&self, index: I) -> ::core::option::Option<&I::Output>
+    where
+        I: ::core::slice::SliceIndex<[T]>;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(result.is_some() == (self.len() > 0))]
+    pub fn first(&self) -> ::core::option::Option<&T>;
+
+    #[ensures(result.is_some() == (self.len() > 0))]
+    pub fn last(&self) -> ::core::option::Option<&T>;
+
+    #[requires(a < self.len() && b < self.len())]
+    #[ensures(self.len() == old(self.len()))]
+    #[ensures(self[a] === old(snap(&self[b])))]
+    #[ensures(self[b] === old(snap(&self[a])))]
+    #[ensures(forall(|i: usize| (i < self.len() && i != a && i != b) ==>
+        self[i] === old(snap(&self[i]))))]
+    pub fn swap(&mut self, a: usize, b: usize);
+
+    #[ensures(self.len() == old(self.len()))]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        self[i] === old(snap(&self[self.len() - 1 - i]))))]
+    pub fn reverse(&mut self);
+
+    #[ensures(result.as_slice().len() == self.len())]
+    #[ensures(forall(|i: usize| i < self.len() ==> result.as_slice()[i] === self[i]))]
+    pub fn iter(&self) -> ::core::slice::Iter<'_, T>;
+}
+
+// An iterator over a slice is modelled by the slice of the elements that it has
+// not yielded yet.
+#[extern_spec]
+impl<'a, T> ::core::slice::Iter<'a, T> {
+    #[pure]
+    pub fn as_slice(&self) -> &'a [T];
+}
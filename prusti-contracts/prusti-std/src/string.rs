@@ -0,0 +1,38 @@
+use prusti_contracts::*;
+
+#[extern_spec]
+impl str {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+}
+
+#[extern_spec]
+impl ::std::string::String {
+    #[ensures(result.len() == 0)]
+    pub fn new() -> ::std::string::String;
+
+    #[ensures(result.len() == 0)]
+    pub fn with_capacity(capacity: usize) -> ::std::string::String;
+
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    // A character takes between one and four bytes in UTF-8.
+    #[ensures(self.len() > old(self.len()))]
+    #[ensures(self.len() - old(self.len()) <= 4)]
+    pub fn push(&mut self, ch: char);
+
+    #[ensures(self.len() == old(self.len()) + string.len())]
+    pub fn push_str(&mut self, string: &str);
+
+    #[ensures(self.len() == 0)]
+    pub fn clear(&mut self);
+}
@@ -0,0 +1,93 @@
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> ::std::vec::Vec<T> {
+    #[ensures(result.len() == 0)]
+    pub fn new() -> ::std::vec::Vec<T>;
+
+    #[ensures(result.len() == 0)]
+    pub fn with_capacity(capacity: usize) -> ::std::vec::Vec<T>;
+}
+
+// Indexing is supported natively only for arrays and slices, so the elements
+// of a vector are specified, and can be accessed, through `as_slice`.
+#[extern_spec]
+impl<T, A: ::std::alloc::Allocator> ::std::vec::Vec<T, A> {
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[pure]
+    #[ensures(result.len() == self.len())]
+    pub fn as_slice(&self) -> &[T];
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    #[ensures(self.as_slice()[old(self.len())] === value)]
+    #[ensures(forall(|i: usize| i < old(self.len()) ==>
+        self.as_slice()[i] === old(snap(&self.as_slice()[i]))))]
+    pub fn push(&mut self, value: T);
+
+    #[ensures(old(self.len()) == 0 ==> result.is_none() && self.len() == 0)]
+    #[ensures(old(self.len()) > 0 ==> result.is_some() && self.len() == old(self.len()) - 1)]
+    #[ensures(old(self.len()) > 0 ==>
+        result === Some(old(snap(&self.as_slice()[self.len() - 1]))))]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        self.as_slice()[i] === old(snap(&self.as_slice()[i]))))]
+    pub fn pop(&mut self) -> ::core::option::Option<T>;
+
+    #[requires(index <= self.len())]
+    #[ensures(self.len() == old(self.len()) + 1)]
+    #[ensures(self.as_slice()[index] === element)]
+    #[ensures(forall(|i: usize| i < index ==>
+        self.as_slice()[i] === old(snap(&self.as_slice()[i]))))]
+    #[ensures(forall(|i: usize| (index < i && i < self.len()) ==>
+        self.as_slice()[i] === old(snap(&self.as_slice()[i - 1]))))]
+    pub fn insert(&mut self, index: usize, element: T);
+
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    #[ensures(result === old(snap(&self.as_slice()[index])))]
+    #[ensures(forall(|i: usize| i < index ==>
+        self.as_slice()[i] === old(snap(&self.as_slice()[i]))))]
+    #[ensures(forall(|i: usize| (index <= i && i < self.len()) ==>
+        self.as_slice()[i] === old(snap(&self.as_slice()[i + 1]))))]
+    pub fn remove(&mut self, index: usize) -> T;
+
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    #[ensures(result === old(snap(&self.as_slice()[index])))]
+    pub fn swap_remove(&mut self, index: usize) -> T;
+
+    #[ensures(len >= old(self.len()) ==> self.len() == old(self.len()))]
+    #[ensures(len < old(self.len()) ==> self.len() == len)]
+    pub fn truncate(&mut self, len: usize);
+
+    #[ensures(self.len() == old(self.len()) + old(other.len()))]
+    #[ensures(other.len() == 0)]
+    pub fn append(&mut self, other: &mut ::std::vec::Vec<T, A>);
+
+    #[ensures(self.len() == 0)]
+    pub fn clear(&mut self);
+}
+
+// Slice methods such as `get` and `iter` are called on vectors through `Deref`,
+// and are specified in `slice.rs`.
+#[extern_spec]
+impl<T, A: ::std::alloc::Allocator> ::core::ops::Deref for ::std::vec::Vec<T, A> {
+    #[pure]
+    #[ensures(result.len() == self.len())]
+    #[ensures(forall(|i: usize| i < self.len() ==> result[i] === self.as_slice()[i]))]
+    fn deref(&self) -> &[T];
+}
+
+// The sequence model of a vector is the one of its slice, so it follows `len`,
+// `as_slice` and the methods specified above, e.g. `push` appends to it.
+#[extern_spec]
+impl<T: Copy> prusti_contracts::AsSeq<T> for ::std::vec::Vec<T> {
+    #[pure]
+    #[ensures(result == self.as_slice().as_seq())]
+    fn as_seq(&self) -> Seq<T>;
+}
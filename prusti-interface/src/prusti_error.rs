@@ -110,6 +110,15 @@ impl PrustiError {
         )
     }
 
+    /// Report an invalid value of a configuration flag of Prusti
+    pub fn configuration<S: ToString>(message: S, span: MultiSpan) -> Self {
+        check_message(message.to_string());
+        PrustiError::new(
+            format!("[Prusti: invalid configuration] {}", message.to_string()),
+            span,
+        )
+    }
+
     /// Report a non-fatal issue
     pub fn warning<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
@@ -155,6 +164,14 @@ impl PrustiError {
         matches!(self.kind, PrustiErrorKind::Error)
     }
 
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> &MultiSpan {
+        &self.span
+    }
+
     // FIXME: This flag is a temporary workaround for having duplicate errors
     // coming from verifying functions multiple times. We should verify each
     // function only once.
@@ -0,0 +1,215 @@
+use super::common::*;
+use crate::{
+    environment::{EnvQuery, Environment},
+    utils::{has_extern_spec_attr, has_prusti_attr, read_prusti_attr, read_prusti_attrs},
+    PrustiError,
+};
+use prusti_rustc_interface::{
+    ast::ast::LitKind,
+    errors::MultiSpan,
+    hir::{self as hir, intravisit},
+    middle::hir::map::Map,
+    span::Span,
+};
+use rustc_hash::FxHashMap;
+
+/// Warns about preconditions that are trivially unsatisfiable, e.g.
+/// `#[requires(false)]`, which make the function verify vacuously.
+pub struct UnsatisfiablePreconditionsChecker;
+
+impl<'tcx> SpecCheckerStrategy<'tcx> for UnsatisfiablePreconditionsChecker {
+    fn check(&self, env: &Environment<'tcx>) -> Vec<PrustiError> {
+        let specs = collect_specs(env.query);
+        specs
+            .procedures
+            .iter()
+            .flat_map(|procedure| procedure.preconditions.iter())
+            .filter_map(|spec_id| specs.spec_functions.get(spec_id))
+            .filter(|spec_function| spec_function.constant_value == Some(false))
+            .map(|spec_function| {
+                PrustiError::warning(
+                    "this precondition is unsatisfiable, so the function is verified vacuously \
+                    and cannot be called",
+                    MultiSpan::from_span(spec_function.span),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Warns about postconditions that are implied by `true`, e.g.
+/// `#[ensures(true)]`, which do not specify anything.
+pub struct TrivialPostconditionsChecker;
+
+impl<'tcx> SpecCheckerStrategy<'tcx> for TrivialPostconditionsChecker {
+    fn check(&self, env: &Environment<'tcx>) -> Vec<PrustiError> {
+        let specs = collect_specs(env.query);
+        specs
+            .procedures
+            .iter()
+            .flat_map(|procedure| procedure.postconditions.iter())
+            .filter_map(|spec_id| specs.spec_functions.get(spec_id))
+            .filter(|spec_function| spec_function.constant_value == Some(true))
+            .map(|spec_function| {
+                PrustiError::warning(
+                    "this postcondition is implied by `true`, so it does not specify anything",
+                    MultiSpan::from_span(spec_function.span),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Warns about `#[trusted]` functions without a postcondition that return a
+/// value or take a mutable reference, about which callers can then assume
+/// nothing.
+pub struct TrustedWithoutPostconditionChecker;
+
+impl<'tcx> SpecCheckerStrategy<'tcx> for TrustedWithoutPostconditionChecker {
+    fn check(&self, env: &Environment<'tcx>) -> Vec<PrustiError> {
+        collect_specs(env.query)
+            .procedures
+            .iter()
+            .filter(|procedure| {
+                procedure.trusted
+                    && !procedure.pure
+                    && procedure.has_effect
+                    && procedure.postconditions.is_empty()
+            })
+            .map(|procedure| {
+                PrustiError::warning(
+                    "this function is trusted but has no postcondition, so nothing can be \
+                    assumed about its result or its effect on its arguments",
+                    MultiSpan::from_span(procedure.span),
+                )
+            })
+            .collect()
+    }
+}
+
+struct SpecFunction {
+    span: Span,
+    /// The value of the specification, if it is a constant.
+    constant_value: Option<bool>,
+}
+
+struct Procedure {
+    span: Span,
+    preconditions: Vec<String>,
+    /// The ids of the postconditions, including pledges.
+    postconditions: Vec<String>,
+    trusted: bool,
+    pure: bool,
+    /// Whether the function returns a value or takes a mutable reference.
+    has_effect: bool,
+}
+
+fn collect_specs(env_query: EnvQuery<'_>) -> CollectSpecsVisitor<'_> {
+    let mut collect = CollectSpecsVisitor {
+        env_query,
+        spec_functions: FxHashMap::default(),
+        procedures: Vec::new(),
+    };
+    env_query.hir().walk_toplevel_module(&mut collect);
+    collect
+}
+
+/// Collects the specification functions, by specification id, and the
+/// specifications of the functions.
+struct CollectSpecsVisitor<'tcx> {
+    env_query: EnvQuery<'tcx>,
+    spec_functions: FxHashMap<String, SpecFunction>,
+    procedures: Vec<Procedure>,
+}
+
+impl<'tcx> intravisit::Visitor<'tcx> for CollectSpecsVisitor<'tcx> {
+    type Map = Map<'tcx>;
+    type NestedFilter = prusti_rustc_interface::middle::hir::nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.env_query.hir()
+    }
+
+    fn visit_fn(
+        &mut self,
+        fk: intravisit::FnKind<'tcx>,
+        fd: &'tcx hir::FnDecl<'tcx>,
+        b: hir::BodyId,
+        s: Span,
+        id: hir::HirId,
+    ) {
+        let attrs = self.env_query.get_local_attributes(id);
+        if let Some(spec_id) = read_prusti_attr("spec_id", attrs) {
+            let body = self.env_query.hir().body(b);
+            self.spec_functions.insert(
+                spec_id,
+                SpecFunction {
+                    span: s,
+                    constant_value: constant_value(body.value),
+                },
+            );
+        } else if !has_prusti_attr(attrs, "spec_only") && !has_extern_spec_attr(attrs) {
+            let mut postconditions = read_prusti_attrs("post_spec_id_ref", attrs);
+            postconditions.extend(read_prusti_attrs("pledge_spec_id_ref", attrs));
+            let returns_value = match fd.output {
+                hir::FnRetTy::DefaultReturn(_) => false,
+                hir::FnRetTy::Return(ty) => !matches!(ty.kind, hir::TyKind::Tup([])),
+            };
+            let takes_mutable_reference = fd.inputs.iter().any(|input| {
+                matches!(
+                    input.kind,
+                    hir::TyKind::Rptr(
+                        _,
+                        hir::MutTy {
+                            mutbl: hir::Mutability::Mut,
+                            ..
+                        }
+                    )
+                )
+            });
+            self.procedures.push(Procedure {
+                span: self
+                    .env_query
+                    .get_def_span(self.env_query.as_local_def_id(id)),
+                preconditions: read_prusti_attrs("pre_spec_id_ref", attrs),
+                postconditions,
+                trusted: has_prusti_attr(attrs, "trusted"),
+                pure: has_prusti_attr(attrs, "pure"),
+                has_effect: returns_value || takes_mutable_reference,
+            });
+        }
+
+        intravisit::walk_fn(self, fk, fd, b, id);
+    }
+}
+
+/// The value of a boolean expression that is constant, e.g. `true` or
+/// `x > 0 && false`. Specification functions wrap the expression written by
+/// the user in a block, a type ascription and a double negation.
+fn constant_value(expr: &hir::Expr) -> Option<bool> {
+    match expr.kind {
+        hir::ExprKind::Lit(ref lit) => match lit.node {
+            LitKind::Bool(value) => Some(value),
+            _ => None,
+        },
+        hir::ExprKind::Unary(hir::UnOp::Not, inner) => constant_value(inner).map(|value| !value),
+        hir::ExprKind::Binary(op, left, right) => {
+            match (op.node, constant_value(left), constant_value(right)) {
+                (hir::BinOpKind::And, Some(false), _) | (hir::BinOpKind::And, _, Some(false)) => {
+                    Some(false)
+                }
+                (hir::BinOpKind::Or, Some(true), _) | (hir::BinOpKind::Or, _, Some(true)) => {
+                    Some(true)
+                }
+                // Both operands of `&&` are then `true`, or both of `||` are `false`.
+                (hir::BinOpKind::And | hir::BinOpKind::Or, Some(left), Some(_)) => Some(left),
+                _ => None,
+            }
+        }
+        hir::ExprKind::Type(inner, _) | hir::ExprKind::DropTemps(inner) => constant_value(inner),
+        hir::ExprKind::Block(block, _) if block.stmts.is_empty() => {
+            block.expr.and_then(constant_value)
+        }
+        _ => None,
+    }
+}
@@ -1,13 +1,19 @@
 //! Module for verifying user-provided specifications after macro expansion
 
 mod common;
+mod lint_checks;
 mod predicate_checks;
 mod type_model_checks;
 mod version_checks;
 
 use crate::environment::Environment;
 use common::*;
+use lint_checks::{
+    TrivialPostconditionsChecker, TrustedWithoutPostconditionChecker,
+    UnsatisfiablePreconditionsChecker,
+};
 use predicate_checks::IllegalPredicateUsagesChecker;
+use prusti_common::config;
 use type_model_checks::{IllegalModelUsagesChecker, ModelDefinedOnTypeWithoutFields};
 use version_checks::MismatchedVersionsChecker;
 
@@ -25,14 +31,18 @@ impl<'tcx> Default for SpecChecker<'tcx> {
 
 impl<'tcx> SpecChecker<'tcx> {
     pub fn new() -> Self {
-        Self {
-            checks: vec![
-                Box::new(MismatchedVersionsChecker {}),
-                Box::new(IllegalPredicateUsagesChecker {}),
-                Box::new(IllegalModelUsagesChecker {}),
-                Box::new(ModelDefinedOnTypeWithoutFields {}),
-            ],
+        let mut checks: Vec<Box<dyn SpecCheckerStrategy<'tcx>>> = vec![
+            Box::new(MismatchedVersionsChecker {}),
+            Box::new(IllegalPredicateUsagesChecker {}),
+            Box::new(IllegalModelUsagesChecker {}),
+            Box::new(ModelDefinedOnTypeWithoutFields {}),
+        ];
+        if config::lint_specs() {
+            checks.push(Box::new(UnsatisfiablePreconditionsChecker {}));
+            checks.push(Box::new(TrivialPostconditionsChecker {}));
+            checks.push(Box::new(TrustedWithoutPostconditionChecker {}));
         }
+        Self { checks }
     }
 
     /// Executes all checks and emits errors
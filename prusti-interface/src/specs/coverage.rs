@@ -0,0 +1,248 @@
+use crate::{
+    environment::Environment,
+    specs::typed::{DefSpecificationMap, ProcedureSpecification, SpecificationItem},
+};
+use prusti_common::config;
+use prusti_rustc_interface::{hir::def_id::DefId, middle::ty::print::with_no_trimmed_paths};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    io,
+    path::Path,
+};
+
+/// A report of which public functions and types of each module of the local
+/// crate have specifications. Only the items that are reachable from outside
+/// the crate count as public.
+#[derive(Debug, Serialize)]
+pub struct SpecCoverage {
+    #[serde(rename = "crate")]
+    krate: String,
+    modules: Vec<ModuleCoverage>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ModuleCoverage {
+    path: String,
+    /// The number of functions with at least one specification.
+    specified_functions: usize,
+    trusted_functions: usize,
+    /// The number of functions that are not trusted, and thus verified, or
+    /// `None` if verification is disabled with `NO_VERIFY`.
+    verified_functions: Option<usize>,
+    types_with_invariants: usize,
+    functions: Vec<FunctionCoverage>,
+    types: Vec<TypeCoverage>,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionCoverage {
+    name: String,
+    requires: bool,
+    /// Whether the function has a postcondition or a pledge.
+    ensures: bool,
+    pure: bool,
+    trusted: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct TypeCoverage {
+    name: String,
+    invariant: bool,
+    trusted: bool,
+}
+
+impl FunctionCoverage {
+    fn new(name: String, spec: Option<&ProcedureSpecification>) -> Self {
+        let Some(spec) = spec else {
+            return FunctionCoverage {
+                name,
+                requires: false,
+                ensures: false,
+                pure: false,
+                trusted: false,
+            };
+        };
+        FunctionCoverage {
+            name,
+            requires: is_non_empty(&spec.pres),
+            ensures: is_non_empty(&spec.posts) || is_non_empty(&spec.pledges),
+            pure: spec.kind.is_pure().unwrap_or(false),
+            trusted: spec.trusted.extract_inherit().unwrap_or(false),
+        }
+    }
+
+    fn is_specified(&self) -> bool {
+        self.requires || self.ensures || self.pure || self.trusted
+    }
+}
+
+impl SpecCoverage {
+    /// Collects the coverage of the public functions and types of the local
+    /// crate by the given specifications.
+    pub fn collect(env: &Environment<'_>, def_spec: &DefSpecificationMap) -> Self {
+        let tcx = env.tcx();
+        let effective_visibilities = tcx.effective_visibilities(());
+        let is_exported = |def_id: DefId| {
+            def_id
+                .as_local()
+                .map_or(false, |def_id| effective_visibilities.is_exported(def_id))
+        };
+        // Without verification, no function counts as verified.
+        let verify = !config::no_verify();
+        let new_module = || ModuleCoverage {
+            verified_functions: verify.then_some(0),
+            ..Default::default()
+        };
+        let mut modules: BTreeMap<String, ModuleCoverage> = BTreeMap::new();
+        // The paths of modules and items both start with the crate name.
+        let krate = env.name.local_crate_name();
+        let item_path = |def_id: DefId| {
+            let path = with_no_trimmed_paths!(tcx.def_path_str(def_id));
+            if path.is_empty() {
+                krate.clone()
+            } else {
+                format!("{krate}::{path}")
+            }
+        };
+        let module_path = |def_id: DefId| {
+            item_path(
+                tcx.parent_module_from_def_id(def_id.expect_local())
+                    .to_def_id(),
+            )
+        };
+
+        let (procedures, types) = env.get_annotated_procedures_and_types();
+        for def_id in procedures {
+            if tcx.is_closure(def_id) || !is_exported(def_id) {
+                continue;
+            }
+            let spec = def_spec
+                .get_proc_spec(&def_id)
+                .map(|spec_graph| &spec_graph.base_spec);
+            let function = FunctionCoverage::new(item_path(def_id), spec);
+            let module = modules
+                .entry(module_path(def_id))
+                .or_insert_with(new_module);
+            module.specified_functions += function.is_specified() as usize;
+            if function.trusted {
+                module.trusted_functions += 1;
+            } else if let Some(verified_functions) = &mut module.verified_functions {
+                *verified_functions += 1;
+            }
+            module.functions.push(function);
+        }
+        for ty in types {
+            let Some(adt_def) = ty.ty_adt_def() else {
+                continue;
+            };
+            let def_id = adt_def.did();
+            if !is_exported(def_id) {
+                continue;
+            }
+            let spec = def_spec.get_type_spec(&def_id);
+            let typ = TypeCoverage {
+                name: item_path(def_id),
+                invariant: spec.map_or(false, |spec| is_non_empty(&spec.invariant)),
+                trusted: spec.map_or(false, |spec| {
+                    spec.trusted.extract_inherit().unwrap_or(false)
+                }),
+            };
+            let module = modules
+                .entry(module_path(def_id))
+                .or_insert_with(new_module);
+            module.types_with_invariants += typ.invariant as usize;
+            module.types.push(typ);
+        }
+
+        for (path, module) in modules.iter_mut() {
+            module.path = path.clone();
+            module.functions.sort_by(|a, b| a.name.cmp(&b.name));
+            module.types.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        SpecCoverage {
+            krate,
+            modules: modules.into_values().collect(),
+        }
+    }
+
+    /// Writes the report as JSON to the given file.
+    pub fn write_json(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn is_non_empty<T>(item: &SpecificationItem<Vec<T>>) -> bool {
+    item.extract_with_selective_replacement()
+        .map_or(false, |items| !items.is_empty())
+}
+
+/// The percentage of `part` in `total`, or 100 if `total` is zero.
+fn percentage(part: usize, total: usize) -> usize {
+    if total == 0 {
+        100
+    } else {
+        part * 100 / total
+    }
+}
+
+impl Display for SpecCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Specification coverage of crate `{}`:", self.krate)?;
+        for module in &self.modules {
+            let functions = module.functions.len();
+            let verified = match module.verified_functions {
+                Some(verified_functions) => format!(
+                    "{} verified ({}%)",
+                    verified_functions,
+                    percentage(verified_functions, functions)
+                ),
+                None => "verification disabled".to_string(),
+            };
+            writeln!(
+                f,
+                "module `{}`: {}/{} public functions specified ({}%), {} trusted ({}%), \
+                {}; {}/{} public types with invariants",
+                module.path,
+                module.specified_functions,
+                functions,
+                percentage(module.specified_functions, functions),
+                module.trusted_functions,
+                percentage(module.trusted_functions, functions),
+                verified,
+                module.types_with_invariants,
+                module.types.len(),
+            )?;
+            for function in &module.functions {
+                let annotations: Vec<_> = [
+                    (function.requires, "requires"),
+                    (function.ensures, "ensures"),
+                    (function.pure, "pure"),
+                    (function.trusted, "trusted"),
+                ]
+                .into_iter()
+                .filter_map(|(present, annotation)| present.then_some(annotation))
+                .collect();
+                if annotations.is_empty() {
+                    writeln!(f, "  fn `{}`: no specification", function.name)?;
+                } else {
+                    writeln!(f, "  fn `{}`: {}", function.name, annotations.join(", "))?;
+                }
+            }
+            for typ in &module.types {
+                let annotation = match (typ.invariant, typ.trusted) {
+                    (true, true) => "invariant, trusted",
+                    (true, false) => "invariant",
+                    (false, true) => "trusted",
+                    (false, false) => "no invariant",
+                };
+                writeln!(f, "  type `{}`: {}", typ.name, annotation)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@ use prusti_rustc_interface::{
 use std::{collections::HashMap, convert::TryInto, fmt::Debug};
 
 pub mod checker;
+pub mod coverage;
 pub mod cross_crate;
 pub mod decoder;
 pub mod encoder;
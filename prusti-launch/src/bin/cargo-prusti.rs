@@ -6,8 +6,12 @@
 #![feature(let_chains)]
 #![feature(option_result_contains)]
 
-use prusti_utils::{config, launch};
-use std::{env, fs, io, path::PathBuf, process::Command};
+use prusti_utils::{config, launch, report::verification};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 fn main() {
     if let Err(code) = process(env::args().skip(1)) {
@@ -44,7 +48,21 @@ where
     };
     let cargo_target = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
     let cargo_target: PathBuf = [cargo_target, "verify".to_string()].into_iter().collect();
-    let exit_status = Command::new(cargo_path)
+    // Each verified crate writes its report to this directory, which is
+    // specific to this run so that only the crates verified in it are
+    // summarized.
+    let summary_dir = if config::summary() {
+        let summary_dir = cargo_target.join(format!("summary-{}", std::process::id()));
+        fs::remove_dir_all(&summary_dir).ok();
+        Some(summary_dir)
+    } else {
+        None
+    };
+    let mut cargo_command = Command::new(cargo_path);
+    if let Some(summary_dir) = &summary_dir {
+        cargo_command.env("PRUSTI_SUMMARY_DIR", summary_dir);
+    }
+    let exit_status = cargo_command
         .arg(&command)
         .args(features)
         .args(args)
@@ -63,9 +81,15 @@ where
         .env("DEFAULT_PRUSTI_FULL_COMPILATION", "true")
         .env("DEFAULT_PRUSTI_LOG_DIR", cargo_target.join("log"))
         .env("DEFAULT_PRUSTI_CACHE_PATH", cargo_target.join("cache.bin"))
+        .env("DEFAULT_PRUSTI_REPORT_DIR", cargo_target.join("report"))
         .status()
         .expect("could not run cargo");
 
+    if let Some(summary_dir) = summary_dir {
+        print_summary(&summary_dir);
+        fs::remove_dir_all(summary_dir).ok();
+    }
+
     if exit_status.success() {
         if command == "build" {
             copy_exported_specs(cargo_target).ok();
@@ -76,6 +100,17 @@ where
     }
 }
 
+/// Print a summary of the reports written by the crates verified in this run.
+fn print_summary(summary_dir: &Path) {
+    // The directory does not exist if no crate was verified.
+    if let Ok(reports) = verification::read_reports(summary_dir) && !reports.is_empty() {
+        println!(
+            "\n{}",
+            verification::format_summary(&reports, config::summary_slowest_items())
+        );
+    }
+}
+
 /// Copy specs from '{cargo_target}/*/deps/*.specs' to '{cargo_target}/*/*.specs'
 fn copy_exported_specs(cargo_target: PathBuf) -> io::Result<()> {
     for de in fs::read_dir(cargo_target)? {
@@ -0,0 +1,47 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use prusti_utils::launch;
+use std::{path::PathBuf, process::Command};
+
+fn main() {
+    if let Err(code) = process(std::env::args().skip(1).collect()) {
+        std::process::exit(code);
+    }
+}
+
+fn process(args: Vec<String>) -> Result<(), i32> {
+    let current_executable_dir = launch::get_current_executable_dir();
+
+    let mut prusti_cache_driver_path = current_executable_dir.join("prusti-cache-driver");
+    if cfg!(windows) {
+        prusti_cache_driver_path.set_extension("exe");
+    }
+
+    let java_home = match std::env::var("JAVA_HOME") {
+        Ok(java_home) => PathBuf::from(java_home),
+        Err(_) => launch::find_java_home()
+            .expect("Failed to find Java home directory. Try setting JAVA_HOME"),
+    };
+
+    let mut cmd = Command::new(&prusti_cache_driver_path);
+    cmd.args(args);
+
+    // The driver does not start the JVM, but it links to the JVM library.
+    let libjvm_path =
+        launch::find_libjvm(&java_home).expect("Failed to find JVM library. Check JAVA_HOME");
+    launch::add_to_loader_path(vec![libjvm_path], &mut cmd);
+
+    launch::set_environment_settings(&mut cmd, &current_executable_dir, &java_home);
+
+    let exit_status = cmd.status().expect("could not run prusti-cache-driver");
+
+    if exit_status.success() {
+        Ok(())
+    } else {
+        Err(exit_status.code().unwrap_or(-1))
+    }
+}
@@ -0,0 +1,216 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use clap::{Parser, Subcommand};
+use prusti_utils::config;
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use viper::{PersistentCache, VerificationResult, RESULT_CACHE_VERSION};
+
+/// Manages the verification cache of Prusti.
+#[derive(Parser, Debug)]
+#[clap(version, about, long_about = None)]
+struct Args {
+    /// The cache file or directory. Defaults to the value of the `CACHE_DIR`
+    /// flag, or else of the `CACHE_PATH` flag.
+    #[clap(long, value_name = "PATH")]
+    cache_path: Option<PathBuf>,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Lists the cached results, most recently used first.
+    Inspect,
+    /// Removes cached results.
+    Prune {
+        /// Removes the results that were not used in the given number of days.
+        #[clap(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+        /// Keeps at most the given number of results, removing the least
+        /// recently used ones.
+        #[clap(long, value_name = "N")]
+        max_entries: Option<usize>,
+    },
+    /// Copies the cache to another file, or into a cache directory.
+    Export {
+        #[clap(value_name = "PATH")]
+        file: PathBuf,
+        /// Merges the cache into the given cache directory, creating it if
+        /// needed, instead of overwriting a file.
+        #[clap(long)]
+        directory: bool,
+    },
+    /// Merges the results of another cache file or directory into the cache.
+    Import {
+        #[clap(value_name = "PATH")]
+        file: PathBuf,
+    },
+    /// Rewrites the cache in the current format.
+    Migrate,
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+fn main() {
+    env_logger::init_from_env(
+        env_logger::Env::new()
+            .filter_or("PRUSTI_LOG", config::log())
+            .write_style_or("PRUSTI_LOG_STYLE", config::log_style()),
+    );
+
+    let args = Args::parse();
+    let cache_path = args
+        .cache_path
+        .or_else(config::cache_dir)
+        .unwrap_or_else(config::cache_path);
+    if cache_path.as_os_str().is_empty() {
+        eprintln!(
+            "No cache given. Use the `--cache-path` option, or the `CACHE_DIR` or \
+            `CACHE_PATH` flag."
+        );
+        std::process::exit(2);
+    }
+
+    // A cache file is saved when dropped, if it changed. A cache directory is
+    // updated immediately.
+    let mut cache = match args.command {
+        // Unlike the other commands, migrating fails if the cache is unreadable.
+        Command::Migrate => return migrate(&cache_path),
+        _ => PersistentCache::load(cache_path.clone()),
+    };
+    match args.command {
+        Command::Inspect => inspect(&cache),
+        Command::Prune {
+            older_than,
+            max_entries,
+        } => {
+            let len = cache.len();
+            if let Some(days) = older_than {
+                let cutoff = now().saturating_sub(days.saturating_mul(SECONDS_PER_DAY));
+                cache.retain(|_, entry| entry.last_used >= cutoff);
+            }
+            if max_entries.is_some() {
+                cache.set_max_entries(max_entries);
+            }
+            println!("Removed {} of {} cached results", len - cache.len(), len);
+        }
+        Command::Export { file, directory } => {
+            if directory || file.is_dir() {
+                PersistentCache::load_directory_cache(file).merge(&cache);
+            } else {
+                cache.save_cache(&file);
+            }
+            println!("Exported {} cached results", cache.len());
+        }
+        Command::Import { file } => {
+            let other = PersistentCache::load(file);
+            let len = cache.len();
+            cache.merge(&other);
+            println!(
+                "Imported {} cached results, of which {} are new",
+                other.len(),
+                cache.len() - len
+            );
+        }
+        Command::Migrate => unreachable!(),
+    }
+}
+
+/// Rewrites a cache file in the current format. Exits with an error if the
+/// cache is a directory or if the file cannot be read, e.g. because of an
+/// unknown version.
+fn migrate(cache_path: &Path) {
+    if cache_path.is_dir() {
+        // Entries of other versions are ignored when loading.
+        eprintln!("Cache directories cannot be migrated.");
+        std::process::exit(2);
+    }
+    let (cache, version) = PersistentCache::try_load_cache(cache_path.to_path_buf())
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "Failed to read the cache \"{}\": {}",
+                cache_path.display(),
+                e
+            );
+            std::process::exit(2);
+        });
+    if version == RESULT_CACHE_VERSION && cache.is_empty() {
+        println!("No cached results to migrate");
+    } else {
+        cache.save_cache(cache_path);
+        println!(
+            "Migrated {} cached results from version {} to version {}",
+            cache.len(),
+            version,
+            RESULT_CACHE_VERSION
+        );
+    }
+}
+
+fn inspect(cache: &PersistentCache) {
+    let now = now();
+    let mut entries: Vec<_> = cache.entries().collect();
+    entries.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.last_used));
+    println!(
+        "{:<20}  {:<40}  {:<20}  {:<12}  {:<12}  {}",
+        "HASH", "PROGRAM", "RESULT", "CREATED", "LAST USED", "CONFIGURATION"
+    );
+    for (request, entry) in entries {
+        let program_name = if entry.program_name.is_empty() {
+            "?"
+        } else {
+            &entry.program_name
+        };
+        println!(
+            "{:<20}  {:<40}  {:<20}  {:<12}  {:<12}  {}",
+            request,
+            program_name,
+            describe_result(&entry.result),
+            format_age(now, entry.created),
+            format_age(now, entry.last_used),
+            entry.configuration.as_deref().unwrap_or("-"),
+        );
+    }
+    println!("{} cached results", cache.len());
+}
+
+fn describe_result(result: &VerificationResult) -> String {
+    match result {
+        VerificationResult::Success => "success".to_string(),
+        VerificationResult::Failure(errors) => format!("{} errors", errors.len()),
+        VerificationResult::ConsistencyErrors(errors) => {
+            format!("{} consistency errors", errors.len())
+        }
+        VerificationResult::JavaException(_) => "java exception".to_string(),
+        VerificationResult::Cancelled => "cancelled".to_string(),
+        VerificationResult::TimedOut => "timed out".to_string(),
+        VerificationResult::BackendTimedOut => "backend timed out".to_string(),
+    }
+}
+
+fn format_age(now: u64, timestamp: u64) -> String {
+    let age = now.saturating_sub(timestamp);
+    if age >= SECONDS_PER_DAY {
+        format!("{} days ago", age / SECONDS_PER_DAY)
+    } else if age >= 60 * 60 {
+        format!("{} hours ago", age / (60 * 60))
+    } else if age >= 60 {
+        format!("{} min ago", age / 60)
+    } else {
+        "just now".to_string()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,95 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Mutex;
+use viper::{VerificationResult, VerifierStopper};
+
+/// Why a verification request was interrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    /// The request was explicitly cancelled.
+    Cancelled,
+    /// The deadline of the request passed.
+    TimedOut,
+}
+
+impl From<Interruption> for VerificationResult {
+    fn from(interruption: Interruption) -> Self {
+        match interruption {
+            Interruption::Cancelled => VerificationResult::Cancelled,
+            Interruption::TimedOut => VerificationResult::TimedOut,
+        }
+    }
+}
+
+#[derive(Default)]
+enum State {
+    #[default]
+    Waiting,
+    Running(VerifierStopper),
+    Interrupted(Interruption),
+    Finished,
+}
+
+/// Allows interrupting a verification request while it is waiting in the
+/// queue or while it is being verified.
+#[derive(Default)]
+pub struct Cancellation {
+    state: Mutex<State>,
+}
+
+impl Cancellation {
+    /// Called before running the verifier. Returns the interruption if the
+    /// request has already been interrupted.
+    pub fn start(&self, stopper: VerifierStopper) -> Result<(), Interruption> {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Interrupted(interruption) => Err(interruption),
+            _ => {
+                *state = State::Running(stopper);
+                Ok(())
+            }
+        }
+    }
+
+    /// Called once the verifier is done. Returns the interruption if the
+    /// request was interrupted in the meantime, in which case the verifier
+    /// has been stopped.
+    pub fn finish(&self) -> Result<(), Interruption> {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            State::Interrupted(interruption) => Err(interruption),
+            _ => {
+                *state = State::Finished;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the interruption, if the request has been interrupted.
+    pub fn interruption(&self) -> Option<Interruption> {
+        match *self.state.lock().unwrap() {
+            State::Interrupted(interruption) => Some(interruption),
+            _ => None,
+        }
+    }
+
+    /// Interrupts the request, stopping its verifier if it is running.
+    /// Requests that already finished are not affected.
+    pub fn interrupt(&self, interruption: Interruption) {
+        let mut state = self.state.lock().unwrap();
+        match std::mem::take(&mut *state) {
+            State::Waiting => *state = State::Interrupted(interruption),
+            State::Running(stopper) => {
+                // Keep the lock, so that the worker does not reuse the
+                // verifier while it is being stopped.
+                *state = State::Interrupted(interruption);
+                stopper.stop();
+            }
+            previous => *state = previous,
+        }
+    }
+}
@@ -4,12 +4,23 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::VerificationRequest;
+use crate::{
+    streaming::{take_frame, StreamedVerificationResult},
+    VerificationRequest,
+};
+use log::warn;
 use prusti_common::config;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
+use std::time::Duration;
 use url::{ParseError, Url};
 use viper::VerificationResult;
 
+/// How often a request is resent to a busy server before giving up.
+const SERVER_BUSY_MAX_RETRIES: u32 = 8;
+/// The delay before resending a request to a busy server, doubled with each
+/// retry.
+const SERVER_BUSY_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
 pub struct PrustiClient {
     client: Client,
     server_url: Url,
@@ -31,31 +42,131 @@ impl PrustiClient {
         &self,
         request: VerificationRequest,
     ) -> reqwest::Result<VerificationResult> {
+        let use_json = config::json_communication();
+        let url = self
+            .server_url
+            .join(if use_json { "json/" } else { "bincode/" })
+            .unwrap()
+            .join("verify/")
+            .unwrap();
+        let bincode_request = if use_json {
+            None
+        } else {
+            Some(bincode::serialize(&request).expect("error encoding verification request"))
+        };
+
+        let mut retries = 0;
+        let mut delay = SERVER_BUSY_INITIAL_DELAY;
+        let response = loop {
+            let base = self.client.post(url.clone());
+            let response = if let Some(bytes) = &bincode_request {
+                base.body(bytes.clone()).send().await?
+            } else {
+                base.json(&request).send().await?
+            };
+            if response.status() != StatusCode::SERVICE_UNAVAILABLE
+                || retries >= SERVER_BUSY_MAX_RETRIES
+            {
+                break response.error_for_status()?;
+            }
+            warn!(
+                "The Prusti server is busy; resending the verification request in {:?}",
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            retries += 1;
+            delay *= 2;
+        };
+
+        let result = if use_json {
+            response.json().await?
+        } else {
+            let bytes = response.bytes().await?;
+            bincode::deserialize(&bytes).expect("error decoding verification result")
+        };
+        Ok(result)
+    }
+
+    /// Asks the server to abort the request with the given identifier.
+    /// Returns whether the server was still working on the request.
+    pub async fn cancel(&self, request_id: &str) -> reqwest::Result<bool> {
+        let response = self
+            .client
+            .post(
+                self.server_url
+                    .join("cancel/")
+                    .unwrap()
+                    .join(request_id)
+                    .unwrap(),
+            )
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+
+    /// Sends a batch of verification requests, whose results are streamed
+    /// back in the order in which the server finishes them.
+    pub async fn verify_stream(
+        &self,
+        requests: Vec<VerificationRequest>,
+    ) -> reqwest::Result<VerificationResultStream> {
         let use_json = config::json_communication();
         let base = self.client.post(
             self.server_url
                 .join(if use_json { "json/" } else { "bincode/" })
                 .unwrap()
-                .join("verify/")
+                .join("verify_stream/")
                 .unwrap(),
         );
         let response = if use_json {
-            base.json(&request)
-                .send()
-                .await?
-                .error_for_status()?
-                .json()
-                .await?
+            base.json(&requests).send().await?
         } else {
-            let bytes = base
-                .body(bincode::serialize(&request).expect("error encoding verification request"))
+            base.body(bincode::serialize(&requests).expect("error encoding verification requests"))
                 .send()
                 .await?
-                .error_for_status()?
-                .bytes()
-                .await?;
-            bincode::deserialize(&bytes).expect("error decoding verification result")
         };
-        Ok(response)
+        Ok(VerificationResultStream {
+            response: response.error_for_status()?,
+            use_json,
+            buffer: vec![],
+        })
+    }
+}
+
+/// The results of a batch of verification requests, as sent by the server.
+pub struct VerificationResultStream {
+    response: reqwest::Response,
+    use_json: bool,
+    buffer: Vec<u8>,
+}
+
+impl VerificationResultStream {
+    /// Waits for the next result, returning `None` once the results of all
+    /// the requests of the batch have been received.
+    pub async fn next(&mut self) -> reqwest::Result<Option<StreamedVerificationResult>> {
+        loop {
+            if let Some(payload) = take_frame(&mut self.buffer) {
+                let result = if self.use_json {
+                    serde_json::from_slice(&payload).expect("error decoding verification result")
+                } else {
+                    bincode::deserialize(&payload).expect("error decoding verification result")
+                };
+                return Ok(Some(result));
+            }
+            match self.response.chunk().await? {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None => {
+                    assert!(
+                        self.buffer.is_empty(),
+                        "the stream of verification results ended in the middle of a result"
+                    );
+                    return Ok(None);
+                }
+            }
+        }
     }
 }
@@ -4,15 +4,24 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+mod cancellation;
 mod client;
+mod method_cache;
 mod process_verification;
 mod server;
+mod streaming;
+mod verification_pool;
 mod verification_request;
+mod verifier_store;
 
+pub use cancellation::*;
 pub use client::*;
 pub use process_verification::*;
 pub use server::*;
+pub use streaming::StreamedVerificationResult;
+pub use verification_pool::*;
 pub use verification_request::*;
+pub use verifier_store::*;
 
 // Futures returned by `Client` need to be executed in a compatible tokio runtime.
 pub use tokio;
@@ -0,0 +1,199 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Caching of the verification results of the individual methods of a
+//! program, so that only the methods whose slice changed have to be verified
+//! again. See `prusti_common::vir::program_slicing`.
+
+use crate::VerificationRequest;
+use log::debug;
+use prusti_common::vir::{
+    program::Program,
+    program_normalization::NormalizationInfo,
+    program_slicing::{slice_program, ProgramSlice},
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+use viper::{Cache, VerificationError, VerificationResult};
+
+struct CachedSlice {
+    slice: ProgramSlice,
+    /// Maps the positions of the sliced program to the ones of the normalized
+    /// slice, whose results are cached.
+    normalization_info: NormalizationInfo,
+    hash: u64,
+}
+
+pub(crate) struct SlicedProgram {
+    /// The first slice verifies the functions and predicates, which are
+    /// verified together with any method.
+    slices: Vec<CachedSlice>,
+}
+
+impl SlicedProgram {
+    /// Slices the program of a normalized request. Returns `None` for vir::low
+    /// programs, which cannot be sliced.
+    pub(crate) fn new(request: &VerificationRequest) -> Option<Self> {
+        let program = match &request.program {
+            Program::Legacy(program) => program,
+            Program::Low(_) => return None,
+        };
+        let slices = slice_program(program)
+            .into_iter()
+            .map(|slice| {
+                let mut normalized_program = Program::Legacy(slice.program.clone());
+                let normalization_info =
+                    NormalizationInfo::normalize_program(&mut normalized_program);
+                // Unlike the result of a request, the result of a slice only
+                // contains the errors of its own items, hence the prefix. Like
+                // the key of a request, the key depends on the portfolio.
+                let mut hasher = DefaultHasher::new();
+                if request.portfolio.is_empty() {
+                    ("slice", &normalized_program, &request.backend_config).hash(&mut hasher);
+                } else {
+                    ("slice", &normalized_program, &request.portfolio).hash(&mut hasher);
+                }
+                CachedSlice {
+                    slice,
+                    normalization_info,
+                    hash: hasher.finish(),
+                }
+            })
+            .collect();
+        Some(SlicedProgram { slices })
+    }
+
+    /// Returns the cached result of each slice, if any, with the positions of
+    /// the sliced program.
+    pub(crate) fn cached_results(&self, mut cache: impl Cache) -> Vec<Option<VerificationResult>> {
+        self.slices
+            .iter()
+            .map(|cached_slice| {
+                let mut result = cache.get(cached_slice.hash)?;
+                cached_slice
+                    .normalization_info
+                    .denormalize_result(&mut result);
+                Some(result)
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the slices that have to be verified, given their
+    /// cached results.
+    pub(crate) fn slices_to_verify(
+        &self,
+        cached_results: &[Option<VerificationResult>],
+    ) -> Vec<usize> {
+        if cached_results.iter().all(Option::is_some) {
+            return vec![];
+        }
+        // The functions and predicates are always verified.
+        let mut indices = vec![0];
+        indices.extend((1..self.slices.len()).filter(|&index| cached_results[index].is_none()));
+        indices
+    }
+
+    /// Removes the methods that are not verified by the given slices from the
+    /// sliced program.
+    pub(crate) fn restrict_program(&self, program: &mut Program, slice_indices: &[usize]) {
+        if let Program::Legacy(program) = program {
+            let methods: Vec<_> = slice_indices
+                .iter()
+                .flat_map(|&index| &self.slices[index].slice.methods)
+                .collect();
+            program
+                .methods
+                .retain(|method| methods.contains(&&method.method_name));
+        }
+    }
+
+    /// Caches the result of verifying the given slices, with the configuration
+    /// that produced it, if any. Nothing is cached if an error cannot be
+    /// attributed to exactly one of the slices.
+    pub(crate) fn store_result(
+        &self,
+        mut cache: impl Cache,
+        slice_indices: &[usize],
+        result: &VerificationResult,
+        configuration: Option<&str>,
+    ) {
+        let errors = match result {
+            VerificationResult::Success => vec![],
+            VerificationResult::Failure(errors) => errors.clone(),
+            _ => return,
+        };
+        let mut slice_errors: Vec<Vec<VerificationError>> = vec![vec![]; slice_indices.len()];
+        for error in errors {
+            let pos_id = error
+                .offending_pos_id
+                .as_ref()
+                .or(error.pos_id.as_ref())
+                .and_then(|pos| pos.parse::<u64>().ok());
+            let mut owners = slice_indices.iter().enumerate().filter(|&(_, &index)| {
+                pos_id.map_or(false, |pos_id| {
+                    self.slices[index].slice.own_positions.contains(&pos_id)
+                })
+            });
+            match (owners.next(), owners.next()) {
+                (Some((owner, _)), None) => slice_errors[owner].push(error),
+                _ => {
+                    debug!(
+                        "Not caching the results of the slices, because error {:?} \
+                        cannot be attributed to one of them",
+                        error
+                    );
+                    return;
+                }
+            }
+        }
+        for (&index, errors) in slice_indices.iter().zip(slice_errors) {
+            let cached_slice = &self.slices[index];
+            let slice_result = if errors.is_empty() {
+                VerificationResult::Success
+            } else {
+                VerificationResult::Failure(errors)
+            };
+            // Errors might refer to positions outside of the slice, e.g. in
+            // the contract of a bodyless method.
+            if let Some(slice_result) = cached_slice
+                .normalization_info
+                .try_normalize_result(&slice_result)
+            {
+                let program_name = &cached_slice.slice.program.name;
+                match configuration {
+                    Some(configuration) => cache.insert_with_configuration(
+                        cached_slice.hash,
+                        program_name,
+                        slice_result,
+                        configuration,
+                    ),
+                    None => cache.insert(cached_slice.hash, program_name, slice_result),
+                };
+            }
+        }
+    }
+}
+
+/// Combines the results of verifying disjoint parts of a program.
+pub(crate) fn merge_results(
+    results: impl IntoIterator<Item = VerificationResult>,
+) -> VerificationResult {
+    let mut errors = vec![];
+    for result in results {
+        match result {
+            VerificationResult::Success => {}
+            VerificationResult::Failure(slice_errors) => errors.extend(slice_errors),
+            other => return other,
+        }
+    }
+    if errors.is_empty() {
+        VerificationResult::Success
+    } else {
+        VerificationResult::Failure(errors)
+    }
+}
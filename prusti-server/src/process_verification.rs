@@ -4,24 +4,166 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{VerificationRequest, ViperBackendConfig};
+use crate::{
+    method_cache::{merge_results, SlicedProgram},
+    verification_pool::max_concurrency,
+    Cancellation, Interruption, VerificationRequest, VerifierStore, ViperBackendConfig,
+};
 use log::info;
 use prusti_common::{
     config,
-    report::log::{report, to_legal_file_name},
-    vir::{program_normalization::NormalizationInfo, ToViper},
+    report::{
+        log::{report, to_legal_file_name},
+        verification::CacheUsage,
+    },
+    vir::{program::Program, program_normalization::NormalizationInfo, ToViper},
     Stopwatch,
 };
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::create_dir_all,
+    iter,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 use viper::{
-    smt_manager::SmtManager, Cache, VerificationBackend, VerificationContext, VerificationResult,
+    smt_manager::SmtManager, Cache, PersistentCache, VerificationBackend, VerificationContext,
+    VerificationResult,
 };
 
+/// Serializes the startup of Viper verifiers, which is not thread safe.
+/// See <https://github.com/viperproject/silicon/issues/578>.
+static VERIFIER_STARTUP_LOCK: Mutex<()> = Mutex::new(());
+
+/// How often a portfolio checks whether its request has been interrupted.
+const PORTFOLIO_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The number of verifications that may still start, or `None` before the
+/// first verification. Together with the verifications of the workers, the
+/// configurations of portfolios are bounded by `SERVER_MAX_CONCURRENCY`.
+static FREE_VERIFICATION_SLOTS: Mutex<Option<usize>> = Mutex::new(None);
+static VERIFICATION_SLOT_RELEASED: Condvar = Condvar::new();
+
+/// The permission to run a verification, which is given back when dropped.
+struct VerificationSlot;
+
+impl VerificationSlot {
+    /// Waits until a verification may start, unless the request gets
+    /// interrupted in the meantime.
+    fn acquire(cancellation: &Cancellation) -> Result<Self, Interruption> {
+        let mut free_slots = FREE_VERIFICATION_SLOTS.lock().unwrap();
+        loop {
+            if let Some(interruption) = cancellation.interruption() {
+                return Err(interruption);
+            }
+            let free = free_slots.get_or_insert_with(max_concurrency);
+            if *free > 0 {
+                *free -= 1;
+                return Ok(VerificationSlot);
+            }
+            free_slots = VERIFICATION_SLOT_RELEASED
+                .wait_timeout(free_slots, PORTFOLIO_POLL_INTERVAL)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+impl Drop for VerificationSlot {
+    fn drop(&mut self) {
+        let mut free_slots = FREE_VERIFICATION_SLOTS.lock().unwrap();
+        *free_slots.get_or_insert_with(max_concurrency) += 1;
+        VERIFICATION_SLOT_RELEASED.notify_one();
+    }
+}
+
+/// Loads the verification cache configured by the `CACHE_DIR` or the
+/// `CACHE_PATH` flag.
+pub fn load_persistent_cache() -> PersistentCache {
+    let mut cache = match config::cache_dir() {
+        Some(cache_dir) => PersistentCache::load_directory_cache(cache_dir),
+        None => PersistentCache::load_cache(config::cache_path()),
+    };
+    cache.set_seed_directory(config::cache_seed_dir());
+    cache.set_max_entries(config::cache_max_entries());
+    cache
+}
+
+/// How the result of a verification request was obtained.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VerificationStats {
+    pub cache_usage: CacheUsage,
+    /// The time spent processing the request, including the cache lookups.
+    pub duration: Duration,
+    /// The configuration of the portfolio that produced the result, as
+    /// recorded in the cache, or `None` if the request was verified with its
+    /// own backend configuration.
+    pub configuration: Option<String>,
+}
+
 pub fn process_verification_request<'v, 't: 'v>(
     verification_context: &'v VerificationContext<'t>,
-    mut request: VerificationRequest,
+    request: VerificationRequest,
+    cache: impl Cache,
+) -> (viper::VerificationResult, VerificationStats) {
+    // Create a new verifier each time.
+    // Workaround for https://github.com/viperproject/prusti-dev/issues/744
+    let mut verifiers = VerifierStore::new(0);
+    process_verification_request_with_verifiers(
+        verification_context,
+        request,
+        cache,
+        &mut verifiers,
+        &Cancellation::default(),
+    )
+}
+
+/// Like `process_verification_request`, but reuses the verifiers of the given
+/// store and puts the verifier used for this request back into it. The
+/// verification is aborted if the request gets interrupted.
+pub fn process_verification_request_with_verifiers<'v, 't: 'v>(
+    verification_context: &'v VerificationContext<'t>,
+    request: VerificationRequest,
     cache: impl Cache,
-) -> viper::VerificationResult {
+    verifiers: &mut VerifierStore<'v>,
+    cancellation: &Cancellation,
+) -> (viper::VerificationResult, VerificationStats) {
+    let start = Instant::now();
+    let (result, cache_usage, configuration) = process_request(
+        verification_context,
+        request,
+        cache,
+        verifiers,
+        cancellation,
+    );
+    let stats = VerificationStats {
+        cache_usage,
+        duration: start.elapsed(),
+        configuration,
+    };
+    (result, stats)
+}
+
+fn process_request<'v, 't: 'v>(
+    verification_context: &'v VerificationContext<'t>,
+    mut request: VerificationRequest,
+    mut cache: impl Cache,
+    verifiers: &mut VerifierStore<'v>,
+    cancellation: &Cancellation,
+) -> (viper::VerificationResult, CacheUsage, Option<String>) {
+    if let Some(interruption) = cancellation.interruption() {
+        info!(
+            "Skipping interrupted request for program {}",
+            request.program.get_name()
+        );
+        return (interruption.into(), CacheUsage::Miss, None);
+    }
+
     let ast_utils = verification_context.new_ast_utils();
 
     // Only for testing: Check that the normalization is reversible.
@@ -44,19 +186,18 @@ pub fn process_verification_request<'v, 't: 'v>(
         request.program.get_name()
     );
 
-    let build_or_dump_viper_program = || {
+    let build_or_dump_viper_program = |program: &Program| {
         let mut stopwatch = Stopwatch::start("prusti-server", "construction of JVM objects");
         let ast_factory = verification_context.new_ast_factory();
-        let viper_program = request
-            .program
-            .to_viper(prusti_common::vir::LoweringContext::default(), &ast_factory);
+        let viper_program =
+            program.to_viper(prusti_common::vir::LoweringContext::default(), &ast_factory);
 
         if config::dump_viper_program() {
             stopwatch.start_next("dumping viper program");
             dump_viper_program(
                 &ast_utils,
                 viper_program,
-                &request.program.get_name_with_check_mode(),
+                &program.get_name_with_check_mode(),
             );
         }
 
@@ -73,15 +214,15 @@ pub fn process_verification_request<'v, 't: 'v>(
         // Some tests need the dump to report a diff of the Viper programs.
         if config::dump_viper_program() {
             ast_utils.with_local_frame(16, || {
-                let _ = build_or_dump_viper_program();
+                let _ = build_or_dump_viper_program(&request.program);
             });
         }
-        return viper::VerificationResult::Success;
+        return (viper::VerificationResult::Success, CacheUsage::Miss, None);
     }
 
     // Early return in case of cache hit
     if config::enable_cache() {
-        if let Some(mut result) = cache.get(hash) {
+        if let Some((mut result, configuration)) = cache.get_with_configuration(hash) {
             info!(
                 "Using cached result {:?} for program {}",
                 &result,
@@ -89,40 +230,296 @@ pub fn process_verification_request<'v, 't: 'v>(
             );
             if config::dump_viper_program() {
                 ast_utils.with_local_frame(16, || {
-                    let _ = build_or_dump_viper_program();
+                    let _ = build_or_dump_viper_program(&request.program);
                 });
             }
             normalization_info.denormalize_result(&mut result);
-            return result;
+            return (result, CacheUsage::Hit, configuration);
         }
     };
 
-    ast_utils.with_local_frame(16, || {
-        let viper_program = build_or_dump_viper_program();
-        let program_name = request.program.get_name();
+    // Only verify the methods whose results are not cached yet.
+    let sliced_program = if config::enable_cache() {
+        SlicedProgram::new(&request)
+    } else {
+        None
+    };
+    let mut verified_slices = vec![];
+    let mut cached_slice_results = vec![];
+    if let Some(sliced_program) = &sliced_program {
+        let mut cached_results = sliced_program.cached_results(&mut cache);
+        verified_slices = sliced_program.slices_to_verify(&cached_results);
+        if verified_slices.is_empty() {
+            let mut result = merge_results(cached_results.into_iter().flatten());
+            info!(
+                "Using cached results of all methods of program {}",
+                request.program.get_name()
+            );
+            if config::dump_viper_program() {
+                ast_utils.with_local_frame(16, || {
+                    let _ = build_or_dump_viper_program(&request.program);
+                });
+            }
+            cache.insert(hash, request.program.get_name(), result.clone());
+            normalization_info.denormalize_result(&mut result);
+            return (result, CacheUsage::Hit, None);
+        }
+        for &index in &verified_slices {
+            cached_results[index] = None;
+        }
+        cached_slice_results = cached_results.into_iter().flatten().collect();
+        if !cached_slice_results.is_empty() {
+            info!(
+                "Using cached results of {} methods of program {}",
+                cached_slice_results.len(),
+                request.program.get_name()
+            );
+            sliced_program.restrict_program(&mut request.program, &verified_slices);
+        }
+    }
 
-        // Create a new verifier each time.
-        // Workaround for https://github.com/viperproject/prusti-dev/issues/744
+    let (mut result, configuration) = if request.portfolio.is_empty() {
+        // Wait for the other verifications, e.g. the configurations of
+        // portfolios, to leave room for this one.
+        let _slot = match VerificationSlot::acquire(cancellation) {
+            Ok(slot) => slot,
+            Err(interruption) => return (interruption.into(), CacheUsage::Miss, None),
+        };
+
+        // The verifier has to be created outside of the local frame below,
+        // because it might outlive the request in the verifier store.
+        let program_name = request.program.get_name();
         let mut stopwatch = Stopwatch::start("prusti-server", "verifier startup");
-        let mut verifier =
-            new_viper_verifier(program_name, verification_context, request.backend_config);
+        let mut verifier = verifiers.take(&request.backend_config).unwrap_or_else(|| {
+            new_viper_verifier(
+                program_name,
+                verification_context,
+                request.backend_config.clone(),
+            )
+        });
+
+        if let Err(interruption) = cancellation.start(verifier.stopper()) {
+            if can_reuse_verifiers() {
+                verifiers.put(request.backend_config.clone(), verifier);
+            }
+            return (interruption.into(), CacheUsage::Miss, None);
+        }
 
         stopwatch.start_next("verification");
-        let mut result = verifier.verify(viper_program);
+        let result = ast_utils.with_local_frame(16, || {
+            let viper_program = build_or_dump_viper_program(&request.program);
+            verifier.verify(viper_program)
+        });
+        stopwatch.finish();
 
-        // Don't cache Java exceptions, which might be due to misconfigured paths.
-        if config::enable_cache() && !matches!(result, VerificationResult::JavaException(_)) {
+        if let Err(interruption) = cancellation.finish() {
+            // The verifier has been stopped, so it can't be reused.
             info!(
-                "Storing new cached result {:?} for program {}",
-                &result,
+                "Verification of program {} was interrupted: {:?}",
+                request.program.get_name(),
+                interruption
+            );
+            return (interruption.into(), CacheUsage::Miss, None);
+        }
+
+        // Don't reuse verifiers that raised Java exceptions, nor verifiers that
+        // were set up to log to program-specific locations.
+        if can_reuse_verifiers() && !matches!(result, VerificationResult::JavaException(_)) {
+            verifiers.put(request.backend_config.clone(), verifier);
+        }
+        (result, None)
+    } else {
+        // Only the methods whose results are not cached are raced.
+        let (result, winner) = verify_portfolio(verification_context, &request, cancellation);
+        if let Some(configuration) = &winner {
+            info!(
+                "Configuration {} won the portfolio for program {}",
+                configuration,
                 request.program.get_name()
             );
-            cache.insert(hash, result.clone());
         }
+        (result, winner)
+    };
+
+    let cache_usage = if cached_slice_results.is_empty() {
+        CacheUsage::Miss
+    } else {
+        CacheUsage::PartialHit
+    };
+    if let Some(sliced_program) = &sliced_program {
+        sliced_program.store_result(
+            &mut cache,
+            &verified_slices,
+            &result,
+            configuration.as_deref(),
+        );
+        result = merge_results(iter::once(result).chain(cached_slice_results));
+    }
+
+    // Don't cache Java exceptions, which might be due to misconfigured paths,
+    // nor the cancellations and time-outs of the verification, whose results
+    // say nothing about the program.
+    if config::enable_cache()
+        && !matches!(result, VerificationResult::JavaException(_))
+        && !result.is_interrupted()
+    {
+        info!(
+            "Storing new cached result {:?} for program {}",
+            &result,
+            request.program.get_name()
+        );
+        match &configuration {
+            Some(configuration) => cache.insert_with_configuration(
+                hash,
+                request.program.get_name(),
+                result.clone(),
+                configuration,
+            ),
+            None => cache.insert(hash, request.program.get_name(), result.clone()),
+        };
+    }
+
+    normalization_info.denormalize_result(&mut result);
+    (result, cache_usage, configuration)
+}
+
+/// Verifies the (normalized) program of the request with each configuration
+/// of its portfolio concurrently, each in its own thread attached to the JVM.
+/// Like other verifications, each configuration waits for a verification slot,
+/// so that the portfolios do not exceed `SERVER_MAX_CONCURRENCY`.
+/// Returns the first conclusive result, i.e. a success, a verification
+/// failure or consistency errors, together with the name of the configuration
+/// that produced it. The verification with the other configurations is then
+/// stopped. If no result is conclusive, the first one is returned.
+fn verify_portfolio(
+    verification_context: &VerificationContext,
+    request: &VerificationRequest,
+    cancellation: &Cancellation,
+) -> (VerificationResult, Option<String>) {
+    let viper = verification_context.viper();
+    let cancellations: Vec<Cancellation> = request
+        .portfolio
+        .iter()
+        .map(|_| Cancellation::default())
+        .collect();
+    let (result_sender, result_receiver) = mpsc::channel();
+
+    let (outcome, panic_payload) = thread::scope(|scope| {
+        for (index, portfolio_config) in request.portfolio.iter().enumerate() {
+            let variant = VerificationRequest {
+                program: request.program.clone(),
+                backend_config: portfolio_config.backend_config.clone(),
+                id: format!("{}-{}", request.id, index),
+                timeout: request.timeout,
+                portfolio: vec![],
+            };
+            let result_sender = result_sender.clone();
+            let viper = &viper;
+            let variant_cancellation = &cancellations[index];
+            scope.spawn(move || {
+                let verification_context = viper.attach_current_thread();
+                // Verifiers are not reused, since they are stopped whenever
+                // another configuration wins.
+                let mut verifiers = VerifierStore::new(0);
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                    process_request(
+                        &verification_context,
+                        variant,
+                        NoCache,
+                        &mut verifiers,
+                        variant_cancellation,
+                    )
+                    .0
+                }));
+                // The portfolio might already have a result.
+                let _ = result_sender.send((index, outcome));
+            });
+        }
+        drop(result_sender);
+
+        let mut first_result = None;
+        let mut outcome = None;
+        let mut panic_payload = None;
+        loop {
+            match result_receiver.recv_timeout(PORTFOLIO_POLL_INTERVAL) {
+                Ok((index, Ok(result))) => {
+                    if is_conclusive(&result) {
+                        outcome = Some((result, Some(request.portfolio[index].name.clone())));
+                        break;
+                    }
+                    first_result.get_or_insert(result);
+                }
+                Ok((index, Err(payload))) => {
+                    if let Some(interruption) = cancellations[index].interruption() {
+                        // Stopping the verifier made the verification fail.
+                        first_result.get_or_insert(interruption.into());
+                    } else {
+                        panic_payload = Some(payload);
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some(interruption) = cancellation.interruption() {
+                        outcome = Some((interruption.into(), None));
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        // Stop the configurations that are still running. The scope waits for
+        // their threads to finish.
+        for variant_cancellation in &cancellations {
+            variant_cancellation.interrupt(Interruption::Cancelled);
+        }
+        let outcome = outcome.unwrap_or_else(|| {
+            let result = first_result.unwrap_or(VerificationResult::Cancelled);
+            (result, None)
+        });
+        (outcome, panic_payload)
+    });
+
+    if let Some(payload) = panic_payload {
+        panic::resume_unwind(payload);
+    }
+    outcome
+}
+
+/// Whether a result says something about the program, unlike interruptions and
+/// Java exceptions, which might be specific to one configuration.
+fn is_conclusive(result: &VerificationResult) -> bool {
+    matches!(
+        result,
+        VerificationResult::Success
+            | VerificationResult::Failure(_)
+            | VerificationResult::ConsistencyErrors(_)
+    )
+}
+
+/// The cache of the configurations of a portfolio, whose results are only
+/// cached for the whole portfolio.
+struct NoCache;
+
+impl Cache for NoCache {
+    fn get(&mut self, _request: u64) -> Option<VerificationResult> {
+        None
+    }
+    fn insert(
+        &mut self,
+        _request: u64,
+        _program_name: &str,
+        _result: VerificationResult,
+    ) -> Option<VerificationResult> {
+        None
+    }
+}
 
-        normalization_info.denormalize_result(&mut result);
-        result
-    })
+/// Verifiers are only reused when the server is configured to store some, and
+/// never when their output is dumped or wrapped.
+fn can_reuse_verifiers() -> bool {
+    config::server_max_stored_verifiers().unwrap_or(0) > 0
+        && !config::dump_debug_info()
+        && !config::use_smt_wrapper()
 }
 
 fn dump_viper_program(ast_utils: &viper::AstUtils, program: viper::Program, program_name: &str) {
@@ -137,6 +534,10 @@ fn new_viper_verifier<'v, 't: 'v>(
     verification_context: &'v viper::VerificationContext<'t>,
     backend_config: ViperBackendConfig,
 ) -> viper::Verifier<'v> {
+    // The lock also protects the environment variables set below.
+    let _startup_guard = VERIFIER_STARTUP_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
     let mut verifier_args: Vec<String> = backend_config.verifier_args;
     let report_path: Option<PathBuf>;
     if config::dump_debug_info() {
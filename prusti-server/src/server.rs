@@ -4,22 +4,39 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{process_verification_request, VerificationRequest};
+use crate::{
+    load_persistent_cache,
+    streaming::{encode_frame, StreamedVerificationResult},
+    ServerBusy, VerificationPool, VerificationRequest,
+};
 use log::info;
 use prusti_common::{config, Stopwatch};
 use std::{
+    convert::Infallible,
     net::{Ipv4Addr, SocketAddr},
     sync::{mpsc, Arc, Mutex},
     thread,
+    time::Instant,
+};
+use tokio::{runtime::Builder, sync::mpsc as tokio_mpsc};
+use viper::Viper;
+use warp::{
+    http::{Response, StatusCode},
+    hyper::Body,
+    Filter,
 };
-use tokio::runtime::Builder;
-use viper::{PersistentCache, Viper};
-use warp::Filter;
 
 #[derive(Debug)]
 struct BincodeReject(bincode::Error);
 impl warp::reject::Reject for BincodeReject {}
 
+impl warp::reject::Reject for ServerBusy {}
+
+/// The worker verifying the request stopped without producing a result.
+#[derive(Debug)]
+struct VerificationAborted;
+impl warp::reject::Reject for VerificationAborted {}
+
 pub fn start_server_on_port(port: u16) {
     listen_on_port_with_address_callback(port, move |address| {
         if port == 0 {
@@ -57,23 +74,29 @@ where
     ));
     stopwatch.finish();
 
-    let cache_data = PersistentCache::load_cache(config::cache_path());
-    let cache = Arc::new(Mutex::new(cache_data));
-    let build_verification_request_handler = |viper_arc: Arc<Viper>, cache| {
+    let cache = Arc::new(Mutex::new(load_persistent_cache()));
+    let pool = Arc::new(VerificationPool::new(viper, cache.clone()));
+    let build_verification_request_handler = |pool: Arc<VerificationPool>| {
         move |request: VerificationRequest| {
-            let stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
-            let viper_thread = viper_arc.attach_current_thread();
-            stopwatch.finish();
-            process_verification_request(&viper_thread, request, &cache)
+            let submission = pool.submit(request);
+            async move {
+                match submission {
+                    Ok(result_receiver) => result_receiver
+                        .await
+                        .map(|(result, _)| result)
+                        .map_err(|_| warp::reject::custom(VerificationAborted)),
+                    Err(server_busy) => {
+                        info!("Rejecting verification request: all workers are busy");
+                        Err(warp::reject::custom(server_busy))
+                    }
+                }
+            }
         }
     };
 
     let json_verify = warp::path!("json" / "verify")
         .and(warp::body::json())
-        .map(build_verification_request_handler(
-            viper.clone(),
-            cache.clone(),
-        ))
+        .and_then(build_verification_request_handler(pool.clone()))
         .map(|response| warp::reply::json(&response));
 
     let bincode_verify = warp::path!("bincode" / "verify")
@@ -84,13 +107,54 @@ where
                 warp::reject::custom(BincodeReject(err))
             })
         })
-        .map(build_verification_request_handler(viper, cache.clone()))
+        .and_then(build_verification_request_handler(pool.clone()))
         .map(|result| {
             warp::http::Response::new(
                 bincode::serialize(&result).expect("could not encode verification result"),
             )
         });
 
+    let json_pool = pool.clone();
+    let json_verify_stream = warp::path!("json" / "verify_stream")
+        .and(warp::body::json())
+        .map(move |requests| {
+            stream_verification_results(json_pool.clone(), requests, |result| {
+                serde_json::to_vec(result).expect("could not encode verification result")
+            })
+        });
+
+    let bincode_verify_stream = warp::path!("bincode" / "verify_stream")
+        .and(warp::body::bytes())
+        .and_then(|buf: warp::hyper::body::Bytes| async move {
+            bincode::deserialize(&buf).map_err(|err| {
+                info!("request bincode body error: {}", err);
+                warp::reject::custom(BincodeReject(err))
+            })
+        })
+        .map(move |requests| {
+            stream_verification_results(pool.clone(), requests, |result| {
+                bincode::serialize(result).expect("could not encode verification result")
+            })
+        });
+
+    let cancel_pool = pool.clone();
+    let cancel =
+        warp::post()
+            .and(warp::path!("cancel" / String))
+            .and_then(move |request_id: String| {
+                let pool = cancel_pool.clone();
+                async move {
+                    let found = tokio::task::spawn_blocking(move || pool.cancel(&request_id))
+                        .await
+                        .expect("cancelling the request panicked");
+                    Ok::<_, Infallible>(if found {
+                        warp::reply::with_status("Cancelled", StatusCode::OK)
+                    } else {
+                        warp::reply::with_status("Unknown request", StatusCode::NOT_FOUND)
+                    })
+                }
+            });
+
     let save_cache = warp::post()
         .and(warp::path("save"))
         .and(warp::path::end())
@@ -99,11 +163,17 @@ where
             warp::reply::html("Saved")
         });
 
-    let endpoints = json_verify.or(bincode_verify).or(save_cache);
+    let endpoints = json_verify
+        .or(bincode_verify)
+        .or(json_verify_stream)
+        .or(bincode_verify_stream)
+        .or(cancel)
+        .or(save_cache)
+        .recover(handle_rejection);
 
-    // Here we use a single thread because
-    // 1. Viper is not thread safe yet (Silicon issue #578), and
-    // 2. By default Silicon already uses as many cores as possible.
+    // The runtime only handles the communication with the clients, so a
+    // single thread suffices. The requests are verified by the workers of the
+    // verification pool.
     let runtime = Builder::new_current_thread()
         .thread_name("prusti-server")
         .enable_all()
@@ -124,3 +194,73 @@ where
 
     unreachable!("The server unexpectedly stopped.");
 }
+
+/// Dispatches a batch of requests to the verification pool, streaming back
+/// the results in the order in which they are finished.
+fn stream_verification_results(
+    pool: Arc<VerificationPool>,
+    requests: Vec<VerificationRequest>,
+    encode: fn(&StreamedVerificationResult) -> Vec<u8>,
+) -> Response<Body> {
+    // The timeouts of the requests run from the reception of the batch.
+    let received = Instant::now();
+    let request_ids: Vec<_> = requests.iter().map(|request| request.id.clone()).collect();
+    let (result_sender, mut result_receiver) = tokio_mpsc::unbounded_channel();
+    let submitting_pool = pool.clone();
+    tokio::spawn(async move {
+        let pool = submitting_pool;
+        for (request_index, request) in requests.into_iter().enumerate() {
+            if result_sender.is_closed() {
+                // The client disconnected, so don't submit the remaining requests.
+                break;
+            }
+            let program_name = request.program.get_name().to_string();
+            let result_receiver = pool.submit_waiting(request, received).await;
+            let result_sender = result_sender.clone();
+            tokio::spawn(async move {
+                let (result, stats) = result_receiver.await.ok().unzip();
+                let result = StreamedVerificationResult {
+                    request_index,
+                    program_name,
+                    result,
+                    stats,
+                };
+                // The body might have been dropped in the meantime.
+                let _ = result_sender.send(result);
+            });
+        }
+    });
+
+    let (mut body_sender, body) = Body::channel();
+    tokio::spawn(async move {
+        while let Some(result) = result_receiver.recv().await {
+            let frame = encode_frame(&encode(&result));
+            if body_sender.send_data(frame.into()).await.is_err() {
+                info!("The client stopped receiving the verification results");
+                tokio::task::spawn_blocking(move || {
+                    for request_id in request_ids {
+                        pool.cancel(&request_id);
+                    }
+                });
+                break;
+            }
+        }
+    });
+    Response::new(body)
+}
+
+async fn handle_rejection(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    if rejection.find::<ServerBusy>().is_some() {
+        Ok(warp::reply::with_status(
+            "server busy",
+            StatusCode::SERVICE_UNAVAILABLE,
+        ))
+    } else if rejection.find::<VerificationAborted>().is_some() {
+        Ok(warp::reply::with_status(
+            "verification aborted",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ))
+    } else {
+        Err(rejection)
+    }
+}
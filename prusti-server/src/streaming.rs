@@ -0,0 +1,79 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The results of a batch of verification requests are streamed back to the
+//! client as they become available. Each result is sent as a frame, which
+//! consists of the length of the encoded result (as a big-endian `u64`)
+//! followed by the result encoded as JSON or bincode.
+
+use crate::VerificationStats;
+use std::mem::size_of;
+use viper::VerificationResult;
+
+/// The result of one request of a batch of verification requests.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StreamedVerificationResult {
+    /// The position of the request in the batch.
+    pub request_index: usize,
+    /// The name of the program of the request.
+    pub program_name: String,
+    /// The result of the request, or `None` if its verification was aborted.
+    pub result: Option<VerificationResult>,
+    /// How the result was obtained, if there is one.
+    pub stats: Option<VerificationStats>,
+}
+
+const FRAME_HEADER_LEN: usize = size_of::<u64>();
+
+pub(crate) fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    frame.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Removes the first complete frame from the buffer and returns its payload.
+pub(crate) fn take_frame(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
+    if buffer.len() < FRAME_HEADER_LEN {
+        return None;
+    }
+    let mut header = [0; FRAME_HEADER_LEN];
+    header.copy_from_slice(&buffer[..FRAME_HEADER_LEN]);
+    let payload_len = u64::from_be_bytes(header) as usize;
+    if buffer.len() < FRAME_HEADER_LEN + payload_len {
+        return None;
+    }
+    let payload = buffer[FRAME_HEADER_LEN..FRAME_HEADER_LEN + payload_len].to_vec();
+    buffer.drain(..FRAME_HEADER_LEN + payload_len);
+    Some(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_split_across_chunks() {
+        let mut stream = encode_frame(b"first");
+        stream.extend(encode_frame(b""));
+        stream.extend(encode_frame(b"third"));
+
+        let mut buffer = vec![];
+        let mut payloads = vec![];
+        for chunk in stream.chunks(3) {
+            buffer.extend_from_slice(chunk);
+            while let Some(payload) = take_frame(&mut buffer) {
+                payloads.push(payload);
+            }
+        }
+
+        assert_eq!(
+            payloads,
+            vec![b"first".to_vec(), b"".to_vec(), b"third".to_vec()]
+        );
+        assert!(buffer.is_empty());
+    }
+}
@@ -0,0 +1,264 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    process_verification_request_with_verifiers, Cancellation, Interruption, VerificationRequest,
+    VerificationStats, VerifierStore,
+};
+use log::{error, info, warn};
+use prusti_common::{config, report::verification::CacheUsage, Stopwatch};
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Instant,
+};
+use tokio::{
+    sync::{oneshot, OwnedSemaphorePermit, Semaphore},
+    task::JoinHandle,
+};
+use viper::{PersistentCache, VerificationResult, Viper};
+
+/// The requests that are being verified or waiting to be, by identifier.
+type PendingRequests = Arc<Mutex<HashMap<String, Arc<Cancellation>>>>;
+
+/// The result of a verification request, with how it was obtained.
+pub type VerificationOutcome = (VerificationResult, VerificationStats);
+
+struct VerificationJob {
+    request: VerificationRequest,
+    cancellation: Arc<Cancellation>,
+    result_sender: oneshot::Sender<VerificationOutcome>,
+    /// The task interrupting the request at its deadline, if it has a timeout.
+    /// It is aborted once the job has been processed.
+    deadline: Option<JoinHandle<()>>,
+    /// Released once the job has been processed.
+    _queue_slot: OwnedSemaphorePermit,
+}
+
+/// Returned when all workers are busy and the queue of pending requests is full.
+#[derive(Debug)]
+pub struct ServerBusy;
+
+/// A pool of worker threads attached to the JVM, each verifying one request
+/// at a time with its own set of stored verifiers.
+pub struct VerificationPool {
+    job_sender: mpsc::SyncSender<VerificationJob>,
+    /// One permit for each request that is being verified or waiting to be.
+    queue_slots: Arc<Semaphore>,
+    pending_requests: PendingRequests,
+}
+
+impl VerificationPool {
+    /// Spawns `SERVER_MAX_CONCURRENCY` workers, which share at most
+    /// `SERVER_MAX_STORED_VERIFIERS` stored verifiers between them (none,
+    /// unless the reuse of verifiers is enabled). At most as many requests as
+    /// there are workers will be queued on top of the ones being verified.
+    pub fn new(viper: Arc<Viper>, cache: Arc<Mutex<PersistentCache>>) -> Self {
+        let max_concurrency = max_concurrency();
+        let max_stored_verifiers = config::server_max_stored_verifiers().unwrap_or(0);
+        if 0 < max_stored_verifiers && max_stored_verifiers < max_concurrency {
+            warn!(
+                "Only {} verifiers will be stored for {} concurrent requests; \
+                some workers will have to start a new verifier for each request",
+                max_stored_verifiers, max_concurrency
+            );
+        }
+        info!(
+            "Starting {} verification workers storing up to {} verifiers",
+            max_concurrency, max_stored_verifiers
+        );
+
+        // Sending a job never blocks, because there are at most as many jobs
+        // as there are queue slots.
+        let queue_slots = 2 * max_concurrency;
+        let (job_sender, job_receiver) = mpsc::sync_channel(queue_slots);
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let pending_requests = PendingRequests::default();
+        for worker_id in 0..max_concurrency {
+            // Distribute the stored verifiers as evenly as possible.
+            let stored_verifiers = max_stored_verifiers / max_concurrency
+                + usize::from(worker_id < max_stored_verifiers % max_concurrency);
+            let viper = viper.clone();
+            let cache = cache.clone();
+            let job_receiver = job_receiver.clone();
+            let pending_requests = pending_requests.clone();
+            thread::Builder::new()
+                .name(format!("prusti-server-worker-{}", worker_id))
+                .spawn(move || {
+                    run_worker(
+                        &viper,
+                        &cache,
+                        &job_receiver,
+                        &pending_requests,
+                        stored_verifiers,
+                    )
+                })
+                .expect("failed to spawn verification worker");
+        }
+
+        VerificationPool {
+            job_sender,
+            queue_slots: Arc::new(Semaphore::new(queue_slots)),
+            pending_requests,
+        }
+    }
+
+    /// Queues a request, returning a receiver for its result. Fails without
+    /// blocking if the queue is full.
+    pub fn submit(
+        &self,
+        request: VerificationRequest,
+    ) -> Result<oneshot::Receiver<VerificationOutcome>, ServerBusy> {
+        let queue_slot = self
+            .queue_slots
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| ServerBusy)?;
+        Ok(self.enqueue(request, queue_slot, Instant::now()))
+    }
+
+    /// Queues a request as soon as there is room in the queue, returning a
+    /// receiver for its result. The timeout of the request runs from the given
+    /// time of its reception, which includes the time spent waiting for room.
+    pub async fn submit_waiting(
+        &self,
+        request: VerificationRequest,
+        received: Instant,
+    ) -> oneshot::Receiver<VerificationOutcome> {
+        let queue_slot = self
+            .queue_slots
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the queue slots are never closed");
+        self.enqueue(request, queue_slot, received)
+    }
+
+    /// Interrupts a request that is being verified or waiting to be.
+    /// Returns whether such a request was found.
+    ///
+    /// Stopping a running verifier blocks, so this should not be called from
+    /// an asynchronous context.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        let cancellation = self
+            .pending_requests
+            .lock()
+            .unwrap()
+            .get(request_id)
+            .cloned();
+        if let Some(cancellation) = cancellation {
+            info!("Cancelling verification request {}", request_id);
+            cancellation.interrupt(Interruption::Cancelled);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Must be called from within the Tokio runtime, which enforces the
+    /// timeout of the request, counted from the time it was received.
+    fn enqueue(
+        &self,
+        request: VerificationRequest,
+        queue_slot: OwnedSemaphorePermit,
+        received: Instant,
+    ) -> oneshot::Receiver<VerificationOutcome> {
+        let cancellation = Arc::new(Cancellation::default());
+        self.pending_requests
+            .lock()
+            .unwrap()
+            .insert(request.id.clone(), cancellation.clone());
+        let deadline = request.timeout.map(|timeout| {
+            let cancellation = cancellation.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep_until((received + timeout).into()).await;
+                tokio::task::spawn_blocking(move || cancellation.interrupt(Interruption::TimedOut));
+            })
+        });
+
+        let (result_sender, result_receiver) = oneshot::channel();
+        let job = VerificationJob {
+            request,
+            cancellation,
+            result_sender,
+            deadline,
+            _queue_slot: queue_slot,
+        };
+        self.job_sender
+            .send(job)
+            .expect("all verification workers stopped");
+        result_receiver
+    }
+}
+
+/// The number of verifications that may run at the same time, i.e. the number
+/// of workers of the pool.
+pub(crate) fn max_concurrency() -> usize {
+    config::server_max_concurrency()
+        .unwrap_or_else(num_cpus::get)
+        .max(1)
+}
+
+fn run_worker(
+    viper: &Viper,
+    cache: &Arc<Mutex<PersistentCache>>,
+    job_receiver: &Mutex<mpsc::Receiver<VerificationJob>>,
+    pending_requests: &PendingRequests,
+    max_stored_verifiers: usize,
+) {
+    let stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
+    let verification_context = viper.attach_current_thread();
+    stopwatch.finish();
+    let mut verifiers = VerifierStore::new(max_stored_verifiers);
+
+    loop {
+        // The lock is released as soon as a job has been received.
+        let job = match job_receiver.lock().unwrap().recv() {
+            Ok(job) => job,
+            Err(_) => break,
+        };
+        let request_id = job.request.id.clone();
+        let request_name = job.request.program.get_name().to_string();
+        let start = Instant::now();
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            process_verification_request_with_verifiers(
+                &verification_context,
+                job.request,
+                cache,
+                &mut verifiers,
+                &job.cancellation,
+            )
+        }));
+        pending_requests.lock().unwrap().remove(&request_id);
+        if let Some(deadline) = &job.deadline {
+            deadline.abort();
+        }
+        match outcome {
+            Ok(outcome) => {
+                // The client might have disconnected in the meantime.
+                let _ = job.result_sender.send(outcome);
+            }
+            Err(_) => {
+                if let Some(interruption) = job.cancellation.interruption() {
+                    // Stopping the verifier made the verification fail.
+                    let stats = VerificationStats {
+                        cache_usage: CacheUsage::Miss,
+                        duration: start.elapsed(),
+                        configuration: None,
+                    };
+                    let _ = job.result_sender.send((interruption.into(), stats));
+                } else {
+                    // Dropping the sender reports the failure to the client.
+                    error!("Verification of program {} panicked", request_name);
+                }
+                // The panic might have left the JVM in an inconsistent state.
+                verifiers.clear();
+            }
+        }
+    }
+}
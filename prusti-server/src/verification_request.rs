@@ -8,19 +8,49 @@ use prusti_common::{config, vir::program::Program};
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
+    time::Duration,
 };
 use viper::{self, VerificationBackend};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Hash)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct VerificationRequest {
     pub program: Program,
     pub backend_config: ViperBackendConfig,
+    /// Identifies the request when cancelling it.
+    pub id: String,
+    /// How long the server may take to verify the request, counting from its
+    /// reception. The request times out once this time has passed.
+    pub timeout: Option<Duration>,
+    /// When not empty, the program is verified concurrently with each of these
+    /// configurations instead of `backend_config`, and the first conclusive
+    /// result is used.
+    pub portfolio: Vec<PortfolioConfig>,
 }
 
 impl VerificationRequest {
+    /// Creates a request with a fresh identifier and the configured timeout.
+    pub fn new(program: Program, backend_config: ViperBackendConfig) -> Self {
+        Self {
+            program,
+            backend_config,
+            id: uuid::Uuid::new_v4().to_string(),
+            timeout: config::server_request_timeout().map(Duration::from_secs),
+            portfolio: if config::portfolio() {
+                PortfolioConfig::from_config()
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    /// The hash used as key of the cache. It only depends on what is verified.
     pub(crate) fn get_hash(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
+        if self.portfolio.is_empty() {
+            (&self.program, &self.backend_config).hash(&mut hasher);
+        } else {
+            (&self.program, &self.portfolio).hash(&mut hasher);
+        }
         hasher.finish()
     }
 }
@@ -73,6 +103,16 @@ impl ViperBackendConfig {
             }
             VerificationBackend::Carbon => {
                 verifier_args.extend(vec!["--disableAllocEncoding".to_string()]);
+                if config::counterexample() {
+                    // The Boogie model is mapped back to Viper by the viper
+                    // crate, see `viper::carbon_counterexample`.
+                    verifier_args.push("--counterexample".to_string());
+                    verifier_args.push("native".to_string());
+                }
+                if let Some(timeout) = config::carbon_timeout() {
+                    verifier_args.push("--timeout".to_string());
+                    verifier_args.push(timeout.to_string());
+                }
             }
         }
         Self {
@@ -80,4 +120,53 @@ impl ViperBackendConfig {
             verifier_args,
         }
     }
+
+    /// Makes Z3 use the given random seed. Only applies to Silicon, whose
+    /// prover arguments are extended.
+    pub fn with_z3_seed(mut self, seed: u32) -> Self {
+        if let Some(index) = self
+            .verifier_args
+            .iter()
+            .position(|arg| arg == "--proverConfigArgs")
+        {
+            if let Some(prover_args) = self.verifier_args.get_mut(index + 1) {
+                prover_args.push_str(&format!(" smt.random_seed={seed} sat.random_seed={seed}"));
+            }
+        }
+        self
+    }
+}
+
+/// One of the configurations with which a program is verified in portfolio
+/// mode, see `VerificationRequest::portfolio`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Eq, PartialEq, Hash)]
+pub struct PortfolioConfig {
+    /// Describes the configuration, e.g. in the verification cache.
+    pub name: String,
+    pub backend_config: ViperBackendConfig,
+}
+
+impl PortfolioConfig {
+    /// The configurations enabled by the `PORTFOLIO_Z3_SEEDS` flag: Silicon,
+    /// Carbon and Silicon with each of the seeds.
+    pub fn from_config() -> Vec<Self> {
+        let mut portfolio = vec![
+            PortfolioConfig {
+                name: "Silicon".to_string(),
+                backend_config: ViperBackendConfig::new(VerificationBackend::Silicon),
+            },
+            PortfolioConfig {
+                name: "Carbon".to_string(),
+                backend_config: ViperBackendConfig::new(VerificationBackend::Carbon),
+            },
+        ];
+        for seed in config::portfolio_z3_seeds() {
+            portfolio.push(PortfolioConfig {
+                name: format!("Silicon (Z3 seed {seed})"),
+                backend_config: ViperBackendConfig::new(VerificationBackend::Silicon)
+                    .with_z3_seed(seed),
+            });
+        }
+        portfolio
+    }
 }
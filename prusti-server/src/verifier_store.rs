@@ -0,0 +1,58 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::ViperBackendConfig;
+use std::collections::VecDeque;
+use viper::Verifier;
+
+/// A bounded collection of started Viper verifiers, kept around so that
+/// subsequent requests with the same backend configuration do not need to pay
+/// the verifier startup cost again.
+///
+/// The verifiers borrow the JVM environment of the thread that created them,
+/// so a store is local to a single thread.
+pub struct VerifierStore<'v> {
+    capacity: usize,
+    /// Least recently used verifiers come first.
+    verifiers: VecDeque<(ViperBackendConfig, Verifier<'v>)>,
+}
+
+impl<'v> VerifierStore<'v> {
+    /// Creates a store holding at most `capacity` verifiers. A capacity of
+    /// zero disables the reuse of verifiers altogether.
+    pub fn new(capacity: usize) -> Self {
+        VerifierStore {
+            capacity,
+            verifiers: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Takes a verifier created with the given configuration out of the store.
+    pub fn take(&mut self, backend_config: &ViperBackendConfig) -> Option<Verifier<'v>> {
+        let index = self
+            .verifiers
+            .iter()
+            .rposition(|(config, _)| config == backend_config)?;
+        self.verifiers.remove(index).map(|(_, verifier)| verifier)
+    }
+
+    /// Puts a verifier back into the store, stopping the least recently used
+    /// one if the store is full.
+    pub fn put(&mut self, backend_config: ViperBackendConfig, verifier: Verifier<'v>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.verifiers.len() >= self.capacity {
+            self.verifiers.pop_front();
+        }
+        self.verifiers.push_back((backend_config, verifier));
+    }
+
+    /// Stops all the stored verifiers.
+    pub fn clear(&mut self) {
+        self.verifiers.clear();
+    }
+}
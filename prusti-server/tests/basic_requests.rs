@@ -1,10 +1,11 @@
 use lazy_static::lazy_static;
 use prusti_common::vir::*;
 use prusti_server::{
-    spawn_server_thread, tokio::runtime::Builder, PrustiClient, VerificationRequest,
-    ViperBackendConfig,
+    spawn_server_thread, tokio::runtime::Builder, PortfolioConfig, PrustiClient,
+    VerificationRequest, ViperBackendConfig,
 };
-use viper::VerificationResult;
+use std::thread;
+use viper::{VerificationBackend, VerificationResult};
 
 lazy_static! {
     // only start the jvm & server once
@@ -42,12 +43,145 @@ fn empty_program() {
     }
 }
 
+#[test]
+fn concurrent_requests() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| thread::spawn(|| process_program(|_| ())))
+        .collect();
+
+    for handle in handles {
+        match handle.join().expect("verification thread panicked") {
+            VerificationResult::Success => {}
+            other => panic!(
+                "concurrently verified program not verified successfully, instead found {:?}",
+                other
+            ),
+        }
+    }
+}
+
+#[test]
+fn streamed_results() {
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+    let requests = vec![
+        build_request(|_| ()),
+        build_request(|program| {
+            program.name = "inconsistent".to_string();
+            program.fields.push(Field {
+                name: "field".to_string(), // reserved identifier
+                typ: Type::Int,
+            });
+        }),
+    ];
+
+    let mut results = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(async {
+            let mut stream = client
+                .verify_stream(requests)
+                .await
+                .expect("Verification requests failed");
+            let mut results = vec![];
+            while let Some(result) = stream.next().await.expect("Receiving results failed") {
+                results.push(result);
+            }
+            results
+        });
+    results.sort_by_key(|result| result.request_index);
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].program_name, "dummy");
+    assert_eq!(results[1].program_name, "inconsistent");
+    match &results[0].result {
+        Some(VerificationResult::Success) => {}
+        other => panic!(
+            "empty program not verified successfully, instead found {:?}",
+            other
+        ),
+    }
+    match &results[1].result {
+        Some(VerificationResult::ConsistencyErrors(errors)) => assert_eq!(errors.len(), 1),
+        other => panic!(
+            "consistency errors not identified, instead found {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn portfolio_request() {
+    let mut request = build_request(|program| {
+        program.fields.push(Field {
+            name: "field".to_string(), // reserved identifier
+            typ: Type::Int,
+        });
+    });
+    request.portfolio = PortfolioConfig::from_config();
+
+    match verify_request(request) {
+        VerificationResult::ConsistencyErrors(errors) => assert_eq!(errors.len(), 1),
+        other => panic!(
+            "consistency errors not identified by the portfolio, instead found {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn z3_seeds_only_apply_to_silicon() {
+    let silicon = ViperBackendConfig::new(VerificationBackend::Silicon);
+    let seeded = silicon.clone().with_z3_seed(7);
+    let prover_args = seeded
+        .verifier_args
+        .iter()
+        .position(|arg| arg == "--proverConfigArgs")
+        .map(|index| &seeded.verifier_args[index + 1])
+        .expect("Silicon has no prover arguments");
+    assert!(prover_args.ends_with(" smt.random_seed=7 sat.random_seed=7"));
+    assert_eq!(silicon.verifier_args.len(), seeded.verifier_args.len());
+
+    let carbon = ViperBackendConfig::new(VerificationBackend::Carbon);
+    assert_eq!(carbon.clone().with_z3_seed(7), carbon);
+}
+
+#[test]
+fn cancel_unknown_request() {
+    let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
+
+    let found = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(client.cancel("unknown-request"))
+        .expect("Cancellation request failed");
+
+    assert!(!found, "an unknown request was cancelled");
+}
+
 fn process_program<F>(configure: F) -> VerificationResult
 where
     F: FnOnce(&mut Program),
 {
+    verify_request(build_request(configure))
+}
+
+fn verify_request(request: VerificationRequest) -> VerificationResult {
     let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
 
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(client.verify(request))
+        .expect("Verification request failed")
+}
+
+fn build_request<F>(configure: F) -> VerificationRequest
+where
+    F: FnOnce(&mut Program),
+{
     let mut program = Program {
         name: "dummy".to_string(),
         domains: vec![],
@@ -59,17 +193,8 @@ where
     };
     configure(&mut program);
 
-    let request = VerificationRequest {
-        program: prusti_common::vir::program::Program::Legacy(program),
-        backend_config: ViperBackendConfig::new(
-            prusti_common::config::viper_backend().parse().unwrap(),
-        ),
-    };
-
-    Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .expect("failed to construct Tokio runtime")
-        .block_on(client.verify(request))
-        .expect("Verification request failed")
+    VerificationRequest::new(
+        prusti_common::vir::program::Program::Legacy(program),
+        ViperBackendConfig::new(prusti_common::config::viper_backend().parse().unwrap()),
+    )
 }
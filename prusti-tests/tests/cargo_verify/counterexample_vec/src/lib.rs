@@ -0,0 +1,7 @@
+extern crate prusti_std;
+
+pub fn check_vec(v: Vec<u32>) {
+    if v.len() == 1 {
+        assert!(v.as_slice()[0] != 5);
+    }
+}
@@ -0,0 +1,50 @@
+use prusti_contracts::*;
+
+#[requires(x > 0)]
+#[ensures(result < x)]
+pub fn decrement(x: u32) -> u32 {
+    x - 1
+}
+
+#[pure]
+pub fn double(x: u32) -> u64 {
+    2 * x as u64
+}
+
+pub fn unspecified() {
+    private();
+    internal::helper();
+}
+
+fn private() {}
+
+mod internal {
+    // Public, but not reachable from outside the crate.
+    pub fn helper() {}
+}
+
+pub mod shapes {
+    use prusti_contracts::*;
+
+    #[invariant(self.side > 0)]
+    pub struct Square {
+        pub side: u32,
+    }
+
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    impl Square {
+        pub fn side(&self) -> u32 {
+            self.side
+        }
+    }
+
+    #[trusted]
+    #[ensures(result == 42)]
+    pub fn answer() -> u32 {
+        42
+    }
+}
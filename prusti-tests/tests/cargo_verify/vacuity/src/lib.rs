@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+#[requires(x > 10)]
+pub fn contradicting_assumption(x: u32) {
+    prusti_assume!(x < 5);
+}
+
+#[trusted]
+#[ensures(false)]
+fn diverging() {}
+
+pub fn contradicting_trusted_callee() {
+    diverging();
+}
+
+pub fn failing() {
+    assert!(false);
+}
@@ -4,7 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use cargo_test_support::{cargo_test, project, symlink_supported};
+use cargo_test_support::{cargo_test, project, symlink_supported, Project};
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -85,6 +85,227 @@ error: could not compile `foo` due to previous error; 1 warning emitted
         .run();
 }
 
+#[cargo_test]
+fn json_verification_report() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "fn verified() { assert!(true); }\n\
+            fn failing() { assert!(false); }\n\
+            fn main() {}\n",
+        )
+        .build();
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_REPORT_FORMAT", "json")
+        .with_status(101)
+        .with_stderr_contains(
+            "[ERROR] [Prusti: verification error] the asserted expression might not hold",
+        )
+        .run();
+
+    let report_dir = p.build_dir().join("verify").join("report");
+    let report_files: Vec<_> = fs::read_dir(&report_dir)
+        .unwrap_or_else(|_| panic!("Failed to read directory {}", report_dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(report_files.len(), 1, "{:?}", report_files);
+    assert_eq!(report_files[0].extension().unwrap(), "json");
+    let report = fs::read_to_string(&report_files[0]).unwrap();
+    for expected in [
+        r#""crate_name": "foo""#,
+        r#""name": "foo::verified""#,
+        r#""status": "verified""#,
+        r#""name": "foo::failing""#,
+        r#""status": "failed""#,
+        r#""kind": "Panic""#,
+        r#""message": "[Prusti: verification error] the asserted expression might not hold""#,
+        r#""file": "src/main.rs""#,
+    ] {
+        assert!(
+            report.contains(expected),
+            "{} not found in the report:\n{}",
+            expected,
+            report
+        );
+    }
+}
+
+#[cargo_test]
+fn verification_summary() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "fn verified() { assert!(true); }\n\
+            fn failing() { assert!(false); }\n\
+            fn main() {}\n",
+        )
+        .build();
+    p.process(cargo_prusti_path())
+        .with_status(101)
+        .with_stdout_contains("CRATE [..] ITEMS [..] VERIFIED [..] FAILED [..]")
+        .with_stdout_contains("Failed items:")
+        .with_stdout_contains("  foo::failing (src/main.rs:2:1)")
+        .with_stdout_contains("Slowest items:")
+        .run();
+
+    // The summary can be disabled.
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SUMMARY", "false")
+        .with_status(101)
+        .with_stdout_does_not_contain("Failed items:")
+        .run();
+}
+
+#[cargo_test]
+fn counterexample_tests() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "fn check(x: u32, b: bool) { assert!(x != 3 || b); }\n\
+            fn main() {}\n",
+        )
+        .build();
+    let tests_dir = p.root().join("counterexample_tests");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_COUNTEREXAMPLE", "true")
+        .env("PRUSTI_COUNTEREXAMPLE_TESTS_DIR", &tests_dir)
+        .with_status(101)
+        .with_stderr_contains("[..]a test replaying the counterexample was written to [..]")
+        .run();
+
+    let test = fs::read_to_string(tests_dir.join("counterexample_check_line_1.rs"))
+        .expect("the counterexample test was not written");
+    assert!(test.contains("    #[test]\n    fn counterexample_check_line_1() {"));
+    assert!(test.contains("        let x = 3u32;"));
+    assert!(test.contains("        let b = false;"));
+    assert!(test.contains("        let _result = crate::check(x, b);"));
+}
+
+#[cargo_test]
+fn counterexample_tests_of_compound_values() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "struct Point { x: i32, y: i32 }\n\
+            enum Shape { Circle(u32), Square(u32) }\n\
+            fn check_point(p: Point) { assert!(p.x != 3 || p.y != 4); }\n\
+            fn check_shape(s: Shape) { if let Shape::Circle(r) = s { assert!(r != 2); } }\n\
+            fn check_slice(s: &[u32]) { if s.len() == 1 { assert!(s[0] != 5); } }\n\
+            fn main() {}\n",
+        )
+        .build();
+    let tests_dir = p.root().join("counterexample_tests");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_COUNTEREXAMPLE", "true")
+        .env("PRUSTI_COUNTEREXAMPLE_TESTS_DIR", &tests_dir)
+        .with_status(101)
+        .with_stderr_contains("[..]a test replaying the counterexample was written to [..]")
+        .run();
+
+    for (file_name, expected) in [
+        (
+            "counterexample_check_point_line_3.rs",
+            "        let p = crate::Point { x: 3i32, y: 4i32 };",
+        ),
+        (
+            "counterexample_check_shape_line_4.rs",
+            "        let s = crate::Shape::Circle { 0: 2u32 };",
+        ),
+        (
+            "counterexample_check_slice_line_5.rs",
+            "        let s = &[5u32];",
+        ),
+    ] {
+        let test = fs::read_to_string(tests_dir.join(file_name))
+            .unwrap_or_else(|_| panic!("the counterexample test {} was not written", file_name));
+        assert!(
+            test.contains(expected),
+            "{} not found in the counterexample test:\n{}",
+            expected,
+            test
+        );
+    }
+}
+
+#[cargo_test]
+fn counterexample_tests_of_vectors() {
+    // The specifications of `Vec` are provided by `prusti-std`.
+    let (p, _) = build_local_project("counterexample_vec");
+    let tests_dir = p.root().join("counterexample_tests");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_COUNTEREXAMPLE", "true")
+        .env("PRUSTI_COUNTEREXAMPLE_TESTS_DIR", &tests_dir)
+        .with_status(101)
+        .with_stderr_contains("[..]a test replaying the counterexample was written to [..]")
+        .run();
+
+    let test = fs::read_to_string(tests_dir.join("counterexample_check_vec_line_5.rs"))
+        .expect("the counterexample test was not written");
+    let expected = "        let v = vec![5u32];";
+    assert!(
+        test.contains(expected),
+        "{} not found in the counterexample test:\n{}",
+        expected,
+        test
+    );
+}
+
+#[cargo_test]
+fn counterexample_json() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "struct Point { x: i32 }\n\
+            fn check(p: Point) { assert!(p.x != 3); }\n\
+            fn main() {}\n",
+        )
+        .build();
+    let json_dir = p.root().join("counterexamples");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_COUNTEREXAMPLE", "true")
+        .env("PRUSTI_COUNTEREXAMPLE_JSON_DIR", &json_dir)
+        .with_status(101)
+        .with_stderr_contains("[..]the counterexample was written to [..].json")
+        .run();
+
+    let files: Vec<_> = fs::read_dir(&json_dir)
+        .expect("the counterexample was not written")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    let json = fs::read_to_string(&files[0]).unwrap();
+    assert!(json.contains(r#""name": "p""#));
+    assert!(json.contains(r#""kind": "struct""#));
+    assert!(json.contains(r#""name": "x""#));
+    assert!(json.contains(r#""value": "3""#));
+    assert!(json.contains(r#""line": 2"#));
+}
+
+#[cargo_test]
+fn counterexample_trace() {
+    let p = project()
+        .file(
+            "src/main.rs",
+            "fn count(n: u32) {\n\
+            let mut i = 0;\n\
+            while i < n {\n\
+            assert!(i != 5);\n\
+            i += 1;\n\
+            }\n\
+            }\n\
+            fn main() {}\n",
+        )
+        .build();
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_COUNTEREXAMPLE", "true")
+        .env("PRUSTI_COUNTEREXAMPLE_TRACE", "true")
+        .with_status(101)
+        .with_stderr_contains("[..]counterexample state before executing this code:")
+        .with_stderr_contains("[..]counterexample state at the start of a loop iteration:")
+        .with_stderr_contains("[..]  i: 5")
+        .run();
+}
+
 /// Test `cargo-prusti` on one of the crates in `test/cargo_verify`.
 ///
 /// Special files and folders in the root of the test crate:
@@ -98,6 +319,29 @@ error: could not compile `foo` due to previous error; 1 warning emitted
 /// For more details on the special syntax allowed in the `output.*` files, check the documentation
 /// of `cargo_test_support`: <https://doc.crates.io/contrib/tests/writing.html>.
 fn test_local_project<T: Into<PathBuf>>(project_name: T) {
+    let (project, project_path) = build_local_project(project_name);
+
+    // Set the expected exit status, stdout and stderr
+    let mut test_builder = project.process(cargo_prusti_path());
+    test_builder.arg("--quiet");
+    let opt_expected_stdout = fs::read_to_string(project_path.join("output.stdout")).ok();
+    let opt_expected_stderr = fs::read_to_string(project_path.join("output.stderr")).ok();
+    if let Some(ref expected_stdout) = opt_expected_stdout {
+        // In some cases, Prusti outputs more macro definitions than needed.
+        // See: https://github.com/viperproject/prusti-dev/pull/762
+        test_builder.with_stdout_contains(expected_stdout);
+    }
+    if let Some(ref expected_stderr) = opt_expected_stderr {
+        test_builder.with_status(101).with_stderr(expected_stderr);
+    }
+
+    // Run the test
+    test_builder.run();
+}
+
+/// Builds a test project linking to one of the crates in `test/cargo_verify`, and fetches its
+/// dependencies. Returns the project and the path of the local crate.
+fn build_local_project<T: Into<PathBuf>>(project_name: T) -> (Project, PathBuf) {
     let mut project_builder = project().no_manifest();
     let relative_project_path = Path::new("tests/cargo_verify").join(project_name.into());
     let project_path = fs::canonicalize(&relative_project_path).unwrap_or_else(|_| {
@@ -155,22 +399,7 @@ fn test_local_project<T: Into<PathBuf>>(project_name: T) {
         .env("CARGO_TARGET_DIR", "target/verify")
         .run();
 
-    // Set the expected exit status, stdout and stderr
-    let mut test_builder = project.process(cargo_prusti_path());
-    test_builder.arg("--quiet");
-    let opt_expected_stdout = fs::read_to_string(project_path.join("output.stdout")).ok();
-    let opt_expected_stderr = fs::read_to_string(project_path.join("output.stderr")).ok();
-    if let Some(ref expected_stdout) = opt_expected_stdout {
-        // In some cases, Prusti outputs more macro definitions than needed.
-        // See: https://github.com/viperproject/prusti-dev/pull/762
-        test_builder.with_stdout_contains(expected_stdout);
-    }
-    if let Some(ref expected_stderr) = opt_expected_stderr {
-        test_builder.with_status(101).with_stderr(expected_stderr);
-    }
-
-    // Run the test
-    test_builder.run();
+    (project, project_path)
 }
 
 #[cargo_test]
@@ -236,6 +465,40 @@ fn test_no_std() {
     test_local_project("test_no_std");
 }
 
+#[cargo_test]
+fn test_vacuity() {
+    test_local_project("vacuity");
+}
+
+#[cargo_test]
+fn test_spec_coverage() {
+    test_local_project("spec_coverage");
+}
+
+#[cargo_test]
+fn spec_coverage_json() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub mod inner { pub fn unspecified() {} }\n\
+            pub struct Point { pub x: i32 }\n",
+        )
+        .build();
+    let json_path = p.root().join("coverage.json");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SPEC_COVERAGE_JSON", &json_path)
+        .env("PRUSTI_NO_VERIFY", "true")
+        .run();
+
+    let json = fs::read_to_string(&json_path).expect("the coverage was not written");
+    assert!(json.contains(r#""path": "foo::inner""#));
+    assert!(json.contains(r#""name": "foo::inner::unspecified""#));
+    assert!(json.contains(r#""specified_functions": 0"#));
+    assert!(json.contains(r#""verified_functions": null"#));
+    assert!(json.contains(r#""name": "foo::Point""#));
+    assert!(json.contains(r#""invariant": false"#));
+}
+
 #[ignore] // Currently broken
 #[cargo_test]
 fn test_veribetrfs() {
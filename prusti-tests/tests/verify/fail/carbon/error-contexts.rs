@@ -0,0 +1,55 @@
+// compile-flags: -Pviper_backend=Carbon
+use prusti_contracts::*;
+
+fn assert_false(x: u32) {
+    assert!(x > 0); //~ ERROR the asserted expression might not hold
+}
+
+#[ensures(result > x)] //~ ERROR postcondition might not hold
+fn postcondition(x: i32) -> i32 {
+    x
+}
+
+#[requires(x > 0)]
+fn callee(x: i32) {}
+
+fn precondition() {
+    callee(0); //~ ERROR precondition might not hold
+}
+
+#[pure]
+#[requires(x != 0)]
+fn pure_callee(x: i32) -> i32 {
+    100 / x
+}
+
+#[ensures(result == pure_callee(x))] //~ ERROR precondition of pure function call might not hold
+fn pure_precondition(x: i32) -> i32 {
+    1
+}
+
+fn panic(x: bool) {
+    if x {
+        panic!(); //~ ERROR panic!(..) statement might be reachable
+    }
+}
+
+fn unreachable(x: bool) {
+    if x {
+        unreachable!(); //~ ERROR unreachable!(..) statement might be reachable
+    }
+}
+
+fn divide_by_zero(x: i32) -> i32 {
+    100 / x //~ ERROR assertion might fail with "attempt to divide by zero"
+}
+
+fn loop_invariant() {
+    let mut x = 0;
+    while x < 100 {
+        body_invariant!(x == 42); //~ ERROR loop invariant might not hold
+        x += 1;
+    }
+}
+
+fn main() {}
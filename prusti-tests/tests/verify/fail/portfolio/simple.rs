@@ -0,0 +1,9 @@
+// compile-flags: -Pportfolio=true
+use prusti_contracts::*;
+
+#[ensures(result > x)] //~ ERROR postcondition might not hold
+fn identity(x: u32) -> u32 {
+    x
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use prusti_contracts::*;
+
+// Without `unsafe_core_proof`, the bodies of lemmas would not be checked to
+// terminate, so this lemma would prove `false`.
+#[lemma]
+#[ensures(false)]
+fn unsound() { unsound() } //~ ERROR lemmas are supported only with `unsafe_core_proof` enabled
+
+fn apply() {
+    prusti_apply!(unsound()); //~ ERROR applying lemmas is supported only with `unsafe_core_proof` enabled
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+// compile-flags: -Pviper_backend=Carbon
+use prusti_contracts::*;
+
+#[requires(x > 0)]
+#[ensures(result >= x)]
+fn callee(x: u32) -> u32 {
+    x
+}
+
+#[pure]
+#[requires(x != 0)]
+fn pure_callee(x: i32) -> i32 {
+    100 / x
+}
+
+#[requires(x != 0)]
+#[ensures(result == pure_callee(x))]
+fn pure_precondition(x: i32) -> i32 {
+    100 / x
+}
+
+fn loop_invariant() {
+    let mut x = 0;
+    while x < 100 {
+        body_invariant!(x < 100);
+        x += 1;
+    }
+}
+
+fn main() {
+    let y = callee(3);
+    assert!(y >= 3);
+}
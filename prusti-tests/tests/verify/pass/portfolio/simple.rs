@@ -0,0 +1,13 @@
+// compile-flags: -Pportfolio=true
+use prusti_contracts::*;
+
+#[requires(x < 100)]
+#[ensures(result == x + 1)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {
+    let y = increment(41);
+    assert!(y == 42);
+}
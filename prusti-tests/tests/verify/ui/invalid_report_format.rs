@@ -0,0 +1,9 @@
+// compile-flags: -Preport_format=xml
+use prusti_contracts::*;
+
+#[ensures(result == x)]
+fn identity(x: u32) -> u32 {
+    x
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+// compile-flags: --deny warnings -Plint_specs=true
+#![allow(unused)]
+use prusti_contracts::*;
+
+#[requires(false)]
+fn unsatisfiable_precondition() {}
+
+#[requires(x > 0 && false)]
+fn unsatisfiable_conjunction(x: u32) {}
+
+#[ensures(true)]
+fn trivial_postcondition() {}
+
+#[trusted]
+fn trusted_without_postcondition() -> u32 {
+    42
+}
+
+// Callers cannot observe anything about a trusted function that neither
+// returns a value nor takes a mutable reference.
+#[trusted]
+fn trusted_without_effect(x: u32) {}
+
+#[requires(x > 0)]
+#[ensures(result > 0)]
+fn satisfiable(x: u32) -> u32 {
+    x
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// compile-flags: --deny warnings -Plint_specs_with_verification=true
+#![allow(unused)]
+use prusti_contracts::*;
+
+// The preconditions contradict each other, which only the verifier notices.
+#[requires(x > 5)]
+#[requires(x < 3)]
+fn unsatisfiable_precondition(x: u32) {}
+
+#[requires(x > 5)]
+#[requires(x < 10)]
+fn satisfiable_precondition(x: u32) {}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// compile-flags: --deny warnings -Pinfer_triggers=false
+#![allow(unused)]
+use prusti_contracts::*;
+
+#[pure]
+fn f(x: i32) -> i32 {
+    x
+}
+
+// Explicit triggers are checked for matching loops even without inference.
+#[requires(forall(|x: i32| f(x) <= f(x + 1), triggers=[(f(x),)]))]
+fn explicit_matching_loop() {}
+
+fn main() {}
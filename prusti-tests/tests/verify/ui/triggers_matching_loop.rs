@@ -0,0 +1,16 @@
+// compile-flags: --deny warnings
+#![allow(unused)]
+use prusti_contracts::*;
+
+#[pure]
+fn f(x: i32) -> i32 {
+    x
+}
+
+#[requires(forall(|x: i32| f(x) == x))]
+fn inferred() {}
+
+#[requires(forall(|x: i32| f(x) <= f(x + 1)))]
+fn matching_loop() {}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// compile-flags: --deny warnings
+#![allow(unused)]
+use prusti_contracts::*;
+
+#[pure]
+fn f(x: i32) -> i32 {
+    x
+}
+
+// Arithmetic is not allowed in triggers, so `f(x + 1)` cannot be a trigger.
+#[requires(forall(|x: i32| f(x + 1) == x + 1))]
+fn no_valid_trigger() {}
+
+// Without any function application, the choice is left to the verifier.
+#[requires(forall(|x: i32| x == x))]
+fn no_function_application() {}
+
+fn main() {}
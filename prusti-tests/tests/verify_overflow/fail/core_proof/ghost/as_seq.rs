@@ -0,0 +1,61 @@
+// compile-flags: -Punsafe_core_proof=true
+
+#![allow(unused)]
+
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+#[requires(a.as_seq() == seq![1, 2, 3])]
+fn array_model(a: [u32; 3]) {
+    prusti_assert!(a.as_seq()[1] == 2);
+    prusti_assert!(a.as_seq().len() == Int::new(3));
+    prusti_assert!(a.as_seq().contains(3));
+}
+
+fn slice_len(s: &[u32]) {
+    prusti_assert!(s.as_seq().len() == Int::new_usize(s.len()));
+}
+
+#[requires(i < s.len())]
+fn slice_lookup(s: &[u32], i: usize) {
+    prusti_assert!(s.as_seq()[i] == s[i]);
+}
+
+#[requires(s.len() > 0)]
+#[ensures(result == s.as_seq()[0])]
+#[ensures(s.as_seq().contains(result))]
+fn first(s: &[u32]) -> u32 {
+    s[0]
+}
+
+fn slice_fails(s: &[u32]) {
+    prusti_assert!(s.as_seq() == Seq::empty()); //~ ERROR: asserted expression might not hold
+}
+
+fn vec_len(v: &Vec<u32>) {
+    prusti_assert!(v.as_seq().len() == Int::new_usize(v.len()));
+}
+
+#[requires(i < v.len())]
+fn vec_lookup(v: &Vec<u32>, i: usize) {
+    prusti_assert!(v.as_seq()[i] == v.as_slice()[i]);
+}
+
+#[requires(v.len() < 10)]
+#[ensures(v.as_seq() == old(v.as_seq()).concat(seq![x]))]
+fn vec_push(v: &mut Vec<u32>, x: u32) {
+    v.push(x);
+}
+
+#[requires(v.len() > 0)]
+#[ensures(v.as_seq() == old(v.as_seq()).take(v.len()))]
+fn vec_pop(v: &mut Vec<u32>) {
+    v.pop();
+}
+
+fn vec_fails(v: &Vec<u32>) {
+    prusti_assert!(v.as_seq() == Seq::empty()); //~ ERROR: asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+// compile-flags: -Punsafe_core_proof=true
+
+#![allow(unused)]
+
+use prusti_contracts::*;
+
+type Map = prusti_contracts::Map<u32, u32>;
+
+fn keys(map: Map, k: u32) {
+    prusti_assert!(Map::empty().keys() == Set::empty());
+    prusti_assert!(map.keys().contains(k) == map.contains(k));
+    prusti_assert!(map![1 => 2, 3 => 4].keys() == set![1, 3]);
+    prusti_assert!(map.insert(k, 0).keys().contains(k));
+}
+
+fn keys_fails() {
+    prusti_assert!(map![1 => 2].keys() == set![2]); //~ ERROR: asserted expression might not hold
+}
+
+fn values(map: Map, k: u32, v: u32) {
+    prusti_assert!(Map::empty().values() == Set::empty());
+    prusti_assert!(map![1 => 2, 3 => 2].values().contains(2));
+    prusti_assert!(map.insert(k, v).values().contains(v));
+}
+
+fn values_fails(map: Map, v: u32) {
+    prusti_assert!(map.values().contains(v)); //~ ERROR: asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,52 @@
+// compile-flags: -Punsafe_core_proof=true
+
+#![allow(unused)]
+
+use prusti_contracts::*;
+
+type Multiset = prusti_contracts::Multiset<u32>;
+
+fn empty_multiset_zero_len() {
+    prusti_assert!(Multiset::empty().len() == Int::new(0));
+}
+
+fn multiset_len() {
+    prusti_assert!(multiset![1, 1].len() == Int::new(2));
+}
+
+fn multiset_len_fails() {
+    prusti_assert!(multiset![1, 1].len() == Int::new(1)); //~ ERROR: asserted expression might not hold
+}
+
+fn count(x: u32) {
+    prusti_assert!(multiset![1, 2, 1].count(1) == Int::new(2));
+    prusti_assert!(Multiset::empty().count(x) == Int::new(0));
+    prusti_assert!(multiset![1, 2].contains(2));
+    prusti_assert!(!multiset![1, 2].contains(3));
+}
+
+fn count_fails() {
+    prusti_assert!(multiset![1, 2, 1].count(1) == Int::new(1)); //~ ERROR: asserted expression might not hold
+}
+
+fn permutation() {
+    prusti_assert!(multiset![1, 2, 3] == multiset![3, 1, 2]);
+}
+
+fn permutation_fails() {
+    prusti_assert!(multiset![1, 1, 2] == multiset![1, 2]); //~ ERROR: asserted expression might not hold
+}
+
+fn operations(a: Multiset, b: Multiset, x: u32) {
+    prusti_assert!(a.union(b).count(x) == a.count(x) + b.count(x));
+    prusti_assert!(a.union(b).len() == a.len() + b.len());
+    prusti_assert!(a.intersection(b).subset(a));
+    prusti_assert!(a.difference(b).subset(a));
+}
+
+#[ensures(result.count(x) == m.count(x) + Int::new(1))]
+fn add(m: Multiset, x: u32) -> Multiset {
+    m.union(multiset![x])
+}
+
+fn main() {}
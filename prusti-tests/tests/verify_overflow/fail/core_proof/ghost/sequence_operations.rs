@@ -0,0 +1,59 @@
+// compile-flags: -Punsafe_core_proof=true
+
+#![allow(unused)]
+
+use prusti_contracts::*;
+
+type Seq = prusti_contracts::Seq<u32>;
+
+fn update() {
+    prusti_assert!(seq![1, 2, 3].update(1, 5) == seq![1, 5, 3]);
+    prusti_assert!(seq![1, 2, 3].update(1, 5)[1] == 5);
+}
+
+#[requires(Int::new_usize(idx) < seq.len())]
+fn update_keeps_len(seq: Seq, idx: usize, val: u32) {
+    prusti_assert!(seq.update(idx, val).len() == seq.len());
+    prusti_assert!(seq.update(idx, val)[idx] == val);
+}
+
+fn update_fails() {
+    prusti_assert!(seq![1, 2, 3].update(0, 5) == seq![1, 2, 3]); //~ ERROR: asserted expression might not hold
+}
+
+fn take_and_drop() {
+    prusti_assert!(seq![1, 2, 3].take(2) == seq![1, 2]);
+    prusti_assert!(seq![1, 2, 3].drop(2) == seq![3]);
+    prusti_assert!(seq![1, 2, 3, 4].subsequence(1, 3) == seq![2, 3]);
+}
+
+#[requires(Int::new_usize(n) <= seq.len())]
+fn take_len(seq: Seq, n: usize) {
+    prusti_assert!(seq.take(n).len() == Int::new_usize(n));
+}
+
+fn take_fails() {
+    prusti_assert!(seq![1, 2, 3].take(1) == seq![1, 2]); //~ ERROR: asserted expression might not hold
+}
+
+fn contains(x: u32) {
+    prusti_assert!(seq![1, 2, 3].contains(2));
+    prusti_assert!(!Seq::empty().contains(x));
+    prusti_assert!(seq![1, 2].concat(seq![x]).contains(x));
+}
+
+fn contains_fails(x: u32) {
+    prusti_assert!(seq![1, 2, 3].contains(x)); //~ ERROR: asserted expression might not hold
+}
+
+fn index_of() {
+    prusti_assert!(seq![4, 5, 6].index_of(5) == Int::new(1));
+    prusti_assert!(seq![4, 5, 5].index_of(5) == Int::new(1));
+    prusti_assert!(seq![4, 5, 6].index_of(7) == Int::new(-1));
+}
+
+fn index_of_fails() {
+    prusti_assert!(seq![5, 5].index_of(5) == Int::new(1)); //~ ERROR: asserted expression might not hold
+}
+
+fn main() {}
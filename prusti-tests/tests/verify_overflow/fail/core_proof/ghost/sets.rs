@@ -0,0 +1,60 @@
+// compile-flags: -Punsafe_core_proof=true
+
+#![allow(unused)]
+
+use prusti_contracts::*;
+
+type Set = prusti_contracts::Set<u32>;
+
+fn empty_set_zero_len() {
+    prusti_assert!(Set::empty().len() == Int::new(0));
+}
+
+fn single_set_len() {
+    prusti_assert!(Set::single(1).len() == Int::new(1));
+    prusti_assert!(set![1, 1].len() == Int::new(1));
+}
+
+fn set_len_fails() {
+    prusti_assert!(set![1, 2].len() == Int::new(1)); //~ ERROR: asserted expression might not hold
+}
+
+fn set_eq() {
+    prusti_assert!(Set::empty() == Set::empty());
+    prusti_assert!(set![1, 2] == set![2, 1]);
+    prusti_assert!(set![1, 1, 2] == set![1, 2]);
+}
+
+fn set_eq_fails() {
+    prusti_assert!(set![1] == set![2]); //~ ERROR: asserted expression might not hold
+}
+
+fn membership(x: u32) {
+    prusti_assert!(set![1, 2, 3].contains(2));
+    prusti_assert!(!Set::empty().contains(x));
+    prusti_assert!(Set::single(x).contains(x));
+}
+
+fn membership_fails(x: u32) {
+    prusti_assert!(set![1, 2].contains(x)); //~ ERROR: asserted expression might not hold
+}
+
+fn operations(a: Set, b: Set, x: u32) {
+    prusti_assert!(a.union(b).contains(x) == (a.contains(x) || b.contains(x)));
+    prusti_assert!(a.intersection(b).contains(x) == (a.contains(x) && b.contains(x)));
+    prusti_assert!(a.difference(b).contains(x) == (a.contains(x) && !b.contains(x)));
+    prusti_assert!(a.intersection(b).subset(a));
+    prusti_assert!(a.subset(a.union(b)));
+}
+
+fn subset_fails(a: Set, b: Set) {
+    prusti_assert!(a.union(b).subset(a)); //~ ERROR: asserted expression might not hold
+}
+
+#[requires(!s.contains(x))]
+#[ensures(result.len() == s.len() + Int::new(1))]
+fn insert_fresh(s: Set, x: u32) -> Set {
+    s.union(set![x])
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+// compile-flags: -Punsafe_core_proof=true
+
+#![allow(unused)]
+
+use prusti_contracts::*;
+
+fn main() {}
+
+#[lemma]
+#[requires(a <= b && b <= c)]
+#[ensures(a <= c)]
+fn le_trans(a: u32, b: u32, c: u32) {}
+
+#[requires(a <= b && b <= c)]
+#[ensures(a <= c)]
+fn apply_lemma(a: u32, b: u32, c: u32) {
+    prusti_apply!(le_trans(a, b, c));
+}
+
+fn lemma_precondition_is_checked(a: u32, b: u32, c: u32) {
+    prusti_apply!(le_trans(a, b, c)); //~ ERROR: precondition might not hold
+}
+
+#[lemma]
+#[ensures(a < a)] //~ ERROR postcondition might not hold.
+fn false_lemma(a: u32) {}
+
+fn lemma_outside_of_ghost_code(a: u32) {
+    le_trans(a, a, a); //~ ERROR: Lemmas can only be applied in ghost code
+}
+
+#[pure]
+#[terminates(Int::new_usize(n))]
+#[ensures(result <= n)]
+fn count(n: usize) -> usize {
+    if n == 0 { 0 }
+    else { count(n - 1) + 1 }
+}
+
+#[lemma]
+#[terminates(Int::new_usize(n))]
+#[ensures(count(n) == n)]
+fn count_is_identity(n: usize) {
+    if n > 0 {
+        prusti_apply!(count_is_identity(n - 1));
+    }
+}
+
+fn induction(n: usize) {
+    prusti_apply!(count_is_identity(n));
+    prusti_assert!(count(n) == n);
+}
+
+#[lemma]
+#[ensures(count(n) == n)]
+fn induction_needs_a_termination_measure(n: usize) {
+    if n > 0 {
+        prusti_apply!(induction_needs_a_termination_measure(n - 1)); //~ ERROR: the termination measure of this call is not necessarily lower
+    }
+}
@@ -0,0 +1,26 @@
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+fn remove_from_empty() {
+    let mut v: Vec<u32> = Vec::new();
+    v.remove(0); //~ ERROR precondition might not hold
+}
+
+fn pop_from_empty() {
+    let mut v: Vec<u32> = Vec::new();
+    assert!(v.pop().is_some()); //~ ERROR the asserted expression might not hold
+}
+
+fn push_wrong_element() {
+    let mut v: Vec<u32> = Vec::new();
+    v.push(1);
+    assert!(v.as_slice()[0] == 2); //~ ERROR the asserted expression might not hold
+}
+
+fn unwrap_none() {
+    let x: Option<u32> = None;
+    x.unwrap(); //~ ERROR precondition might not hold
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use prusti_contracts::*;
+use prusti_contracts::sorting::i32::*;
+
+fn main() {}
+
+#[requires(s.len() >= 2)]
+#[ensures(result <= s[1])] //~ ERROR postcondition might not hold.
+fn unsorted_first(s: &[i32]) -> i32 {
+    s[0]
+}
+
+#[requires(s.len() >= 2 && sorted(s))]
+#[ensures(result <= s[1])]
+fn sorted_first(s: &[i32]) -> i32 {
+    s[0]
+}
+
+#[requires(a.len() == b.len())]
+#[ensures(permutation_of(a, b))] //~ ERROR postcondition might not hold.
+fn same_length_only(a: &[i32], b: &[i32]) {}
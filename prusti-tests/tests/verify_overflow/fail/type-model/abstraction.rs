@@ -0,0 +1,29 @@
+// The specifications over a model computed by an abstraction function are
+// not trusted, but verified against the fields.
+use prusti_contracts::*;
+
+struct Counter {
+    count: u32,
+}
+
+#[model(abstraction = counter_model)]
+struct Counter {
+    value: u32,
+}
+
+#[pure]
+fn counter_model(counter: &Counter) -> CounterModel {
+    CounterModel {
+        value: counter.count,
+    }
+}
+
+impl Counter {
+    #[requires(self.model().value < u32::MAX - 1)]
+    #[ensures(self.model().value == old(self.model().value) + 1)] //~ ERROR postcondition might not hold.
+    fn increment(&mut self) {
+        self.count += 2;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+#[requires(x.is_some())]
+#[ensures(x === Some(result))]
+fn get(x: Option<u32>) -> u32 {
+    x.unwrap()
+}
+
+#[ensures(result == 0 || x.is_some())]
+fn get_or_zero(x: Option<u32>) -> u32 {
+    x.unwrap_or(0)
+}
+
+fn main() {
+    let mut x = Some(3);
+    assert!(x.is_some() && !x.is_none());
+    assert!(get(x) == 3);
+    let y = x.map(|v| v == 3);
+    assert!(y.is_some());
+    let taken = x.take();
+    assert!(taken.is_some() && x.is_none());
+    assert!(get_or_zero(x) == 0);
+    assert!(x.ok_or(()).is_err());
+    let previous = x.replace(4);
+    assert!(previous.is_none() && x.is_some());
+    let none: Option<u32> = None;
+    assert!(none.map_or(7, |v| v) == 7);
+    let or_none = none.or(Some(2));
+    prusti_assert!(or_none === Some(2));
+    let or_some = Some(1).or(Some(2));
+    prusti_assert!(or_some === Some(1));
+}
@@ -0,0 +1,32 @@
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+#[requires(s.len() == 3)]
+#[requires(s[0] == 1 && s[1] == 2 && s[2] == 3)]
+#[ensures(s[0] == 3 && s[1] == 2 && s[2] == 1)]
+fn swap_ends(s: &mut [i32]) {
+    s.swap(0, 2);
+}
+
+#[requires(s.len() == 2)]
+#[requires(s[0] == 1 && s[1] == 2)]
+#[ensures(s[0] == 2 && s[1] == 1)]
+fn reverse_pair(s: &mut [i32]) {
+    s.reverse();
+}
+
+#[requires(!s.is_empty())]
+fn first_and_last(s: &[i32]) {
+    assert!(s.first().is_some());
+    assert!(s.last().is_some());
+}
+
+#[requires(s.len() == 2)]
+fn iter(s: &[i32]) {
+    let iter = s.iter();
+    assert!(iter.as_slice().len() == 2);
+    assert!(iter.as_slice()[1] == s[1]);
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+fn main() {
+    let mut s = String::new();
+    assert!(s.is_empty());
+    s.push('a');
+    assert!(s.len() >= 1 && s.len() <= 4);
+    s.clear();
+    assert!(s.len() == 0);
+}
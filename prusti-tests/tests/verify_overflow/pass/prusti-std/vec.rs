@@ -0,0 +1,62 @@
+// The specifications of `Vec` in `prusti-std` can be used by depending on it.
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+#[requires(v.len() < 10)]
+#[ensures(v.len() == old(v.len()) + 2)]
+fn push_two(v: &mut Vec<u32>) {
+    v.push(1);
+    v.push(2);
+}
+
+#[requires(v.len() == 2)]
+#[requires(v.as_slice()[0] == 1 && v.as_slice()[1] == 2)]
+#[ensures(v.len() == 2)]
+#[ensures(v.as_slice()[0] == 0 && v.as_slice()[1] == 2)]
+fn replace_first(v: &mut Vec<u32>) {
+    let first = v.remove(0);
+    assert!(first == 1);
+    assert!(v.as_slice()[0] == 2);
+    v.insert(0, 0);
+}
+
+#[requires(v.len() < 10)]
+fn push_and_pop(v: &mut Vec<u32>) {
+    let len = v.len();
+    v.push(5);
+    assert!(v.as_slice()[len] == 5);
+    let popped = v.pop();
+    prusti_assert!(popped === Some(5));
+    assert!(v.len() == len);
+}
+
+#[requires(v.len() > 0)]
+fn deref(v: &Vec<u32>) {
+    let s: &[u32] = v;
+    assert!(s.len() == v.len());
+    assert!(s[0] == v.as_slice()[0]);
+}
+
+fn main() {
+    let mut v = Vec::new();
+    assert!(v.is_empty());
+    push_two(&mut v);
+    assert!(v.len() == 2);
+    v.insert(0, 5);
+    assert!(v.len() == 3);
+    v.remove(1);
+    assert!(v.len() == 2);
+    v.swap_remove(0);
+    assert!(v.pop().is_some());
+    assert!(v.pop().is_none());
+
+    let mut other = Vec::with_capacity(3);
+    other.push(7);
+    v.append(&mut other);
+    assert!(v.len() == 1 && other.is_empty());
+    v.truncate(5);
+    assert!(v.len() == 1);
+    v.clear();
+    assert!(v.len() == 0);
+}
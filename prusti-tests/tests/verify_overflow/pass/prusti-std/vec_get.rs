@@ -0,0 +1,28 @@
+// compile-flags: -Penable_ghost_constraints=true
+
+// The specification of `get` applies to the `usize` indices of vectors, which
+// are slices through `Deref`.
+extern crate prusti_std;
+
+use prusti_contracts::*;
+
+#[requires(i < v.len())]
+#[ensures(result == v.as_slice()[i])]
+fn get_element(v: &Vec<u32>, i: usize) -> u32 {
+    match v.get(i) {
+        Some(value) => *value,
+        None => unreachable!(),
+    }
+}
+
+#[ensures(result == (i < v.len()))]
+fn has_element(v: &Vec<u32>, i: usize) -> bool {
+    v.get(i).is_some()
+}
+
+fn main() {
+    let mut v = Vec::new();
+    v.push(7);
+    assert!(get_element(&v, 0) == 7);
+    assert!(!has_element(&v, 1));
+}
@@ -0,0 +1,59 @@
+use prusti_contracts::*;
+use prusti_contracts::sorting::i32::*;
+
+fn main() {}
+
+#[requires(sorted(s))]
+#[ensures(
+    match result {
+        Some(index) => index < s.len() && s[index] == n,
+        None => forall(|k: usize| k < s.len() ==> s[k] != n),
+    }
+)]
+pub fn binary_search(s: &[i32], n: i32) -> Option<usize> {
+    let mut lo = 0;
+    let mut hi = s.len();
+    while lo < hi {
+        body_invariant!(lo < hi && hi <= s.len());
+        body_invariant!(sorted(s));
+        body_invariant!(forall(|k: usize| k < lo ==> s[k] < n));
+        body_invariant!(forall(|k: usize| (hi <= k && k < s.len()) ==> n < s[k]));
+        let mid = lo + (hi - lo) / 2;
+        if s[mid] < n {
+            prusti_assert!(sorted_below(s, mid, n));
+            lo = mid + 1;
+        } else if n < s[mid] {
+            prusti_assert!(sorted_above(s, mid, n));
+            hi = mid;
+        } else {
+            return Some(mid);
+        }
+    }
+    None
+}
+
+#[requires(s.len() >= 2 && sorted(s))]
+#[ensures(result <= s[s.len() - 1])]
+fn first_is_smallest(s: &[i32]) -> i32 {
+    s[0]
+}
+
+#[requires(all_in_range(s, 0, s.len(), 0, 100))]
+#[requires(i < s.len())]
+#[ensures(result <= 100)]
+fn bounded(s: &[i32], i: usize) -> i32 {
+    s[i]
+}
+
+#[requires(a.len() == 2 && b.len() == 2)]
+#[requires(a[0] == b[1] && a[1] == b[0])]
+#[ensures(permutation_of(a, b))]
+fn swapped_pair(a: &[i32], b: &[i32]) {
+    prusti_assert!(swap_permutation(a, b, 0, 1));
+}
+
+#[requires(permutation_of(a, b))]
+#[ensures(permutation_of(b, a))]
+fn permutation_symmetric(a: &[i32], b: &[i32]) {
+    prusti_assert!(permutation_sym(a, b));
+}
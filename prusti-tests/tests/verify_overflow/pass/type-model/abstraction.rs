@@ -0,0 +1,45 @@
+// This test computes the model of a struct with an abstraction function, so
+// that the specifications over the model are verified against the fields.
+use prusti_contracts::*;
+
+struct Counter {
+    count: u32,
+}
+
+#[model(abstraction = counter_model)]
+struct Counter {
+    value: u32,
+}
+
+#[pure]
+fn counter_model(counter: &Counter) -> CounterModel {
+    CounterModel {
+        value: counter.count,
+    }
+}
+
+impl Counter {
+    #[ensures(result.model().value == 0)]
+    fn new() -> Self {
+        Counter { count: 0 }
+    }
+
+    #[requires(self.model().value < u32::MAX)]
+    #[ensures(self.model().value == old(self.model().value) + 1)]
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+
+    #[pure]
+    #[ensures(result == self.model().value)]
+    fn get(&self) -> u32 {
+        self.count
+    }
+}
+
+fn main() {
+    let mut counter = Counter::new();
+    counter.increment();
+    counter.increment();
+    assert!(counter.get() == 2);
+}
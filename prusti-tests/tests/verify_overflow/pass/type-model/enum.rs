@@ -0,0 +1,55 @@
+// This test models the state of a struct with an enum, whose variants are
+// named through the generated `ConnectionModel` alias.
+use prusti_contracts::*;
+
+struct Connection {
+    handle: i32,
+}
+
+#[model]
+enum Connection {
+    Closed,
+    Open { sent: u32 },
+}
+
+#[pure]
+fn is_open(state: ConnectionModel) -> bool {
+    match state {
+        ConnectionModel::Closed => false,
+        ConnectionModel::Open { .. } => true,
+    }
+}
+
+#[pure]
+fn sent(state: ConnectionModel) -> u32 {
+    match state {
+        ConnectionModel::Closed => 0,
+        ConnectionModel::Open { sent } => sent,
+    }
+}
+
+#[trusted]
+#[ensures(is_open(result.model()))]
+#[ensures(sent(result.model()) == 0)]
+fn open() -> Connection {
+    Connection { handle: 1 }
+}
+
+#[trusted]
+#[requires(is_open(conn.model()))]
+#[ensures(is_open(conn.model()))]
+#[ensures(sent(conn.model()) > old(sent(conn.model())))]
+fn send(conn: &mut Connection) {}
+
+#[trusted]
+#[ensures(!is_open(conn.model()))]
+fn close(conn: &mut Connection) {}
+
+fn main() {
+    let mut conn = open();
+    send(&mut conn);
+    send(&mut conn);
+    prusti_assert!(sent(conn.model()) > 1);
+    close(&mut conn);
+    prusti_assert!(!is_open(conn.model()));
+}
@@ -0,0 +1,29 @@
+// This test models a struct with a tuple struct.
+use prusti_contracts::*;
+
+struct Counter {
+    count: u64,
+}
+
+#[model]
+struct Counter(u32, bool);
+
+#[trusted]
+#[ensures(result.model().0 == 0)]
+#[ensures(!result.model().1)]
+fn new_counter() -> Counter {
+    Counter { count: 0 }
+}
+
+#[trusted]
+#[requires(counter.model().0 < 100)]
+#[ensures(counter.model().0 == old(counter.model().0) + 1)]
+#[ensures(counter.model().1)]
+fn increment(counter: &mut Counter) {}
+
+fn main() {
+    let mut counter = new_counter();
+    increment(&mut counter);
+    increment(&mut counter);
+    prusti_assert!(counter.model().0 == 2 && counter.model().1);
+}
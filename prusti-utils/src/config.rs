@@ -8,7 +8,10 @@
 pub mod commandline;
 
 use self::commandline::CommandLine;
-use crate::launch::{find_viper_home, get_current_executable_dir};
+use crate::{
+    launch::{find_viper_home, get_current_executable_dir},
+    report::verification::ReportFormat,
+};
 use ::config::{Config, Environment, File};
 use log::warn;
 use serde::Deserialize;
@@ -69,6 +72,8 @@ lazy_static::lazy_static! {
         // 0. Default values
         settings.set_default("be_rustc", false).unwrap();
         settings.set_default("viper_backend", "Silicon").unwrap();
+        settings.set_default("portfolio", false).unwrap();
+        settings.set_default::<Vec<u32>>("portfolio_z3_seeds", vec![]).unwrap();
         settings.set_default::<Option<String>>("smt_solver_path", env::var("Z3_EXE").ok()).unwrap();
         settings.set_default::<Option<String>>("smt_solver_wrapper_path", None).unwrap();
         settings.set_default::<Option<String>>("boogie_path", env::var("BOOGIE_EXE").ok()).unwrap();
@@ -76,6 +81,7 @@ lazy_static::lazy_static! {
         settings.set_default::<Option<String>>("java_home", None).unwrap();
 
         settings.set_default::<Option<u32>>("check_timeout", None).unwrap();
+        settings.set_default::<Option<u32>>("carbon_timeout", None).unwrap();
         settings.set_default("check_foldunfold_state", false).unwrap();
         settings.set_default("check_overflows", true).unwrap();
         settings.set_default("check_panics", true).unwrap();
@@ -86,6 +92,11 @@ lazy_static::lazy_static! {
         settings.set_default("log_style", "auto").unwrap();
         settings.set_default("log_dir", "log").unwrap();
         settings.set_default("cache_path", "").unwrap();
+        settings.set_default::<Option<u64>>("cache_max_entries", None).unwrap();
+        settings.set_default::<Option<String>>("cache_dir", None).unwrap();
+        settings.set_default::<Option<String>>("cache_seed_dir", None).unwrap();
+        settings.set_default::<Option<String>>("report_format", None).unwrap();
+        settings.set_default("report_dir", "report").unwrap();
         settings.set_default("dump_debug_info", false).unwrap();
         settings.set_default("dump_debug_info_during_fold", false).unwrap();
         settings.set_default("dump_nll_facts", false).unwrap();
@@ -102,6 +113,7 @@ lazy_static::lazy_static! {
         settings.set_default("assert_timeout", 10_000).unwrap();
         settings.set_default("smt_qi_eager_threshold", 1000).unwrap();
         settings.set_default("use_more_complete_exhale", true).unwrap();
+        settings.set_default("infer_triggers", true).unwrap();
         settings.set_default("skip_unsupported_features", false).unwrap();
         settings.set_default("internal_errors_as_warnings", false).unwrap();
         settings.set_default("allow_unreachable_unsupported_code", false).unwrap();
@@ -109,6 +121,9 @@ lazy_static::lazy_static! {
         settings.set_default("no_verify_deps", false).unwrap();
         settings.set_default("full_compilation", false).unwrap();
         settings.set_default("json_communication", false).unwrap();
+        settings.set_default("lint_specs", false).unwrap();
+        settings.set_default("lint_specs_with_verification", false).unwrap();
+        settings.set_default("detect_vacuity", false).unwrap();
         settings.set_default("optimizations", "all").unwrap();
         settings.set_default("intern_names", true).unwrap();
         settings.set_default("enable_purification_optimization", false).unwrap();
@@ -131,7 +146,12 @@ lazy_static::lazy_static! {
         settings.set_default("print_typeckd_specs", false).unwrap();
         settings.set_default("print_collected_verification_items", false).unwrap();
         settings.set_default("hide_uuids", false).unwrap();
+        settings.set_default("spec_coverage", false).unwrap();
+        settings.set_default::<Option<String>>("spec_coverage_json", None).unwrap();
         settings.set_default("counterexample", false).unwrap();
+        settings.set_default::<Option<String>>("counterexample_json_dir", None).unwrap();
+        settings.set_default::<Option<String>>("counterexample_tests_dir", None).unwrap();
+        settings.set_default("counterexample_trace", false).unwrap();
         settings.set_default("print_counterexample_if_model_is_present", false).unwrap();
         settings.set_default::<Option<String>>("save_failing_trace_to_file", None).unwrap();
         settings.set_default::<Option<String>>("execute_only_failing_trace", None).unwrap();
@@ -139,9 +159,13 @@ lazy_static::lazy_static! {
         settings.set_default("print_hash", false).unwrap();
         settings.set_default("enable_cache", true).unwrap();
         settings.set_default("enable_ghost_constraints", false).unwrap();
+        settings.set_default::<Option<u64>>("server_request_timeout", None).unwrap();
 
         settings.set_default("cargo_path", "cargo").unwrap();
         settings.set_default("cargo_command", "check").unwrap();
+        settings.set_default("summary", true).unwrap();
+        settings.set_default("summary_slowest_items", 5).unwrap();
+        settings.set_default::<Option<String>>("summary_dir", None).unwrap();
 
         // Flags for testing.
         settings.set_default::<Option<i64>>("verification_deadline", None).unwrap();
@@ -207,6 +231,7 @@ lazy_static::lazy_static! {
                 .with_list_parse_key("delete_basic_blocks")
                 .with_list_parse_key("extra_jvm_args")
                 .with_list_parse_key("extra_verifier_args")
+                .with_list_parse_key("portfolio_z3_seeds")
                 .with_list_parse_key("verify_only_basic_block_path")
                 .list_separator(" ")
         ).unwrap();
@@ -315,6 +340,19 @@ pub fn viper_backend() -> String {
         .to_string()
 }
 
+/// When enabled, each Viper program is verified concurrently by Silicon, by
+/// Carbon and by Silicon with each of the `PORTFOLIO_Z3_SEEDS`, and the first
+/// success or verification failure is used. `VIPER_BACKEND` is then ignored.
+pub fn portfolio() -> bool {
+    read_setting("portfolio")
+}
+
+/// The random seeds of Z3 with which Silicon additionally verifies each
+/// program when `PORTFOLIO` is enabled.
+pub fn portfolio_z3_seeds() -> Vec<u32> {
+    read_setting("portfolio_z3_seeds")
+}
+
 /// The path to the SMT solver to use. `prusti-rustc` is expected to set this
 /// configuration flag to the correct path to Z3.
 pub fn smt_solver_path() -> String {
@@ -433,6 +471,20 @@ pub fn log_style() -> String {
     read_setting("log_style")
 }
 
+/// The format of the verification report, `json` or `sarif`. If set, the
+/// verification outcome of each item of a crate is written to a report in
+/// `report_dir`. Returns an error if the flag is set to another format.
+pub fn report_format() -> Result<Option<ReportFormat>, String> {
+    read_setting::<Option<String>>("report_format")
+        .map(|format| format.parse())
+        .transpose()
+}
+
+/// Path to the directory in which verification reports will be stored.
+pub fn report_dir() -> PathBuf {
+    PathBuf::from(read_setting::<String>("report_dir"))
+}
+
 /// Path to directory in which log files and dumped output will be stored.
 pub fn log_dir() -> PathBuf {
     PathBuf::from(read_setting::<String>("log_dir"))
@@ -446,6 +498,28 @@ pub fn cache_path() -> PathBuf {
     PathBuf::from(read_setting::<String>("cache_path"))
 }
 
+/// Maximum number of results kept in the verification cache. Beyond this
+/// number, the least recently used results are evicted. By default, the cache
+/// grows without bound.
+pub fn cache_max_entries() -> Option<usize> {
+    read_setting("cache_max_entries")
+}
+
+/// Path to a cache directory, holding one file per cached result. Unlike a
+/// cache file, the directory can be shared by several Prusti processes running
+/// in parallel, which see each other's results immediately. Takes precedence
+/// over `cache_path`.
+pub fn cache_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("cache_dir").map(PathBuf::from)
+}
+
+/// Path to a cache directory from which the results missing in the cache are
+/// taken, e.g. one prepared by a previous CI run. The directory is never
+/// modified.
+pub fn cache_seed_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("cache_seed_dir").map(PathBuf::from)
+}
+
 /// When enabled, binary operations and numeric casts will be checked for
 /// overflows.
 pub fn check_overflows() -> bool {
@@ -502,6 +576,14 @@ pub fn check_timeout() -> Option<u32> {
     read_setting("check_timeout")
 }
 
+/// Maximum time (in seconds) for Boogie to spend on the verification of a
+/// program when using Carbon. Maps to Carbon's command-line argument
+/// `--timeout`; a program whose verification times out is reported as timed
+/// out by the backend, and its result is not cached.
+pub fn carbon_timeout() -> Option<u32> {
+    read_setting("carbon_timeout")
+}
+
 /// When enabled, a more complete `exhale` version is used in the verifier.
 /// See [`consolidate`](https://github.com/viperproject/silicon/blob/f48de7f6e2d90d9020812869c713a5d3e2035995/src/main/scala/rules/StateConsolidator.scala#L29-L46).
 /// Equivalent to the verifier command-line argument
@@ -510,17 +592,39 @@ pub fn use_more_complete_exhale() -> bool {
     read_setting("use_more_complete_exhale")
 }
 
+/// When enabled, Prusti infers the triggers of quantifiers without explicit
+/// triggers, instead of leaving their choice to the verifier, and warns about
+/// quantifiers whose function applications contain no valid trigger. Explicit
+/// triggers that might cause a matching loop are reported regardless of this
+/// flag.
+pub fn infer_triggers() -> bool {
+    read_setting("infer_triggers")
+}
+
 /// When enabled, prints the items collected for verification.
 pub fn print_collected_verification_items() -> bool {
     read_setting("print_collected_verification_items")
 }
 
+/// When enabled, prints a report of which public functions and types of each
+/// module of the crate have specifications.
+pub fn spec_coverage() -> bool {
+    read_setting("spec_coverage")
+}
+
+/// Path of the file to which the specification coverage report is written as
+/// JSON.
+pub fn spec_coverage_json() -> Option<PathBuf> {
+    read_setting::<Option<String>>("spec_coverage_json").map(PathBuf::from)
+}
+
 /// When enabled, prints the AST with desugared specifications.
 pub fn print_desugared_specs() -> bool {
     read_setting("print_desugared_specs")
 }
 
-/// When enabled, prints the type-checked specifications.
+/// When enabled, prints the type-checked specifications and, once the
+/// quantifiers are encoded, the triggers of each quantifier.
 pub fn print_typeckd_specs() -> bool {
     read_setting("print_typeckd_specs")
 }
@@ -537,6 +641,28 @@ pub fn counterexample() -> bool {
     read_setting("counterexample")
 }
 
+/// Path to the directory in which the counterexample of each failing error is
+/// written as JSON, when `COUNTEREXAMPLE` is enabled.
+pub fn counterexample_json_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("counterexample_json_dir").map(PathBuf::from)
+}
+
+/// Path to the directory in which a unit test replaying the counterexample of
+/// each failing function is written, when `COUNTEREXAMPLE` is enabled.
+pub fn counterexample_tests_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("counterexample_tests_dir").map(PathBuf::from)
+}
+
+/// When enabled together with `COUNTEREXAMPLE`, counterexamples report the
+/// values of the local variables at each labelled program point of the failing
+/// path: before each block of code, and at the start and at the end of loop
+/// iterations.
+///
+/// Note: This is supported only by the default encoding.
+pub fn counterexample_trace() -> bool {
+    read_setting("counterexample_trace")
+}
+
 /// When enabled, Prusti will print a counterexample for a model and its original
 /// type
 pub fn print_counterexample_if_model_is_present() -> bool {
@@ -611,33 +737,45 @@ pub fn print_hash() -> bool {
 /// to improve future verification. By default the cache is only saved in
 /// memory (of the `prusti-server` if enabled). For long-running verification
 /// projects use `CACHE_PATH` to save to disk.
+///
+/// The results of the individual Viper methods are cached as well, keyed by
+/// the method and everything it transitively depends on, so that only the
+/// methods that changed are verified again.
 pub fn enable_cache() -> bool {
     read_setting("enable_cache")
 }
 
 /// Maximum amount of instantiated Viper verifiers the server will keep around
-/// for reuse. If not set, defaults to
-/// `SERVER_MAX_CONCURRENT_VERIFICATION_OPERATIONS`. It also doesn't make much
-/// sense to set this option to less than that, since then the server will
-/// likely have to keep creating new verifiers, reducing the performance gained
-/// from reuse.
+/// for reuse. If not set, no verifiers are stored and a new verifier is
+/// created for each request. To enable the reuse of verifiers, set it to
+/// `SERVER_MAX_CONCURRENCY` or more; setting it to less than that doesn't make
+/// much sense, since then the server will likely have to keep creating new
+/// verifiers, reducing the performance gained from reuse.
 ///
 /// **Note:** This does _not_ limit how many verification requests the server
 /// handles concurrently, only the size of what is essentially its verifier
 /// cache.
 pub fn server_max_stored_verifiers() -> Option<usize> {
-    // TODO: default to below in prusti-server
-    // TODO: warn if lower than below
     read_optional_setting("server_max_stored_verifiers")
 }
 
 /// Maximum amount of verification requests the server will work on
 /// concurrently. If not set, defaults to the number of (logical) cores on
-/// the system.
+/// the system. At most as many requests are queued on top of that; further
+/// requests are rejected with a "server busy" response, upon which the client
+/// retries after a delay.
 pub fn server_max_concurrency() -> Option<usize> {
     read_optional_setting("server_max_concurrency")
 }
 
+/// Maximum time (in seconds) the server may take to verify a single program,
+/// counting from the reception of the request. Verification requests that
+/// exceed it are aborted and reported as timed out. If not set, there is no
+/// limit.
+pub fn server_request_timeout() -> Option<u64> {
+    read_setting("server_request_timeout")
+}
+
 /// When set to an address and port (e.g. `"127.0.0.1:2468"`), Prusti will
 /// connect to the given server and use it for its verification backend.
 ///
@@ -653,6 +791,26 @@ pub fn json_communication() -> bool {
     read_setting("json_communication")
 }
 
+/// When enabled, Prusti warns about specifications that are vacuous: trivially
+/// unsatisfiable preconditions, postconditions implied by `true`, and trusted
+/// functions without a postcondition.
+pub fn lint_specs() -> bool {
+    read_setting("lint_specs")
+}
+
+/// When enabled, Prusti additionally verifies whether the precondition of each
+/// verified function is satisfiable, and warns if it is not.
+pub fn lint_specs_with_verification() -> bool {
+    read_setting("lint_specs_with_verification")
+}
+
+/// When enabled, Prusti checks for each verified function whether the end of
+/// the function and the code after each `prusti_assume!` are reachable, and
+/// warns if they are not, because the function is then verified vacuously.
+pub fn detect_vacuity() -> bool {
+    read_setting("detect_vacuity")
+}
+
 /// When enabled, Viper name mangling will be disabled.
 ///
 /// **Note:** This is very likely to result in invalid programs being generated
@@ -1019,6 +1177,24 @@ pub fn cargo_command() -> String {
     read_setting("cargo_command")
 }
 
+/// When enabled, `cargo-prusti` prints a summary of the verification outcome
+/// of the items of the crates it verified.
+pub fn summary() -> bool {
+    read_setting("summary")
+}
+
+/// The number of items that took the longest to verify listed in the summary
+/// printed by `cargo-prusti`.
+pub fn summary_slowest_items() -> usize {
+    read_setting("summary_slowest_items")
+}
+
+/// Path to the directory in which the JSON reports used by the summary of
+/// `cargo-prusti` are collected. Set by `cargo-prusti`.
+pub fn summary_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("summary_dir").map(PathBuf::from)
+}
+
 /// When enabled, type invariants can be declared on types using the
 /// `#[invariant(...)]` attribute.
 pub fn enable_type_invariants() -> bool {
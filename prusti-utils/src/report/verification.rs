@@ -0,0 +1,329 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module defines the machine-readable report of the verification
+//! outcome of each item of a crate, meant for tools such as dashboards and
+//! code-review bots. See the `REPORT_FORMAT` flag.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path, str::FromStr};
+
+/// The formats in which a verification report can be written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A `VerificationReport` serialized as JSON.
+    Json,
+    /// A SARIF 2.1.0 log, with one result per item or per error.
+    Sarif,
+}
+
+impl ReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Sarif => "sarif",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "sarif" => Ok(ReportFormat::Sarif),
+            _ => Err(format!(
+                "unknown report format '{format}', expected 'json' or 'sarif'"
+            )),
+        }
+    }
+}
+
+/// The verification outcome of the items of a crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub crate_name: String,
+    pub items: Vec<ItemReport>,
+}
+
+/// The verification outcome of one item (i.e. a function or method).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemReport {
+    /// The name of the item, prefixed by the crate name.
+    pub name: String,
+    /// The definition path of the item, which is not preserved across
+    /// compilations.
+    pub def_path: String,
+    pub span: Option<SourceSpan>,
+    pub status: ItemStatus,
+    pub errors: Vec<ErrorReport>,
+    /// The time spent verifying the Viper programs of the item, in
+    /// milliseconds.
+    pub time_ms: u64,
+    /// Whether the results of the Viper programs of the item were cached, or
+    /// `None` if no program was verified.
+    pub cache: Option<CacheUsage>,
+    /// The backends that verified the Viper programs of the item.
+    pub backends: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ItemStatus {
+    Verified,
+    Failed,
+    /// The item is trusted, so it was not verified.
+    Trusted,
+    /// The item uses unsupported features, so it was not verified.
+    Unsupported,
+    /// The item was not verified for another reason, e.g. because it is a
+    /// predicate.
+    Skipped,
+}
+
+impl ItemStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemStatus::Verified => "verified",
+            ItemStatus::Failed => "failed",
+            ItemStatus::Trusted => "trusted",
+            ItemStatus::Unsupported => "unsupported",
+            ItemStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// How the result of a verification was obtained from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheUsage {
+    /// The whole result was cached.
+    Hit,
+    /// The results of some of the Viper methods were cached.
+    PartialHit,
+    Miss,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    /// The kind of the error, e.g. the error context of a verification error
+    /// such as `AssertMethodPostcondition`.
+    pub kind: String,
+    pub message: String,
+    pub span: Option<SourceSpan>,
+}
+
+/// A span in a source file, with 1-based lines and columns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl VerificationReport {
+    /// Writes the report in the given format to the given directory, in a file
+    /// named after the given file stem.
+    pub fn write(&self, dir: &Path, file_stem: &str, format: ReportFormat) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.{}", file_stem, format.extension()));
+        let file = io::BufWriter::new(fs::File::create(path)?);
+        match format {
+            ReportFormat::Json => serde_json::to_writer_pretty(file, self)?,
+            ReportFormat::Sarif => serde_json::to_writer_pretty(file, &self.to_sarif())?,
+        }
+        Ok(())
+    }
+
+    /// Converts the report to a SARIF log. Verified items are reported as
+    /// passing results, items that were not verified as not applicable, and
+    /// each error of the other items as a failing result.
+    pub fn to_sarif(&self) -> serde_json::Value {
+        let mut rules: Vec<&str> = self
+            .items
+            .iter()
+            .flat_map(|item| item.errors.iter().map(|error| error.kind.as_str()))
+            .collect();
+        rules.sort_unstable();
+        rules.dedup();
+
+        let mut results = vec![];
+        for item in &self.items {
+            let properties = serde_json::json!({
+                "name": item.name,
+                "defPath": item.def_path,
+                "status": item.status,
+                "timeMs": item.time_ms,
+                "cache": item.cache,
+                "backends": item.backends,
+            });
+            if item.errors.is_empty() {
+                let kind = match item.status {
+                    ItemStatus::Verified => "pass",
+                    _ => "notApplicable",
+                };
+                results.push(serde_json::json!({
+                    "kind": kind,
+                    "level": "none",
+                    "message": { "text": format!("{} is {}", item.name, item.status.name()) },
+                    "locations": sarif_locations(item.span.as_ref()),
+                    "properties": properties,
+                }));
+            }
+            for error in &item.errors {
+                let level = match item.status {
+                    ItemStatus::Unsupported => "warning",
+                    _ => "error",
+                };
+                results.push(serde_json::json!({
+                    "kind": "fail",
+                    "level": level,
+                    "ruleId": error.kind,
+                    "ruleIndex": rules.binary_search(&error.kind.as_str()).unwrap(),
+                    "message": { "text": error.message },
+                    "locations": sarif_locations(error.span.as_ref().or(item.span.as_ref())),
+                    "properties": properties,
+                }));
+            }
+        }
+
+        serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "Prusti",
+                        "informationUri": "https://github.com/viperproject/prusti-dev",
+                        "rules": rules
+                            .iter()
+                            .map(|rule| serde_json::json!({ "id": rule }))
+                            .collect::<Vec<_>>(),
+                    }
+                },
+                "properties": { "crateName": self.crate_name },
+                "results": results,
+            }]
+        })
+    }
+}
+
+fn sarif_locations(span: Option<&SourceSpan>) -> serde_json::Value {
+    match span {
+        Some(span) => serde_json::json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": span.file },
+                "region": {
+                    "startLine": span.line,
+                    "startColumn": span.column,
+                    "endLine": span.end_line,
+                    "endColumn": span.end_column,
+                }
+            }
+        }]),
+        None => serde_json::json!([]),
+    }
+}
+
+/// Reads the JSON reports of a directory, skipping the files that are not
+/// reports.
+pub fn read_reports(dir: &Path) -> io::Result<Vec<VerificationReport>> {
+    let mut reports = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "json")
+        {
+            let file = io::BufReader::new(fs::File::open(&path)?);
+            if let Ok(report) = serde_json::from_reader(file) {
+                reports.push(report);
+            }
+        }
+    }
+    reports.sort_by(|a: &VerificationReport, b| a.crate_name.cmp(&b.crate_name));
+    Ok(reports)
+}
+
+/// Formats a table with the number of items of each crate by status, followed
+/// by the items that failed and the `slowest_items` items that took the
+/// longest to verify.
+pub fn format_summary(reports: &[VerificationReport], slowest_items: usize) -> String {
+    const STATUSES: [ItemStatus; 5] = [
+        ItemStatus::Verified,
+        ItemStatus::Failed,
+        ItemStatus::Trusted,
+        ItemStatus::Unsupported,
+        ItemStatus::Skipped,
+    ];
+    let count_row = |name: &str, items: &[&ItemReport]| {
+        let mut row = format!("{:<30} {:>6}", name, items.len());
+        for status in STATUSES {
+            let count = items.iter().filter(|item| item.status == status).count();
+            row.push_str(&format!(" {:>11}", count));
+        }
+        let cached = items
+            .iter()
+            .filter(|item| item.cache == Some(CacheUsage::Hit))
+            .count();
+        row.push_str(&format!(" {:>11}\n", cached));
+        row
+    };
+
+    let mut summary = format!("{:<30} {:>6}", "CRATE", "ITEMS");
+    for status in STATUSES {
+        summary.push_str(&format!(" {:>11}", status.name().to_uppercase()));
+    }
+    summary.push_str(&format!(" {:>11}\n", "CACHED"));
+    for report in reports {
+        summary.push_str(&count_row(
+            &report.crate_name,
+            &report.items.iter().collect::<Vec<_>>(),
+        ));
+    }
+    let all_items: Vec<&ItemReport> = reports.iter().flat_map(|report| &report.items).collect();
+    summary.push_str(&count_row("total", &all_items));
+
+    let failed_items: Vec<_> = all_items
+        .iter()
+        .filter(|item| item.status == ItemStatus::Failed)
+        .collect();
+    if !failed_items.is_empty() {
+        summary.push_str("\nFailed items:\n");
+        for item in failed_items {
+            summary.push_str(&format!("  {}{}\n", item.name, format_location(item)));
+        }
+    }
+
+    let mut timed_items: Vec<_> = all_items
+        .iter()
+        .filter(|item| item.cache.is_some())
+        .collect();
+    timed_items.sort_by_key(|item| std::cmp::Reverse(item.time_ms));
+    timed_items.truncate(slowest_items);
+    if !timed_items.is_empty() {
+        summary.push_str("\nSlowest items:\n");
+        for item in timed_items {
+            summary.push_str(&format!(
+                "  {:>8} ms  {}{}\n",
+                item.time_ms,
+                item.name,
+                format_location(item)
+            ));
+        }
+    }
+    summary
+}
+
+fn format_location(item: &ItemReport) -> String {
+    match &item.span {
+        Some(span) => format!(" ({}:{}:{})", span.file, span.line, span.column),
+        None => String::new(),
+    }
+}
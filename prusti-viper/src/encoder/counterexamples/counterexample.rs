@@ -1,6 +1,11 @@
+use super::{
+    counterexample_json::{entry_to_json, variable_to_json, EntryView, JsonEntry},
+    counterexample_test::CounterexampleTest,
+};
 use prusti_interface::PrustiError;
-use prusti_rustc_interface::span::Span;
-use std::fmt;
+use prusti_rustc_interface::{errors::MultiSpan, span::Span};
+use serde_json::json;
+use std::{fmt, iter};
 
 /// Counterexample information for a single variable.
 pub struct CounterexampleEntry {
@@ -36,6 +41,21 @@ impl CounterexampleEntry {
             final_value,
         }
     }
+
+    /// Serializes the initial (if any) and final value of the variable,
+    /// using the given function to serialize its span.
+    fn to_json(&self, span_to_json: &dyn Fn(&MultiSpan) -> serde_json::Value) -> serde_json::Value {
+        let span = span_to_json(&MultiSpan::from(self.span));
+        let initial_value = self
+            .initial_value
+            .as_ref()
+            .map(|initial_value| (Some("initial"), initial_value.to_json(), span.clone()));
+        let final_value = (Some("final"), self.final_value.to_json(), span);
+        variable_to_json(
+            self.name.as_deref(),
+            initial_value.into_iter().chain(iter::once(final_value)),
+        )
+    }
 }
 
 /// Indents the debug output of the given value with "  " starting with the
@@ -66,21 +86,107 @@ impl fmt::Display for CounterexampleEntry {
     }
 }
 
+/// The values of the local variables at a labelled program point of the
+/// failing path.
+pub struct TraceState {
+    /// Describes the program point, e.g. "at the start of a loop iteration".
+    description: String,
+    span: Span,
+    values: Vec<(String, Entry)>,
+}
+
+impl TraceState {
+    pub fn new(description: String, span: Span, values: Vec<(String, Entry)>) -> Self {
+        TraceState {
+            description,
+            span,
+            values,
+        }
+    }
+}
+
+impl fmt::Display for TraceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "counterexample state {}:", self.description)?;
+        for (name, value) in &self.values {
+            write!(f, "\n  {}: {}", name, indented_debug(value))?;
+        }
+        Ok(())
+    }
+}
+
 /// A concrete counterexample containing mapped values of arguments and locals
 /// (the latter only for impure functions), as well as the result (if any).
-pub struct Counterexample(Vec<CounterexampleEntry>);
+pub struct Counterexample {
+    entries: Vec<CounterexampleEntry>,
+    /// The states of the failing path, in the order in which they are reached.
+    trace: Vec<TraceState>,
+    /// A unit test replaying the counterexample, if it could be generated.
+    test: Option<CounterexampleTest>,
+}
 
 impl Counterexample {
     pub fn new(entries: Vec<CounterexampleEntry>) -> Self {
-        Self(entries)
+        Self {
+            entries,
+            trace: vec![],
+            test: None,
+        }
+    }
+
+    pub fn with_trace(mut self, trace: Vec<TraceState>) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    pub fn with_test(mut self, test: CounterexampleTest) -> Self {
+        self.test = Some(test);
+        self
+    }
+
+    pub fn test(&self) -> Option<&CounterexampleTest> {
+        self.test.as_ref()
+    }
+
+    /// Serializes the values of the variables and the trace, using the given
+    /// function to serialize their spans.
+    pub fn to_json(
+        &self,
+        span_to_json: &dyn Fn(&MultiSpan) -> serde_json::Value,
+    ) -> serde_json::Value {
+        let variables = self
+            .entries
+            .iter()
+            .map(|entry| entry.to_json(span_to_json))
+            .collect::<Vec<_>>();
+        let trace = self
+            .trace
+            .iter()
+            .map(|state| {
+                let values: Vec<_> = state
+                    .values
+                    .iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value.to_json() }))
+                    .collect();
+                json!({
+                    "point": state.description,
+                    "span": span_to_json(&MultiSpan::from(state.span)),
+                    "variables": values,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "variables": variables, "trace": trace })
     }
 
     /// Annotates a Prusti error with notes for any variable present in the
-    /// mapped counterexample.
+    /// mapped counterexample, followed by the states of the trace.
     pub fn annotate_error(&self, mut prusti_error: PrustiError) -> PrustiError {
-        for entry in &self.0 {
+        for entry in &self.entries {
             prusti_error = prusti_error.add_note(format!("{}", entry), Some(entry.span));
         }
+        for state in &self.trace {
+            prusti_error = prusti_error.add_note(format!("{}", state), Some(state.span));
+        }
         prusti_error
     }
 }
@@ -108,6 +214,8 @@ pub enum Entry {
         //that is why no FxHashMap is used
     },
     Tuple(Vec<Entry>),
+    /// The elements of an array, a slice or a vector.
+    Seq(Vec<Entry>),
     Unknown,
 }
 
@@ -201,6 +309,89 @@ impl Entry {
                 }
                 self.clone()
             }
+            (Entry::Seq(entries1), Entry::Seq(entries2)) => {
+                if entries1.len() == entries2.len() {
+                    let new_entries = entries1
+                        .iter()
+                        .zip(entries2)
+                        .map(|(x, y)| x.merge(y))
+                        .collect();
+                    return Entry::Seq(new_entries);
+                }
+                self.clone()
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Serializes the entry to a tree of JSON objects, each with a `kind`.
+    pub fn to_json(&self) -> serde_json::Value {
+        entry_to_json(self)
+    }
+
+    /// Replaces the unknown parts of the entry with the corresponding parts of
+    /// another entry of the same value.
+    pub fn complete(&self, other: &Entry) -> Entry {
+        let complete_all = |entries1: &[Entry], entries2: &[Entry]| -> Vec<Entry> {
+            iter::zip(entries1, entries2)
+                .map(|(x, y)| x.complete(y))
+                .collect()
+        };
+        let complete_fields =
+            |fields1: &[(String, Entry)], fields2: &[(String, Entry)]| -> Vec<(String, Entry)> {
+                iter::zip(fields1, fields2)
+                    .map(|((name, x), (_, y))| (name.clone(), x.complete(y)))
+                    .collect()
+            };
+        match (self, other) {
+            (Entry::Unknown, _) => other.clone(),
+            (Entry::Ref(entry1), Entry::Ref(entry2)) => Entry::Ref(box entry1.complete(entry2)),
+            (Entry::Box(entry1), Entry::Box(entry2)) => Entry::Box(box entry1.complete(entry2)),
+            (
+                Entry::Struct {
+                    name: name1,
+                    field_entries: field_entries1,
+                },
+                Entry::Struct {
+                    name: name2,
+                    field_entries: field_entries2,
+                },
+            ) if name1 == name2 && field_entries1.len() == field_entries2.len() => Entry::Struct {
+                name: name1.clone(),
+                field_entries: complete_fields(field_entries1, field_entries2),
+            },
+            (
+                Entry::Enum {
+                    super_name: super_name1,
+                    name: name1,
+                    field_entries: field_entries1,
+                },
+                Entry::Enum {
+                    super_name: super_name2,
+                    name: name2,
+                    field_entries: field_entries2,
+                },
+            ) if super_name1 == super_name2 => {
+                if name1 == "?" {
+                    other.clone()
+                } else if name1 == name2 && field_entries1.len() == field_entries2.len() {
+                    Entry::Enum {
+                        super_name: super_name1.clone(),
+                        name: name1.clone(),
+                        field_entries: complete_fields(field_entries1, field_entries2),
+                    }
+                } else {
+                    self.clone()
+                }
+            }
+            (Entry::Tuple(entries1), Entry::Tuple(entries2))
+                if entries1.len() == entries2.len() =>
+            {
+                Entry::Tuple(complete_all(entries1, entries2))
+            }
+            (Entry::Seq(entries1), Entry::Seq(entries2)) if entries1.len() == entries2.len() => {
+                Entry::Seq(complete_all(entries1, entries2))
+            }
             _ => self.clone(),
         }
     }
@@ -212,6 +403,40 @@ impl Default for Entry {
     }
 }
 
+impl JsonEntry for Entry {
+    fn view(&self) -> EntryView<'_, Self> {
+        match self {
+            Entry::Int(value) => EntryView::Int(value),
+            Entry::Float(value) => EntryView::Float(value),
+            Entry::Bool(value) => EntryView::Bool(*value),
+            Entry::Char(value) => EntryView::Char(*value),
+            Entry::Ref(entry) => EntryView::Ref(entry),
+            Entry::Box(entry) => EntryView::Box(entry),
+            Entry::Struct {
+                name,
+                field_entries,
+            } => EntryView::Struct {
+                name,
+                fields: field_entries,
+                display: None,
+            },
+            Entry::Enum {
+                super_name,
+                name,
+                field_entries,
+            } => EntryView::Enum {
+                super_name,
+                name,
+                fields: field_entries,
+                display: None,
+            },
+            Entry::Tuple(entries) => EntryView::Tuple(entries),
+            Entry::Seq(entries) => EntryView::Seq(entries),
+            Entry::Unknown => EntryView::Unknown,
+        }
+    }
+}
+
 impl fmt::Debug for Entry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -271,6 +496,7 @@ impl fmt::Debug for Entry {
                     f1.finish()
                 }
             }
+            Entry::Seq(entries) => f.debug_list().entries(entries).finish(),
             Entry::Unknown => write!(f, "?"),
         }
     }
@@ -0,0 +1,109 @@
+//! The JSON serialization of counterexamples, shared by the counterexamples of
+//! the legacy and of the refactored encoding. See `COUNTEREXAMPLE_JSON_DIR`.
+
+use serde_json::{json, Value};
+
+/// A borrowed view of a counterexample entry, with the parts that have the
+/// same JSON representation in both encodings.
+pub(super) enum EntryView<'a, E> {
+    Int(&'a str),
+    Float(&'a str),
+    Bool(bool),
+    Char(char),
+    Ref(&'a E),
+    Box(&'a E),
+    Struct {
+        name: &'a str,
+        fields: &'a [(String, E)],
+        /// The value formatted as given by `#[print_counterexample]`.
+        display: Option<String>,
+    },
+    Enum {
+        super_name: &'a str,
+        /// The name of the variant, or "?" if it is unknown.
+        name: &'a str,
+        fields: &'a [(String, E)],
+        display: Option<String>,
+    },
+    Union {
+        name: &'a str,
+        field: (&'a str, &'a E),
+    },
+    Array(&'a [E]),
+    Tuple(&'a [E]),
+    Seq(&'a [E]),
+    Unknown,
+}
+
+/// A counterexample entry that can be serialized to JSON.
+pub(super) trait JsonEntry: Sized {
+    fn view(&self) -> EntryView<'_, Self>;
+}
+
+/// Serializes the entry to a tree of JSON objects, each with a `kind`.
+pub(super) fn entry_to_json<E: JsonEntry>(entry: &E) -> Value {
+    let fields_to_json = |fields: &[(String, E)]| -> Vec<Value> {
+        fields
+            .iter()
+            .map(|(name, entry)| json!({ "name": name, "value": entry_to_json(entry) }))
+            .collect()
+    };
+    let elements_to_json =
+        |entries: &[E]| -> Vec<Value> { entries.iter().map(entry_to_json).collect() };
+    match entry.view() {
+        EntryView::Int(value) => json!({ "kind": "int", "value": value }),
+        EntryView::Float(value) => json!({ "kind": "float", "value": value }),
+        EntryView::Bool(value) => json!({ "kind": "bool", "value": value }),
+        EntryView::Char(value) => json!({ "kind": "char", "value": value, "code": value as u32 }),
+        EntryView::Ref(entry) => json!({ "kind": "ref", "target": entry_to_json(entry) }),
+        EntryView::Box(entry) => json!({ "kind": "box", "target": entry_to_json(entry) }),
+        EntryView::Struct {
+            name,
+            fields,
+            display,
+        } => json!({
+            "kind": "struct",
+            "name": name,
+            "fields": fields_to_json(fields),
+            "display": display,
+        }),
+        EntryView::Enum {
+            super_name,
+            name,
+            fields,
+            display,
+        } => json!({
+            "kind": "enum",
+            "name": super_name,
+            "variant": if name == "?" { None } else { Some(name) },
+            "fields": fields_to_json(fields),
+            "display": display,
+        }),
+        EntryView::Union { name, field } => json!({
+            "kind": "union",
+            "name": name,
+            "fields": [{ "name": field.0, "value": entry_to_json(field.1) }],
+        }),
+        EntryView::Array(entries) => {
+            json!({ "kind": "array", "elements": elements_to_json(entries) })
+        }
+        EntryView::Tuple(entries) => {
+            json!({ "kind": "tuple", "elements": elements_to_json(entries) })
+        }
+        EntryView::Seq(entries) => json!({ "kind": "seq", "elements": elements_to_json(entries) }),
+        EntryView::Unknown => json!({ "kind": "unknown" }),
+    }
+}
+
+/// Serializes the values of a variable, each given with the state in which it
+/// was observed (if known) and its serialized span.
+pub(super) fn variable_to_json<'a>(
+    name: Option<&str>,
+    values: impl IntoIterator<Item = (Option<&'a str>, Value, Value)>,
+) -> Value {
+    let values: Vec<_> = values
+        .into_iter()
+        .map(|(state, value, span)| json!({ "state": state, "value": value, "span": span }))
+        .collect();
+    json!({ "name": name, "values": values })
+}
@@ -1,5 +1,7 @@
+use super::counterexample_json::{entry_to_json, variable_to_json, EntryView, JsonEntry};
 use prusti_interface::PrustiError;
 use prusti_rustc_interface::errors::MultiSpan;
+use serde_json::json;
 use std::fmt;
 
 /// Counterexample information for a single variable.
@@ -54,6 +56,29 @@ impl Counterexample {
         Self(entries)
     }
 
+    /// Serializes the history of the values of the variables as the
+    /// `variables` of an object, using the given function to serialize their
+    /// spans.
+    pub fn to_json(
+        &self,
+        span_to_json: &dyn Fn(&MultiSpan) -> serde_json::Value,
+    ) -> serde_json::Value {
+        let variables = self
+            .0
+            .iter()
+            .map(|entry| {
+                variable_to_json(
+                    entry.name.as_deref(),
+                    entry
+                        .history
+                        .iter()
+                        .map(|(value, span)| (None, value.to_json(), span_to_json(span))),
+                )
+            })
+            .collect::<Vec<_>>();
+        json!({ "variables": variables })
+    }
+
     /// Annotates a Prusti error with notes for any variable present in the
     /// mapped counterexample.
     pub fn annotate_error(&self, mut prusti_error: PrustiError) -> PrustiError {
@@ -111,6 +136,54 @@ impl Entry {
             _ => false,
         }
     }
+
+    /// Serializes the entry to a tree of JSON objects, each with a `kind`.
+    /// Structs and enums with a custom format, given by
+    /// `#[print_counterexample]`, have their formatted value as `display`.
+    pub fn to_json(&self) -> serde_json::Value {
+        entry_to_json(self)
+    }
+}
+
+impl JsonEntry for Entry {
+    fn view(&self) -> EntryView<'_, Self> {
+        match self {
+            Entry::Int(value) => EntryView::Int(value),
+            Entry::Float(value) => EntryView::Float(value),
+            Entry::Bool(value) => EntryView::Bool(*value),
+            Entry::Char(value) => EntryView::Char(*value),
+            Entry::Ref(entry) => EntryView::Ref(entry),
+            Entry::Box(entry) => EntryView::Box(entry),
+            Entry::Struct {
+                name,
+                field_entries,
+                custom_print_option,
+            } => EntryView::Struct {
+                name,
+                fields: field_entries,
+                display: custom_print_option.as_ref().map(|_| format!("{:?}", self)),
+            },
+            Entry::Enum {
+                super_name,
+                name,
+                field_entries,
+                custom_print_option,
+            } => EntryView::Enum {
+                super_name,
+                name,
+                fields: field_entries,
+                display: custom_print_option.as_ref().map(|_| format!("{:?}", self)),
+            },
+            Entry::Union { name, field_entry } => EntryView::Union {
+                name,
+                field: (&field_entry.0, &*field_entry.1),
+            },
+            Entry::Array(entries) => EntryView::Array(entries),
+            Entry::Tuple(entries) => EntryView::Tuple(entries),
+            Entry::Seq(entries) => EntryView::Seq(entries),
+            Entry::Unknown => EntryView::Unknown,
+        }
+    }
 }
 
 impl Default for Entry {
@@ -0,0 +1,249 @@
+use super::counterexample::Entry;
+use prusti_interface::data::ProcedureDefId;
+use prusti_rustc_interface::{
+    middle::ty::{self, print::with_no_trimmed_paths, Ty, TyCtxt},
+    span::sym,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A unit test replaying a counterexample, by calling the failing function
+/// with the initial values of its arguments.
+pub struct CounterexampleTest {
+    /// The name of the failing function, usable as part of an identifier.
+    name: String,
+    /// The path with which the failing function is called.
+    function_path: String,
+    /// The name and the expression constructing each argument, or `None` if
+    /// the argument could not be constructed from the counterexample.
+    arguments: Vec<(String, Option<String>)>,
+    /// The result of the function in the counterexample, if any.
+    result: Option<Entry>,
+}
+
+impl CounterexampleTest {
+    /// Builds the test from the types of the arguments of the function and
+    /// the name and initial value of the arguments that are present in the
+    /// counterexample.
+    pub fn new<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        def_id: ProcedureDefId,
+        argument_types: &[Ty<'tcx>],
+        mut argument_entries: Vec<Option<(String, Entry)>>,
+        result: Option<Entry>,
+    ) -> Self {
+        let function_path = item_path(tcx, def_id);
+        let name = tcx.item_name(def_id).to_ident_string();
+        let arguments = argument_types
+            .iter()
+            .enumerate()
+            .map(
+                |(index, &typ)| match argument_entries.get_mut(index).and_then(Option::take) {
+                    // `self` is not a valid name for a local variable.
+                    Some((name, entry)) if name == "self" => {
+                        ("self_".to_string(), construct(tcx, typ, &entry))
+                    }
+                    Some((name, entry)) => (name, construct(tcx, typ, &entry)),
+                    None => (format!("arg{}", index), None),
+                },
+            )
+            .collect();
+        CounterexampleTest {
+            name,
+            function_path,
+            arguments,
+            result,
+        }
+    }
+
+    /// Generates the source of a test module containing the test, whose name
+    /// is made unique by the given suffix, e.g. the line of the error.
+    pub fn to_source(&self, suffix: &str) -> String {
+        let test_name = self.test_name(suffix);
+        let mut lines = vec![
+            format!(
+                "// Generated by Prusti from a counterexample of `{}`.",
+                self.function_path
+            ),
+            "#[cfg(test)]".to_string(),
+            format!("mod {} {{", test_name),
+            "    #[allow(unused_imports)]".to_string(),
+            "    use super::*;".to_string(),
+            String::new(),
+            "    #[test]".to_string(),
+        ];
+        if self.arguments.iter().any(|(_, expr)| expr.is_none()) {
+            lines.push(
+                "    #[ignore = \"some arguments could not be constructed from the counterexample\"]"
+                    .to_string(),
+            );
+        }
+        lines.push(format!("    fn {}() {{", test_name));
+        for (name, expr) in &self.arguments {
+            lines.push(match expr {
+                Some(expr) => format!("        let {} = {};", name, expr),
+                None => format!(
+                    "        let {} = todo!(\"the value of `{}` is unknown\");",
+                    name, name
+                ),
+            });
+        }
+        if let Some(result) = &self.result {
+            let result = format!("{:?}", result).replace('\n', " ");
+            lines.push(format!(
+                "        // The result in the counterexample is: {}",
+                result
+            ));
+        }
+        let arguments: Vec<_> = self
+            .arguments
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        lines.push(format!(
+            "        let _result = {}({});",
+            self.function_path,
+            arguments.join(", ")
+        ));
+        lines.push("    }".to_string());
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+
+    /// Writes the test to a file of the given directory, named after the test.
+    pub fn write(&self, dir: &Path, suffix: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.rs", self.test_name(suffix)));
+        fs::write(&path, self.to_source(suffix))?;
+        Ok(path)
+    }
+
+    fn test_name(&self, suffix: &str) -> String {
+        format!("counterexample_{}_{}", self.name, suffix)
+    }
+}
+
+/// The path of an item in code of its crate: items of the local crate are
+/// prefixed with `crate`.
+fn item_path(tcx: TyCtxt<'_>, def_id: ProcedureDefId) -> String {
+    let path = with_no_trimmed_paths!(tcx.def_path_str(def_id));
+    if def_id.is_local() && !path.starts_with('<') {
+        format!("crate::{}", path)
+    } else {
+        path
+    }
+}
+
+/// Generates an expression constructing the value of the entry, or `None` if
+/// some part of the value is unknown.
+fn construct<'tcx>(tcx: TyCtxt<'tcx>, typ: Ty<'tcx>, entry: &Entry) -> Option<String> {
+    Some(match (typ.kind(), entry) {
+        (ty::TyKind::Bool, Entry::Bool(value)) => value.to_string(),
+        (ty::TyKind::Int(int_ty), Entry::Int(value)) => {
+            value.parse::<i128>().ok()?;
+            format!("{}{}", value, int_ty.name_str())
+        }
+        (ty::TyKind::Uint(uint_ty), Entry::Int(value)) => {
+            value.parse::<u128>().ok()?;
+            format!("{}{}", value, uint_ty.name_str())
+        }
+        (ty::TyKind::Float(float_ty), Entry::Float(value)) => {
+            // Infinities and NaN have no literal.
+            if !value.parse::<f64>().ok()?.is_finite() {
+                return None;
+            }
+            format!("{}{}", value, float_ty.name_str())
+        }
+        (ty::TyKind::Char, Entry::Char(value)) => format!("{:?}", value),
+        (ty::TyKind::Ref(_, inner_typ, mutability), Entry::Ref(inner)) => {
+            format!(
+                "&{}{}",
+                mutability.prefix_str(),
+                construct(tcx, *inner_typ, inner)?
+            )
+        }
+        (ty::TyKind::Adt(adt_def, substs), Entry::Box(inner)) if adt_def.is_box() => {
+            format!("Box::new({})", construct(tcx, substs.type_at(0), inner)?)
+        }
+        (ty::TyKind::Tuple(types), Entry::Tuple(entries)) if types.len() == entries.len() => {
+            let fields = types
+                .iter()
+                .zip(entries)
+                .map(|(typ, entry)| construct(tcx, typ, entry))
+                .collect::<Option<Vec<_>>>()?;
+            match fields.len() {
+                1 => format!("({},)", fields[0]),
+                _ => format!("({})", fields.join(", ")),
+            }
+        }
+        (ty::TyKind::Array(elem_typ, _) | ty::TyKind::Slice(elem_typ), Entry::Seq(entries)) => {
+            format!("[{}]", construct_elements(tcx, *elem_typ, entries)?)
+        }
+        (ty::TyKind::Adt(adt_def, substs), Entry::Seq(entries))
+            if tcx.is_diagnostic_item(sym::Vec, adt_def.did()) =>
+        {
+            format!(
+                "vec![{}]",
+                construct_elements(tcx, substs.type_at(0), entries)?
+            )
+        }
+        (ty::TyKind::Adt(adt_def, substs), Entry::Struct { field_entries, .. })
+            if adt_def.is_struct() =>
+        {
+            let variant = adt_def.non_enum_variant();
+            let fields = construct_fields(tcx, variant, substs, field_entries)?;
+            format!("{} {{ {} }}", item_path(tcx, adt_def.did()), fields)
+        }
+        (
+            ty::TyKind::Adt(adt_def, substs),
+            Entry::Enum {
+                name,
+                field_entries,
+                ..
+            },
+        ) if adt_def.is_enum() => {
+            let variant = adt_def
+                .variants()
+                .iter()
+                .find(|variant| variant.ident(tcx).name.as_str() == name)?;
+            let fields = construct_fields(tcx, variant, substs, field_entries)?;
+            format!("{} {{ {} }}", item_path(tcx, variant.def_id), fields)
+        }
+        _ => return None,
+    })
+}
+
+fn construct_elements<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    elem_typ: Ty<'tcx>,
+    entries: &[Entry],
+) -> Option<String> {
+    let elements = entries
+        .iter()
+        .map(|entry| construct(tcx, elem_typ, entry))
+        .collect::<Option<Vec<_>>>()?;
+    Some(elements.join(", "))
+}
+
+/// Generates the fields of a struct expression. The braced syntax also works
+/// for tuple structs and variants, whose fields are named by their index.
+fn construct_fields<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    variant: &ty::VariantDef,
+    substs: ty::subst::SubstsRef<'tcx>,
+    field_entries: &[(String, Entry)],
+) -> Option<String> {
+    let fields = variant
+        .fields
+        .iter()
+        .map(|field| {
+            let field_name = field.ident(tcx).name.to_ident_string();
+            let (_, entry) = field_entries.iter().find(|(name, _)| *name == field_name)?;
+            let value = construct(tcx, field.ty(tcx, substs), entry)?;
+            Some(format!("{}: {}", field_name, value))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(fields.join(", "))
+}
@@ -1,4 +1,7 @@
-use super::{super::high::types::HighTypeEncoderInterface, counterexample::*};
+use super::{
+    super::high::types::HighTypeEncoderInterface, counterexample::*,
+    counterexample_test::CounterexampleTest,
+};
 use crate::encoder::{
     counterexamples::*,
     places::{Local, LocalVariableManager},
@@ -13,7 +16,7 @@ use prusti_rustc_interface::{
         mir::{self, VarDebugInfo},
         ty::{self, Ty, TyCtxt},
     },
-    span::Span,
+    span::{sym, Span},
 };
 use rustc_hash::FxHashMap;
 use std::iter;
@@ -32,9 +35,13 @@ pub fn backtranslate(
     // function when it fails. But currently most values can not be obtained
     // there because they are folded.
     // Instead, we use the last *labelled* counterexample.
+    // The labels of the program points of the trace are not considered.
+    let program_points = encoder.program_points(def_id);
     let last_label: Option<&str> = silicon_counterexample
         .label_order
-        .last()
+        .iter()
+        .rev()
+        .find(|label| !program_points.contains_key(*label))
         .map(|label| label.as_str());
 
     let old_impure_label = if silicon_counterexample
@@ -62,33 +69,33 @@ pub fn backtranslate(
     // map those needed
     let mut entries = FxHashMap::default();
     let mut args = FxHashMap::default();
+    let argument_types = translator.argument_types();
+    let mut test_arguments = vec![None; argument_types.len()];
 
-    for (rust_name, span, vir_name, typ, encoded_typ, is_arg) in entries_to_process {
+    for (rust_name, span, vir_name, typ, encoded_typ, arg_index) in entries_to_process.iter() {
+        let (rust_name, span, typ, arg_index) = (rust_name.clone(), *span, *typ, *arg_index);
+        let mut final_entry = None;
         if !translator.is_pure {
-            let (silicon_model, opt_sil_entry) =
-                translator.get_silicon_at_label(last_label, &vir_name); //We cannot use the "main" model of silicon because of references
-
-            let entry_snapshot = translator
-                .translate_silicon_entry_with_snapshot(
-                    typ,
-                    opt_sil_entry,
-                    Some(encoded_typ.clone()),
-                )
-                .unwrap_or_default();
-
-            let entry_heap_based = translator
-                .translate_silicon_entry(typ, opt_sil_entry, vir_name.clone(), silicon_model)
-                .unwrap_or_default();
-            let entry = entry_heap_based.merge(&entry_snapshot); //We prefer the heap based counterexample over the snapshot one
-
-            entries.insert((rust_name.clone().to_string(), span), entry);
+            //We cannot use the "main" model of silicon because of references
+            let entry = translator.translate_local_at_label(last_label, vir_name, typ, encoded_typ);
+            final_entry = Some(entry.clone());
+            entries.insert((rust_name.clone(), span), entry);
         }
-        if is_arg {
+        if let Some(arg_index) = arg_index {
             let (silicon_model, opt_sil_entry) =
-                translator.get_silicon_at_label(old_label, &vir_name);
+                translator.get_silicon_at_label(old_label, vir_name);
             let arg_entry = translator
                 .translate_silicon_entry(typ, opt_sil_entry, vir_name.clone(), silicon_model)
                 .unwrap_or_default();
+            // The initial value of an argument that cannot change is often
+            // only partially known, while its final value is known.
+            let test_entry = match final_entry {
+                Some(final_entry) if translator.is_unchanged_argument(arg_index) => {
+                    arg_entry.complete(&final_entry)
+                }
+                _ => arg_entry.clone(),
+            };
+            test_arguments[arg_index] = Some((rust_name.clone(), test_entry));
             args.insert((rust_name, span), arg_entry);
         }
     }
@@ -125,6 +132,7 @@ pub fn backtranslate(
     }
 
     // add counterexample note for the return value, if any
+    let mut test_result = None;
     if !result_entry_heap_based.is_unit() {
         let result_entry_snapshot = translator
             .translate_silicon_entry_with_snapshot(
@@ -134,6 +142,7 @@ pub fn backtranslate(
             )
             .unwrap_or_default();
         let result = result_entry_heap_based.merge(&result_entry_snapshot); //We prefer the heap based counterexample over the snapshot one
+        test_result = Some(result.clone());
         ce_entries.push(CounterexampleEntry::with_one_value(
             result_span,
             None,
@@ -141,9 +150,53 @@ pub fn backtranslate(
         ));
     }
 
+    // add the values of the locals at the labelled program points of the
+    // failing path, if any
+    let trace = silicon_counterexample
+        .label_order
+        .iter()
+        .filter_map(|label| {
+            let program_point = program_points.get(label)?;
+            let values = entries_to_process
+                .iter()
+                .filter_map(|(rust_name, _, vir_name, typ, encoded_typ, _)| {
+                    let entry = translator.translate_local_at_label(
+                        Some(label.as_str()),
+                        vir_name,
+                        *typ,
+                        encoded_typ,
+                    );
+                    // Locals that are not initialized yet have no value.
+                    if matches!(entry, Entry::Unknown) {
+                        None
+                    } else {
+                        Some((rust_name.clone(), entry))
+                    }
+                })
+                .collect();
+            Some(TraceState::new(
+                program_point.description.clone(),
+                program_point.span,
+                values,
+            ))
+        })
+        .collect();
+
+    let test = CounterexampleTest::new(
+        translator.tcx,
+        def_id,
+        &argument_types,
+        test_arguments,
+        test_result,
+    );
     Counterexample::new(ce_entries)
+        .with_trace(trace)
+        .with_test(test)
 }
 
+/// The maximal number of elements of an array or a slice that are translated.
+const MAX_SEQUENCE_LEN: u64 = 64;
+
 pub struct CounterexampleTranslator<'ce, 'tcx> {
     env_query: EnvQuery<'tcx>,
     tcx: TyCtxt<'tcx>,
@@ -185,7 +238,7 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
     fn entries_to_process(
         &self,
         encoder: &Encoder<'_, 'tcx>,
-    ) -> Vec<(String, Span, String, Ty<'tcx>, String, bool)> {
+    ) -> Vec<(String, Span, String, Ty<'tcx>, String, Option<usize>)> {
         let mut entries_to_process = vec![];
         for vdi in &self.var_debug_info {
             let rust_name = vdi.name.to_ident_string();
@@ -202,7 +255,12 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
             let index = local.index();
             let var_local = Local::from(local);
             let typ = self.local_variable_manager.get_type(var_local);
-            let is_arg = index > 0 && index <= self.mir.arg_count;
+            // The index of the argument, starting from 0.
+            let arg_index = if index > 0 && index <= self.mir.arg_count {
+                Some(index - 1)
+            } else {
+                None
+            };
             let vir_name = self.local_variable_manager.get_name(var_local);
             let encoded_typ = self.get_encoded_type(typ, encoder);
             entries_to_process.push((
@@ -211,12 +269,37 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
                 vir_name.clone(),
                 typ,
                 encoded_typ,
-                is_arg,
+                arg_index,
             ));
         }
         entries_to_process
     }
 
+    /// The types of the arguments of the function, in order.
+    fn argument_types(&self) -> Vec<Ty<'tcx>> {
+        (1..=self.mir.arg_count)
+            .map(|index| {
+                let local = Local::from(mir::Local::from_usize(index));
+                self.local_variable_manager.get_type(local)
+            })
+            .collect()
+    }
+
+    /// Whether the value of an argument is the same throughout the function,
+    /// because it is neither mutable nor contains mutable references.
+    fn is_unchanged_argument(&self, arg_index: usize) -> bool {
+        let local = mir::Local::from_usize(arg_index + 1);
+        let local_decl = &self.mir.local_decls[local];
+        local_decl.mutability == mir::Mutability::Not
+            && !local_decl.ty.walk().any(|arg| {
+                matches!(
+                    arg.unpack(),
+                    ty::subst::GenericArgKind::Type(typ)
+                        if matches!(typ.kind(), ty::TyKind::Ref(_, _, mir::Mutability::Mut))
+                )
+            })
+    }
+
     fn result_to_process(&self, encoder: &Encoder<'_, 'tcx>) -> (String, Span, Ty<'tcx>, String) {
         // return the Silicon-name of result + its type
         // other than for entries, this is not always same as the vir-name
@@ -242,23 +325,41 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
         (vir_name, span, typ, encoded_typ)
     }
 
+    /// Translates the value of a local at the given label, preferring the
+    /// heap-based value over the snapshot one.
+    fn translate_local_at_label(
+        &self,
+        label: Option<&str>,
+        vir_name: &str,
+        typ: Ty<'tcx>,
+        encoded_typ: &str,
+    ) -> Entry {
+        let (silicon_model, opt_sil_entry) = self.get_silicon_at_label(label, vir_name);
+        let entry_snapshot = self
+            .translate_silicon_entry_with_snapshot(
+                typ,
+                opt_sil_entry,
+                Some(encoded_typ.to_string()),
+            )
+            .unwrap_or_default();
+        let entry_heap_based = self
+            .translate_silicon_entry(typ, opt_sil_entry, vir_name.to_string(), silicon_model)
+            .unwrap_or_default();
+        entry_heap_based.merge(&entry_snapshot)
+    }
+
     fn get_silicon_at_label(
         &self,
         label: Option<&str>,
         vir_name: &str,
     ) -> (&FxHashMap<String, ModelEntry>, Option<&ModelEntry>) {
-        let silicon_model = match label {
-            // should only be called on labels that actually exist
-            Some(lbl) => {
-                &self
-                    .silicon_counterexample
-                    .old_models
-                    .get(lbl)
-                    .unwrap()
-                    .entries
-            }
-            None => &self.silicon_counterexample.model.entries,
-        };
+        // Counterexamples of Carbon only have the models of the labels whose
+        // heap appears in the Boogie model.
+        let silicon_model =
+            match label.and_then(|lbl| self.silicon_counterexample.old_models.get(lbl)) {
+                Some(old_model) => &old_model.entries,
+                None => &self.silicon_counterexample.model.entries,
+            };
         (silicon_model, silicon_model.get(vir_name))
     }
 
@@ -304,6 +405,16 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
                     .unwrap_or_default(),
             ),
             (ty::TyKind::Ref(..), _) => Entry::Ref(box Entry::Unknown),
+            (
+                ty::TyKind::Array(elem_typ, _) | ty::TyKind::Slice(elem_typ),
+                Some(ModelEntry::Ref(name, _)),
+            ) => self.translate_sequence(typ, *elem_typ, ModelEntry::Var(name.clone()))?,
+            (ty::TyKind::Adt(adt_def, subst), Some(ModelEntry::Ref(name, _)))
+                if self.tcx.is_diagnostic_item(sym::Vec, adt_def.did()) =>
+            {
+                self.translate_vec(subst.type_at(0), name)
+                    .unwrap_or_default()
+            }
             (ty::TyKind::Tuple(subst), Some(ModelEntry::Ref(_, map))) => {
                 let len = subst.len();
                 let mut fields = vec![];
@@ -413,6 +524,79 @@ impl<'ce, 'tcx> CounterexampleTranslator<'ce, 'tcx> {
         })
     }
 
+    /// Translates a vector from the slice returned by `as_slice`, applied to
+    /// the snapshot of the vector in the model.
+    fn translate_vec(&self, elem_typ: Ty<'tcx>, ref_name: &str) -> Option<Entry> {
+        let params = vec![Some(ModelEntry::Var(ref_name.to_string()))];
+        let snapshot = self.get_model_function_value("snap$__$TY$__", &params)?;
+        let params = vec![Some(snapshot.clone())];
+        let slice = self
+            .silicon_counterexample
+            .functions
+            .entries
+            .iter()
+            .filter(|(name, _)| name.contains("as_slice"))
+            .find_map(|(_, function)| function.get_function_value(&params).as_ref())?;
+        let sequence = match slice {
+            ModelEntry::Ref(name, _) => ModelEntry::Var(name.clone()),
+            _ => slice.clone(),
+        };
+        self.translate_sequence(self.tcx.mk_slice(elem_typ), elem_typ, sequence)
+    }
+
+    /// Translates an array or a slice from the interpretation of the functions
+    /// that encode its length and its elements in the model.
+    fn translate_sequence(
+        &self,
+        typ: Ty<'tcx>,
+        elem_typ: Ty<'tcx>,
+        sequence: ModelEntry,
+    ) -> Option<Entry> {
+        let sequence = Some(sequence);
+        let len = match typ.kind() {
+            ty::TyKind::Array(_, len) => {
+                len.try_eval_usize(self.tcx, ty::ParamEnv::reveal_all())?
+            }
+            _ => {
+                let len = self.get_model_function_value("Slice$len", &vec![sequence.clone()]);
+                self.translate_int(len)?.parse().ok()?
+            }
+        };
+        let mut elements = vec![];
+        for index in 0..len.min(MAX_SEQUENCE_LEN) {
+            let params = vec![
+                sequence.clone(),
+                Some(ModelEntry::LitInt(index.to_string())),
+            ];
+            let element = self.get_model_function_value("lookup_pure", &params);
+            let encoded_typ = match element {
+                Some(ModelEntry::DomainValue(domain, _)) => Some(domain.clone()),
+                _ => None,
+            };
+            elements.push(
+                self.translate_silicon_entry_with_snapshot(elem_typ, element, encoded_typ)
+                    .unwrap_or_default(),
+            );
+        }
+        Some(Entry::Seq(elements))
+    }
+
+    /// The value of a function of the model whose name starts with the given
+    /// prefix, since the names of the functions depend on their type
+    /// arguments.
+    fn get_model_function_value(
+        &self,
+        prefix: &str,
+        params: &Vec<Option<ModelEntry>>,
+    ) -> Option<&ModelEntry> {
+        self.silicon_counterexample
+            .functions
+            .entries
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .find_map(|(_, function)| function.get_function_value(params).as_ref())
+    }
+
     fn translate_vardef(
         &self,
         variant: &ty::VariantDef,
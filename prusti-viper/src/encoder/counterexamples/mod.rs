@@ -1,14 +1,18 @@
+mod counterexample_json;
 mod discriminants_interface;
 mod interface;
 mod mapping;
+mod program_points_interface;
 
 pub(crate) use self::{
     discriminants_interface::{DiscriminantsState, DiscriminantsStateInterface},
     interface::{MirProcedureMapping, MirProcedureMappingInterface},
     mapping::{VarMapping, VarMappingInterface},
+    program_points_interface::{ProgramPoint, ProgramPointsState, ProgramPointsStateInterface},
 };
 
 pub mod counterexample;
 pub mod counterexample_refactored;
+pub mod counterexample_test;
 pub mod counterexample_translation;
 pub mod counterexample_translation_refactored;
@@ -0,0 +1,53 @@
+use prusti_interface::data::ProcedureDefId;
+use prusti_rustc_interface::span::Span;
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+
+/// A program point of a procedure whose state is labelled in the encoding,
+/// so that counterexamples can report the values of the locals there.
+#[derive(Clone, Debug)]
+pub(crate) struct ProgramPoint {
+    /// Describes the program point, e.g. "the start of a loop iteration".
+    pub(crate) description: String,
+    pub(crate) span: Span,
+}
+
+#[derive(Default)]
+pub(crate) struct ProgramPointsState {
+    /// The labelled program points of each procedure, by label.
+    program_points: RefCell<FxHashMap<ProcedureDefId, FxHashMap<String, ProgramPoint>>>,
+}
+
+pub(crate) trait ProgramPointsStateInterface {
+    fn add_program_point(
+        &self,
+        proc_def_id: ProcedureDefId,
+        label: String,
+        program_point: ProgramPoint,
+    );
+    fn program_points(&self, proc_def_id: ProcedureDefId) -> FxHashMap<String, ProgramPoint>;
+}
+
+impl<'v, 'tcx: 'v> ProgramPointsStateInterface for super::super::Encoder<'v, 'tcx> {
+    fn add_program_point(
+        &self,
+        proc_def_id: ProcedureDefId,
+        label: String,
+        program_point: ProgramPoint,
+    ) {
+        self.program_points_state
+            .program_points
+            .borrow_mut()
+            .entry(proc_def_id)
+            .or_default()
+            .insert(label, program_point);
+    }
+    fn program_points(&self, proc_def_id: ProcedureDefId) -> FxHashMap<String, ProgramPoint> {
+        self.program_points_state
+            .program_points
+            .borrow()
+            .get(&proc_def_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
@@ -10,8 +10,9 @@ use rustc_hash::FxHashSet;
 use vir_crate::common::check_mode::CheckMode;
 use crate::encoder::builtin_encoder::BuiltinEncoder;
 use crate::encoder::builtin_encoder::BuiltinMethodKind;
-use crate::encoder::errors::{ErrorManager, SpannedEncodingError, EncodingError};
+use crate::encoder::errors::{ErrorManager, SpannedEncodingError, EncodingError, WithSpan};
 use crate::encoder::foldunfold;
+use crate::encoder::mir_encoder::PRECONDITION_LABEL;
 use crate::encoder::procedure_encoder::ProcedureEncoder;
 use prusti_common::{vir_expr, vir_local};
 use prusti_common::config;
@@ -25,6 +26,8 @@ use vir_crate::common::identifier::WithIdentifier;
 use prusti_rustc_interface::hir::def_id::DefId;
 use prusti_rustc_interface::middle::mir;
 use prusti_rustc_interface::middle::ty;
+use prusti_rustc_interface::errors::MultiSpan;
+use prusti_rustc_interface::span::Span;
 use std::cell::{Cell, RefCell, RefMut, Ref};
 use rustc_hash::FxHashMap;
 use std::io::Write;
@@ -44,7 +47,7 @@ use super::mir::{
     sequences::{
         MirSequencesEncoderState, MirSequencesEncoderInterface,
     },
-    contracts::ContractsEncoderState,
+    contracts::{ContractsEncoderInterface, ContractsEncoderState},
     procedures::MirProcedureEncoderState,
     type_invariants::TypeInvariantEncoderState,
     pure::{
@@ -61,6 +64,8 @@ use super::mir::{
 use super::high::types::{HighTypeEncoderState, HighTypeEncoderInterface};
 use super::counterexamples::{MirProcedureMappingInterface, MirProcedureMapping};
 use super::counterexamples::DiscriminantsState;
+use super::counterexamples::ProgramPointsState;
+use super::vacuity::{VacuityCheck, VacuityState, VacuityStateInterface};
 use super::high::to_typed::types::HighToTypedTypeEncoderState;
 
 pub struct Encoder<'v, 'tcx: 'v> {
@@ -72,6 +77,9 @@ pub struct Encoder<'v, 'tcx: 'v> {
     pub(super) high_builtin_function_encoder_state: HighBuiltinFunctionEncoderState,
     procedures: RefCell<FxHashMap<ProcedureDefId, vir::CfgMethod>>,
     programs: Vec<vir::Program>,
+    /// The span of the preconditions checked by each program of the
+    /// `LINT_SPECS_WITH_VERIFICATION` flag, by program name.
+    precondition_lints: RefCell<FxHashMap<String, MultiSpan>>,
     pub(super) mir_sequences_encoder_state: MirSequencesEncoderState<'tcx>,
     pub(super) contracts_encoder_state: ContractsEncoderState<'tcx>,
     pub(super) mir_procedure_encoder_state: MirProcedureEncoderState,
@@ -91,15 +99,28 @@ pub struct Encoder<'v, 'tcx: 'v> {
     vir_program_before_foldunfold_writer: Option<RefCell<Box<dyn Write>>>,
     vir_program_before_viper_writer: Option<RefCell<Box<dyn Write>>>,
     encoding_errors_counter: RefCell<usize>,
+    /// The procedure of the encoding task that is being processed.
+    encoded_procedure: Cell<Option<ProcedureDefId>>,
+    /// The encoding errors registered while processing the encoding task of
+    /// each procedure.
+    procedure_encoding_errors: RefCell<FxHashMap<ProcedureDefId, Vec<SpannedEncodingError>>>,
     name_interner: RefCell<NameInterner>,
     /// Maps locals to the local of their discriminant.
     pub(super) discriminants_state: DiscriminantsState,
+    /// The labelled program points of the counterexample traces.
+    pub(super) program_points_state: ProgramPointsState,
+    /// The reachability probes and checks of vacuity detection.
+    pub(super) vacuity_state: VacuityState,
     pub(super) mir_procedure_mapping: MirProcedureMapping,
     /// Whether the current pure expression that's being encoded sits inside a trigger closure.
     /// Viper limits the type of expressions that are allowed in quantifier triggers and
     /// this requires special care when encoding array/slice accesses which may come with
     /// bound checks included in the MIR.
     pub(super) is_encoding_trigger: Cell<bool>,
+    /// The triggers of the encoded quantifiers, rendered as shown by
+    /// `print_typeckd_specs`, by the span of the quantifier. A quantifier is
+    /// reported only once, even if it is encoded several times.
+    pub(super) quantifier_triggers: RefCell<FxHashMap<Span, String>>,
 }
 
 pub enum EncodingTask<'tcx> {
@@ -152,6 +173,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             builtin_methods: RefCell::new(FxHashMap::default()),
             high_builtin_function_encoder_state: Default::default(),
             programs: Vec::new(),
+            precondition_lints: RefCell::new(FxHashMap::default()),
             mir_sequences_encoder_state: Default::default(),
             mir_procedure_encoder_state: Default::default(),
             mid_core_proof_encoder_state: Default::default(),
@@ -171,11 +193,16 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             snapshot_encoder_state: Default::default(),
             mirror_encoder: RefCell::new(MirrorEncoder::new()),
             encoding_errors_counter: RefCell::new(0),
+            encoded_procedure: Cell::new(None),
+            procedure_encoding_errors: RefCell::new(FxHashMap::default()),
             name_interner: RefCell::new(NameInterner::new()),
             is_encoding_trigger: Cell::new(false),
+            quantifier_triggers: RefCell::new(FxHashMap::default()),
             specifications_state: SpecificationsState::new(def_spec),
             mir_procedure_mapping: Default::default(),
             discriminants_state: Default::default(),
+            program_points_state: Default::default(),
+            vacuity_state: Default::default(),
         }
     }
 
@@ -229,6 +256,160 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         super::definition_collector::collect_definitions(error_span, self, name, self.get_used_viper_methods())
     }
 
+    /// Encodes a program that checks whether the precondition of an encoded
+    /// procedure is satisfiable, by asserting `false` right after assuming
+    /// the precondition. The program verifies only if the precondition is
+    /// unsatisfiable. Returns `None` if the procedure has no precondition.
+    fn encode_precondition_lint(
+        &self,
+        proc_def_id: ProcedureDefId,
+        proc_name: &str,
+    ) -> SpannedEncodingResult<Option<vir::Program>> {
+        let error_span = self.env.query.get_def_span(proc_def_id);
+        let substs = self.env.query.identity_substs(proc_def_id);
+        let contract = self
+            .get_procedure_contract_for_def(proc_def_id, substs)
+            .with_span(error_span)?;
+        let precondition_spans: Vec<_> = contract
+            .functional_precondition(self.env, substs)
+            .into_iter()
+            .map(|(spec_def_id, _)| self.env.query.get_def_span(spec_def_id))
+            .collect();
+        if precondition_spans.is_empty() {
+            return Ok(None);
+        }
+        let mut method = match self.procedures.borrow().get(&proc_def_id) {
+            Some(method) => method.clone(),
+            None => return Ok(None),
+        };
+        method.set_name(format!("{}$precondition_lint", method.name()));
+        // The procedure encoder ends the start block by inhaling the
+        // precondition and labelling the resulting state, after which the
+        // lint asserts `false`.
+        let start_block = method
+            .get_indices()
+            .into_iter()
+            .find(|&block| method.get_block_label(block) == "start")
+            .filter(|&block| {
+                method.basic_blocks[block.index()].stmts.iter().any(|stmt| {
+                    matches!(stmt, vir::Stmt::Label(vir::Label { label }) if label == PRECONDITION_LABEL)
+                })
+            })
+            .ok_or_else(|| SpannedEncodingError::internal(
+                format!("the start block of {} does not inhale the precondition", method.name()),
+                error_span,
+            ))?;
+        let lint_block = method.add_block(
+            "precondition_lint",
+            vec![
+                vir::Stmt::comment("Check that the precondition is satisfiable"),
+                vir::Stmt::Assert(vir::Assert {
+                    expr: false.into(),
+                    position: vir::Position::default(),
+                }),
+            ],
+        );
+        method.set_successor(start_block, vir::Successor::Goto(lint_block));
+        method.set_successor(lint_block, vir::Successor::Return);
+        let program_name = format!("{}$precondition_lint", proc_name);
+        let program = super::definition_collector::collect_definitions(
+            error_span,
+            self,
+            program_name.clone(),
+            vec![method],
+        )?;
+        self.precondition_lints
+            .borrow_mut()
+            .insert(program_name, MultiSpan::from_spans(precondition_spans));
+        Ok(Some(program))
+    }
+
+    /// The triggers of the encoded quantifiers, in the order of their
+    /// position in the source code.
+    pub fn get_quantifier_triggers(&self) -> Vec<String> {
+        let codemap = self.env().query.codemap();
+        let mut quantifier_triggers: Vec<_> = self
+            .quantifier_triggers
+            .borrow()
+            .iter()
+            .map(|(span, triggers)| {
+                let location = codemap.lookup_char_pos(span.lo());
+                let position = (location.line, location.col.0 + 1);
+                (position, triggers.clone())
+            })
+            .collect();
+        quantifier_triggers.sort();
+        quantifier_triggers
+            .into_iter()
+            .map(|((line, column), triggers)| {
+                format!("Triggers of the quantifier at {}:{}{}", line, column, triggers)
+            })
+            .collect()
+    }
+
+    /// The span of the preconditions checked by the given program, if it
+    /// checks that the precondition of a procedure is satisfiable.
+    pub fn get_precondition_lint_span(&self, program_name: &str) -> Option<MultiSpan> {
+        self.precondition_lints.borrow().get(program_name).cloned()
+    }
+
+    /// Encodes a program that checks whether the vacuity probes of an encoded
+    /// procedure are reachable. Each probe is checked by a copy of the method
+    /// that asserts `false` right after the label of the probe, which
+    /// verifies only if the probe is unreachable. Returns `None` if the
+    /// procedure has no probe.
+    fn encode_vacuity_check(
+        &self,
+        proc_def_id: ProcedureDefId,
+        proc_name: &str,
+    ) -> SpannedEncodingResult<Option<vir::Program>> {
+        let probes = self.vacuity_probes(proc_def_id);
+        let method = match self.procedures.borrow().get(&proc_def_id) {
+            Some(method) if !probes.is_empty() => method.clone(),
+            _ => return Ok(None),
+        };
+        let mut methods = Vec::new();
+        let mut checked_probes = Vec::new();
+        for (index, probe) in probes.into_iter().enumerate() {
+            let position = self.error_manager().register_span(proc_def_id, probe.span.clone());
+            let probe_stmt = vir::Stmt::Assert(vir::Assert {
+                expr: false.into(),
+                position,
+            });
+            let mut probe_method = method.clone();
+            probe_method.set_name(format!("{}$vacuity${}", method.name(), index));
+            let inserted = probe_method.basic_blocks.iter_mut().any(|block| {
+                let label_index = block.stmts.iter().position(|stmt| {
+                    matches!(stmt, vir::Stmt::Label(vir::Label { label }) if *label == probe.label)
+                });
+                if let Some(label_index) = label_index {
+                    block.stmts.insert(label_index + 1, probe_stmt.clone());
+                }
+                label_index.is_some()
+            });
+            // The label might have been removed together with dead code.
+            if inserted {
+                methods.push(probe_method);
+                checked_probes.push((probe, position));
+            }
+        }
+        if methods.is_empty() {
+            return Ok(None);
+        }
+        let program_name = format!("{}$vacuity", proc_name);
+        let program = super::definition_collector::collect_definitions(
+            self.env.query.get_def_span(proc_def_id),
+            self,
+            program_name.clone(),
+            methods,
+        )?;
+        self.add_vacuity_check(program_name, VacuityCheck {
+            verified_program: proc_name.to_string(),
+            probes: checked_probes,
+        });
+        Ok(Some(program))
+    }
+
     pub fn get_viper_programs(&mut self) -> Vec<vir::Program> {
         std::mem::take(&mut self.programs)
     }
@@ -247,6 +428,12 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
 
     pub(in crate::encoder) fn register_encoding_error(&self, encoding_error: SpannedEncodingError) {
         debug!("Encoding error: {:?}", encoding_error);
+        if let Some(proc_def_id) = self.encoded_procedure.get() {
+            self.procedure_encoding_errors.borrow_mut()
+                .entry(proc_def_id)
+                .or_default()
+                .push(encoding_error.clone());
+        }
         let prusti_error: PrustiError = encoding_error.into();
         if prusti_error.is_error() {
             self.encoding_errors_counter.borrow_mut().add_assign(1);
@@ -258,6 +445,20 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         *self.encoding_errors_counter.borrow()
     }
 
+    /// The encoding errors registered while encoding a procedure queued for
+    /// verification.
+    pub fn get_procedure_encoding_errors(&self, proc_def_id: ProcedureDefId) -> Vec<SpannedEncodingError> {
+        self.procedure_encoding_errors.borrow()
+            .get(&proc_def_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Whether a procedure is trusted, in which case it is not verified.
+    pub fn is_trusted_procedure(&self, proc_def_id: ProcedureDefId) -> bool {
+        self.is_trusted(proc_def_id, None)
+    }
+
     pub(super) fn get_mirror_domain(&self) -> Option<vir::Domain> {
         self.mirror_encoder.borrow().get_domain().cloned()
     }
@@ -672,6 +873,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         } {
             match task {
                 EncodingTask::Procedure { def_id: proc_def_id, substs } => {
+                    self.encoded_procedure.set(Some(proc_def_id));
                     let proc_name = self.env.name.get_unique_item_name(proc_def_id);
                     let proc_def_path = self.env.name.get_item_def_path(proc_def_id);
                     info!("Encoding: {} ({})", proc_name, proc_def_path);
@@ -742,19 +944,41 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                                 self.register_encoding_error(error);
                                 debug!("Error encoding function: {:?}", proc_def_id);
                             } else {
-                                match self.finalize_viper_program(proc_name, proc_def_id) {
+                                if config::lint_specs_with_verification() {
+                                    match self.encode_precondition_lint(proc_def_id, &proc_name) {
+                                        Ok(Some(program)) => self.programs.push(program),
+                                        Ok(None) => {}
+                                        Err(error) => {
+                                            self.register_encoding_error(error);
+                                            debug!("Error encoding precondition lint: {:?}", proc_def_id);
+                                        }
+                                    }
+                                }
+                                match self.finalize_viper_program(proc_name.clone(), proc_def_id) {
                                     Ok(program) => self.programs.push(program),
                                     Err(error) => {
                                         self.register_encoding_error(error);
                                         debug!("Error finalizing program: {:?}", proc_def_id);
                                     }
                                 }
+                                // The vacuity check is verified after the program it checks.
+                                if config::detect_vacuity() {
+                                    match self.encode_vacuity_check(proc_def_id, &proc_name) {
+                                        Ok(Some(program)) => self.programs.push(program),
+                                        Ok(None) => {}
+                                        Err(error) => {
+                                            self.register_encoding_error(error);
+                                            debug!("Error encoding vacuity check: {:?}", proc_def_id);
+                                        }
+                                    }
+                                }
                             }
                         }
                     }
 
                 }
                 EncodingTask::Type { ty } => {
+                    self.encoded_procedure.set(None);
                     if config::unsafe_core_proof() && config::verify_core_proof() && config::verify_types() {
                         if let Err(error) = self.encode_core_proof_for_type(ty, CheckMode::CoreProof) {
                             self.register_encoding_error(error);
@@ -764,6 +988,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                 }
             }
         }
+        self.encoded_procedure.set(None);
     }
 
     pub fn intern_viper_identifier<S: AsRef<str>>(&self, full_name: S, short_name: S) -> String {
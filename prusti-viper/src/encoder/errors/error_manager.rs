@@ -182,6 +182,89 @@ pub enum ErrorCtxt {
     UnreachableFoldingState,
 }
 
+impl ErrorCtxt {
+    /// The name of the variant of the error context, e.g. `Panic` for
+    /// `Panic(PanicCause::Generic)`, which identifies the kind of the error in
+    /// verification reports.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ErrorCtxt::Panic(_) => "Panic",
+            ErrorCtxt::ExhaleMethodPrecondition => "ExhaleMethodPrecondition",
+            ErrorCtxt::UnexpectedAssumeMethodPrecondition => "UnexpectedAssumeMethodPrecondition",
+            ErrorCtxt::UnexpectedAssumeMethodPostcondition => "UnexpectedAssumeMethodPostcondition",
+            ErrorCtxt::AssertMethodPostcondition => "AssertMethodPostcondition",
+            ErrorCtxt::AssertMethodPostconditionTypeInvariants => {
+                "AssertMethodPostconditionTypeInvariants"
+            }
+            ErrorCtxt::ExhaleMethodPostcondition => "ExhaleMethodPostcondition",
+            ErrorCtxt::LoopInvariant => "LoopInvariant",
+            ErrorCtxt::ExhaleLoopInvariantOnEntry => "ExhaleLoopInvariantOnEntry",
+            ErrorCtxt::ExhaleLoopInvariantAfterIteration => "ExhaleLoopInvariantAfterIteration",
+            ErrorCtxt::AssertLoopInvariantOnEntry => "AssertLoopInvariantOnEntry",
+            ErrorCtxt::AssertLoopInvariantAfterIteration => "AssertLoopInvariantAfterIteration",
+            ErrorCtxt::UnexpectedAssumeLoopInvariantOnEntry => {
+                "UnexpectedAssumeLoopInvariantOnEntry"
+            }
+            ErrorCtxt::LoopVariant => "LoopVariant",
+            ErrorCtxt::LoopVariantOnEntry => "LoopVariantOnEntry",
+            ErrorCtxt::LoopVariantAfterIteration => "LoopVariantAfterIteration",
+            ErrorCtxt::LoopVariantNonDecreased => "LoopVariantNonDecreased",
+            ErrorCtxt::UnexpectedReachableLoop => "UnexpectedReachableLoop",
+            ErrorCtxt::UnexpectedReachableCall => "UnexpectedReachableCall",
+            ErrorCtxt::CallTerminationMeasureLower => "CallTerminationMeasureLower",
+            ErrorCtxt::CallTerminationMeasureNonNegative => "CallTerminationMeasureNonNegative",
+            ErrorCtxt::UnexpectedAssignMethodTerminationMeasure => {
+                "UnexpectedAssignMethodTerminationMeasure"
+            }
+            ErrorCtxt::AssertTerminator(_) => "AssertTerminator",
+            ErrorCtxt::BoundsCheckAssert => "BoundsCheckAssert",
+            ErrorCtxt::SliceRangeBoundsCheckAssert(_) => "SliceRangeBoundsCheckAssert",
+            ErrorCtxt::AbortTerminator => "AbortTerminator",
+            ErrorCtxt::UnreachableTerminator => "UnreachableTerminator",
+            ErrorCtxt::Unexpected => "Unexpected",
+            ErrorCtxt::UnexpectedBuiltinMethod(_) => "UnexpectedBuiltinMethod",
+            ErrorCtxt::UnexpectedStorageLive => "UnexpectedStorageLive",
+            ErrorCtxt::UnexpectedStorageDead => "UnexpectedStorageDead",
+            ErrorCtxt::MovePlace => "MovePlace",
+            ErrorCtxt::CopyPlace => "CopyPlace",
+            ErrorCtxt::WritePlace => "WritePlace",
+            ErrorCtxt::Assign => "Assign",
+            ErrorCtxt::PureFunctionDefinition => "PureFunctionDefinition",
+            ErrorCtxt::PureFunctionCall => "PureFunctionCall",
+            ErrorCtxt::PureFunctionPostconditionValueRangeOfResult => {
+                "PureFunctionPostconditionValueRangeOfResult"
+            }
+            ErrorCtxt::PureFunctionAssertTerminator(_) => "PureFunctionAssertTerminator",
+            ErrorCtxt::PackageMagicWandForPostcondition => "PackageMagicWandForPostcondition",
+            ErrorCtxt::ApplyMagicWandOnExpiry => "ApplyMagicWandOnExpiry",
+            ErrorCtxt::DivergingCallInPureFunction => "DivergingCallInPureFunction",
+            ErrorCtxt::PanicInPureFunction(_) => "PanicInPureFunction",
+            ErrorCtxt::AssertMethodPreconditionWeakening => "AssertMethodPreconditionWeakening",
+            ErrorCtxt::AssertMethodPostconditionStrengthening => {
+                "AssertMethodPostconditionStrengthening"
+            }
+            ErrorCtxt::TypeCast => "TypeCast",
+            ErrorCtxt::Unsupported(_) => "Unsupported",
+            ErrorCtxt::Unfold => "Unfold",
+            ErrorCtxt::UnfoldUnionVariant => "UnfoldUnionVariant",
+            ErrorCtxt::ProcedureCall => "ProcedureCall",
+            ErrorCtxt::DropCall => "DropCall",
+            ErrorCtxt::LifetimeEncoding => "LifetimeEncoding",
+            ErrorCtxt::LifetimeTake => "LifetimeTake",
+            ErrorCtxt::LifetimeReturn => "LifetimeReturn",
+            ErrorCtxt::LifetimeInhale => "LifetimeInhale",
+            ErrorCtxt::LifetimeExhale => "LifetimeExhale",
+            ErrorCtxt::OpenMutRef => "OpenMutRef",
+            ErrorCtxt::OpenFracRef => "OpenFracRef",
+            ErrorCtxt::CloseMutRef => "CloseMutRef",
+            ErrorCtxt::CloseFracRef => "CloseFracRef",
+            ErrorCtxt::SetEnumVariant => "SetEnumVariant",
+            ErrorCtxt::Assumption => "Assumption",
+            ErrorCtxt::UnreachableFoldingState => "UnreachableFoldingState",
+        }
+    }
+}
+
 /// The error manager
 #[derive(Clone)]
 pub struct ErrorManager<'tcx> {
@@ -256,6 +339,14 @@ impl<'tcx> ErrorManager<'tcx> {
             .and_then(|id| self.position_manager.def_id.get(&id).copied())
     }
 
+    /// The error context registered at the offending position of a
+    /// verification error, if any.
+    pub fn get_error_ctxt(&self, ver_error: &VerificationError) -> Option<&ErrorCtxt> {
+        ver_error.offending_pos_id.as_ref()
+            .and_then(|id| id.parse().ok())
+            .and_then(|id: u64| self.error_contexts.get(&id))
+    }
+
     pub fn translate_verification_error(&self, ver_error: &VerificationError) -> PrustiError {
         debug!("Verification error: {:?}", ver_error);
         let opt_pos_id: Option<u64> = match ver_error.offending_pos_id {
@@ -82,6 +82,19 @@ impl SpannedEncodingError {
         )
     }
 
+    /// The name of the kind of the error, e.g. `Unsupported`.
+    pub fn kind_name(&self) -> &'static str {
+        match self.error {
+            EncodingErrorKind::Unsupported(_) => "Unsupported",
+            EncodingErrorKind::Incorrect(_) => "Incorrect",
+            EncodingErrorKind::Internal(_) => "Internal",
+        }
+    }
+
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self.error, EncodingErrorKind::Unsupported(_))
+    }
+
     pub fn kind(&self) -> &EncodingErrorKind {
         &self.error
     }
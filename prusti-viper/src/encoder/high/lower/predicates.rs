@@ -35,6 +35,8 @@ impl IntoPredicates for vir_high::TypeDecl {
             vir_high::TypeDecl::Slice(ty_decl) => ty_decl.lower(ty, encoder),
             vir_high::TypeDecl::Sequence(_ty_decl) => unimplemented!(),
             vir_high::TypeDecl::Map(_ty_decl) => unimplemented!(),
+            vir_high::TypeDecl::Set(_ty_decl) => unimplemented!(),
+            vir_high::TypeDecl::MultiSet(_ty_decl) => unimplemented!(),
             vir_high::TypeDecl::Reference(ty_decl) => ty_decl.lower(ty, encoder),
             vir_high::TypeDecl::Pointer(ty_decl) => ty_decl.lower(ty, encoder),
             vir_high::TypeDecl::Never => construct_never_predicate(encoder),
@@ -24,6 +24,9 @@ impl IntoPolymorphic<vir_poly::Type> for vir_high::Type {
                 key_type: box ty.key_type.lower(encoder),
                 val_type: box ty.val_type.lower(encoder),
             }),
+            vir_high::Type::Set(_) | vir_high::Type::MultiSet(_) => {
+                unreachable!("Sets and multisets are used only in the unsafe core proof")
+            }
             vir_high::Type::Float(float) => {
                 vir_poly::Type::typed_ref(float.to_string().to_lowercase())
             }
@@ -47,6 +47,8 @@ pub(crate) fn create_value_field(ty: vir::Type) -> EncodingResult<vir::FieldDecl
         }
 
         vir::Type::Union(_)
+        | vir::Type::Set(_)
+        | vir::Type::MultiSet(_)
         | vir::Type::Pointer(_)
         | vir::Type::Never
         | vir::Type::Str
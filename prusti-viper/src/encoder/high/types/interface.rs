@@ -183,6 +183,12 @@ impl<'v, 'tcx: 'v> HighTypeEncoderInterface<'tcx> for super::super::super::Encod
         {
             self.queue_type_encoding(ty);
             let high_type = self.encode_type_high(ty)?;
+            if high_type.is_set() || high_type.is_multi_set() {
+                return Err(EncodingError::unsupported(format!(
+                    "{} type is supported only with the unsafe core proof",
+                    high_type
+                )));
+            }
             let polymorphic_type = high_type.lower(self);
             self.high_type_encoder_state
                 .encoded_types
@@ -269,7 +275,9 @@ impl<'v, 'tcx: 'v> HighTypeEncoderInterface<'tcx> for super::super::super::Encod
             | vir_mid::TypeDecl::Reference(_)
             | vir_mid::TypeDecl::Pointer(_)
             | vir_mid::TypeDecl::Sequence(_)
-            | vir_mid::TypeDecl::Map(_) => false,
+            | vir_mid::TypeDecl::Map(_)
+            | vir_mid::TypeDecl::Set(_)
+            | vir_mid::TypeDecl::MultiSet(_) => false,
             vir_mid::TypeDecl::Struct(decl) => decl.fields.is_empty(),
             vir_mid::TypeDecl::Enum(decl) => decl.variants.is_empty(),
             vir_mid::TypeDecl::Array(_decl) => unimplemented!(),
@@ -16,6 +16,7 @@ trait Private {
         &mut self,
         ty: &vir_mid::Type,
     ) -> SpannedEncodingResult<()>;
+    fn encode_sequence_index_of_def(&mut self, ty: &vir_mid::Type) -> SpannedEncodingResult<()>;
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> Private for Lowerer<'p, 'v, 'tcx> {
@@ -95,6 +96,83 @@ impl<'p, 'v: 'p, 'tcx: 'v> Private for Lowerer<'p, 'v, 'tcx> {
         }
         Ok(())
     }
+
+    fn encode_sequence_index_of_def(&mut self, ty: &vir_mid::Type) -> SpannedEncodingResult<()> {
+        if !self.snapshots_state.encoded_sequence_index_of.contains(ty) {
+            self.snapshots_state
+                .encoded_sequence_index_of
+                .insert(ty.clone());
+
+            let element_type = match ty {
+                vir_mid::Type::Sequence(vir_mid::ty::Sequence { element_type, .. }) => element_type,
+                _ => {
+                    unreachable!("ty: {}", ty);
+                }
+            };
+
+            let domain_name = self.encode_snapshot_domain_name(ty)?;
+            let sequence_type = vir_low::Type::seq(element_type.to_snapshot(self)?);
+            use vir_low::macros::*;
+            var_decls! {
+                sequence: {sequence_type.clone()},
+                element: {element_type.to_snapshot(self)?},
+                index: Int
+            };
+            let index_of = self.encode_sequence_index_of_call(
+                ty,
+                sequence.clone().into(),
+                element.clone().into(),
+            )?;
+            let contains = vir_low::Expression::container_op_no_pos(
+                vir_low::expression::ContainerOpKind::SeqContains,
+                sequence_type.clone(),
+                vec![element.clone().into(), sequence.clone().into()],
+            );
+            let len = vir_low::Expression::container_op_no_pos(
+                vir_low::expression::ContainerOpKind::SeqLen,
+                sequence_type.clone(),
+                vec![sequence.clone().into()],
+            );
+            let element_at_index_of = vir_low::Expression::container_op_no_pos(
+                vir_low::expression::ContainerOpKind::SeqIndex,
+                sequence_type.clone(),
+                vec![sequence.clone().into(), index_of.clone()],
+            );
+            let element_at_index = vir_low::Expression::container_op_no_pos(
+                vir_low::expression::ContainerOpKind::SeqIndex,
+                sequence_type,
+                vec![sequence.clone().into(), index.clone().into()],
+            );
+            // The result is the index of the first occurrence of the element.
+            let first = vir_low::Expression::forall(
+                vec![index.clone()],
+                vec![vir_low::Trigger::new(vec![element_at_index.clone()])],
+                expr! {
+                    (([0.into()] <= index) && (index < [index_of.clone()])) ==>
+                        ([element_at_index] != element)
+                },
+            );
+            let body = vir_low::Expression::forall(
+                vec![sequence, element.clone()],
+                vec![vir_low::Trigger::new(vec![index_of.clone()])],
+                expr! {
+                    ([contains.clone()] ==> (
+                        ([0.into()] <= [index_of.clone()]) &&
+                        ([index_of.clone()] < [len]) &&
+                        ([element_at_index_of] == element) &&
+                        [first]
+                    )) &&
+                    ((![contains]) ==> ([index_of] == [(-1).into()]))
+                },
+            );
+            let axiom = vir_low::DomainAxiomDecl {
+                name: format!("{}$sequence_index_of_definition", domain_name),
+                body,
+            };
+            self.declare_axiom(&domain_name, axiom)?;
+        }
+        Ok(())
+    }
 }
 
 pub(in super::super::super) trait BuiltinFunctionsInterface {
@@ -104,6 +182,14 @@ pub(in super::super::super) trait BuiltinFunctionsInterface {
         argument: vir_low::Expression,
         count: vir_low::Expression,
     ) -> SpannedEncodingResult<vir_low::Expression>;
+    /// Returns the index of the first occurrence of `element` in `sequence`,
+    /// or -1 if the sequence does not contain it.
+    fn encode_sequence_index_of_call(
+        &mut self,
+        ty: &vir_mid::Type,
+        sequence: vir_low::Expression,
+        element: vir_low::Expression,
+    ) -> SpannedEncodingResult<vir_low::Expression>;
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> BuiltinFunctionsInterface for Lowerer<'p, 'v, 'tcx> {
@@ -125,4 +211,21 @@ impl<'p, 'v: 'p, 'tcx: 'v> BuiltinFunctionsInterface for Lowerer<'p, 'v, 'tcx> {
             Default::default(),
         )
     }
+    fn encode_sequence_index_of_call(
+        &mut self,
+        ty: &vir_mid::Type,
+        sequence: vir_low::Expression,
+        element: vir_low::Expression,
+    ) -> SpannedEncodingResult<vir_low::Expression> {
+        self.encode_sequence_index_of_def(ty)?;
+        let domain_name = self.encode_snapshot_domain_name(ty)?;
+        let function_name = format!("sequence_index_of${}", domain_name);
+        self.create_domain_func_app(
+            domain_name,
+            function_name,
+            vec![sequence, element],
+            vir_low::Type::Int,
+            Default::default(),
+        )
+    }
 }
@@ -6,7 +6,10 @@ use crate::encoder::{
         lifetimes::*,
         lowerer::DomainsLowererInterface,
         references::ReferencesInterface,
-        snapshots::{IntoSnapshot, SnapshotDomainsInterface, SnapshotValuesInterface},
+        snapshots::{
+            BuiltinFunctionsInterface, IntoSnapshot, SnapshotDomainsInterface,
+            SnapshotValuesInterface,
+        },
         types::TypesInterface,
     },
 };
@@ -492,6 +495,34 @@ pub(super) trait IntoSnapshotLowerer<'p, 'v: 'p, 'tcx: 'v> {
             ))
         };
 
+        let set = |low_kind| {
+            Ok(vir_low::Expression::container_op(
+                low_kind,
+                vir_low::Type::set(ty_args[0].clone()),
+                args.clone(),
+                app.position,
+            ))
+        };
+
+        let multiset = |low_kind| {
+            Ok(vir_low::Expression::container_op(
+                low_kind,
+                vir_low::Type::multi_set(ty_args[0].clone()),
+                args.clone(),
+                app.position,
+            ))
+        };
+
+        // The element comes first, as in Viper's `element in multiset`.
+        let multiset_count = || {
+            vir_low::Expression::container_op(
+                ContainerOpKind::MultiSetContains,
+                vir_low::Type::multi_set(ty_args[0].clone()),
+                vec![args[1].clone(), args[0].clone()],
+                app.position,
+            )
+        };
+
         match app.function {
             BuiltinFunc::Size => {
                 let return_type = self.type_to_snapshot(lowerer, &app.return_type)?;
@@ -545,6 +576,18 @@ pub(super) trait IntoSnapshotLowerer<'p, 'v: 'p, 'tcx: 'v> {
                 let value = map(ContainerOpKind::MapLen)?;
                 lowerer.construct_constant_snapshot(app.get_type(), value, app.position)
             }
+            BuiltinFunc::MapKeys => Ok(vir_low::Expression::container_op(
+                ContainerOpKind::MapDomain,
+                vir_low::Type::set(ty_args[0].clone()),
+                args,
+                app.position,
+            )),
+            BuiltinFunc::MapValues => Ok(vir_low::Expression::container_op(
+                ContainerOpKind::MapRange,
+                vir_low::Type::set(ty_args[1].clone()),
+                args,
+                app.position,
+            )),
             BuiltinFunc::MapContains => {
                 let m = map(ContainerOpKind::MapContains)?;
                 let m = lowerer.construct_constant_snapshot(app.get_type(), m, app.position)?;
@@ -585,6 +628,150 @@ pub(super) trait IntoSnapshotLowerer<'p, 'v: 'p, 'tcx: 'v> {
                 let value = seq(ContainerOpKind::SeqLen)?;
                 lowerer.construct_constant_snapshot(app.get_type(), value, app.position)
             }
+            BuiltinFunc::UpdateSeq => {
+                assert_eq!(args.len(), 3);
+                let index = lowerer.obtain_constant_value(
+                    app.arguments[1].get_type(),
+                    args[1].clone(),
+                    app.position,
+                )?;
+                Ok(vir_low::Expression::container_op(
+                    ContainerOpKind::SeqUpdate,
+                    vir_low::Type::seq(ty_args[0].clone()),
+                    vec![args[0].clone(), index, args[2].clone()],
+                    app.position,
+                ))
+            }
+            BuiltinFunc::TakeSeq | BuiltinFunc::DropSeq => {
+                assert_eq!(args.len(), 2);
+                let count = lowerer.obtain_constant_value(
+                    app.arguments[1].get_type(),
+                    args[1].clone(),
+                    app.position,
+                )?;
+                let kind = if app.function == BuiltinFunc::TakeSeq {
+                    ContainerOpKind::SeqTake
+                } else {
+                    ContainerOpKind::SeqDrop
+                };
+                Ok(vir_low::Expression::container_op(
+                    kind,
+                    vir_low::Type::seq(ty_args[0].clone()),
+                    vec![args[0].clone(), count],
+                    app.position,
+                ))
+            }
+            BuiltinFunc::SubSeq => {
+                assert_eq!(args.len(), 3);
+                let from = lowerer.obtain_constant_value(
+                    app.arguments[1].get_type(),
+                    args[1].clone(),
+                    app.position,
+                )?;
+                let to = lowerer.obtain_constant_value(
+                    app.arguments[2].get_type(),
+                    args[2].clone(),
+                    app.position,
+                )?;
+                let prefix = vir_low::Expression::container_op(
+                    ContainerOpKind::SeqTake,
+                    vir_low::Type::seq(ty_args[0].clone()),
+                    vec![args[0].clone(), to],
+                    app.position,
+                );
+                Ok(vir_low::Expression::container_op(
+                    ContainerOpKind::SeqDrop,
+                    vir_low::Type::seq(ty_args[0].clone()),
+                    vec![prefix, from],
+                    app.position,
+                ))
+            }
+            BuiltinFunc::SeqContains => {
+                assert_eq!(args.len(), 2);
+                // The element comes first, as in Viper's `element in sequence`.
+                let value = vir_low::Expression::container_op(
+                    ContainerOpKind::SeqContains,
+                    vir_low::Type::seq(ty_args[0].clone()),
+                    vec![args[1].clone(), args[0].clone()],
+                    app.position,
+                );
+                let value =
+                    lowerer.construct_constant_snapshot(app.get_type(), value, app.position)?;
+                self.ensure_bool_expression(lowerer, app.get_type(), value, expect_math_bool)
+            }
+            BuiltinFunc::SeqIndexOf => {
+                assert_eq!(args.len(), 2);
+                let value = lowerer.encode_sequence_index_of_call(
+                    app.arguments[0].get_type(),
+                    args[0].clone(),
+                    args[1].clone(),
+                )?;
+                lowerer.construct_constant_snapshot(app.get_type(), value, app.position)
+            }
+            BuiltinFunc::AsSeq => {
+                assert_eq!(args.len(), 1);
+                // The snapshots of arrays and slices already are sequences.
+                Ok(args.pop().unwrap())
+            }
+            BuiltinFunc::EmptySet | BuiltinFunc::SingleSet => set(ContainerOpKind::SetConstructor),
+            BuiltinFunc::SetUnion => set(ContainerOpKind::SetUnion),
+            BuiltinFunc::SetIntersection => set(ContainerOpKind::SetIntersection),
+            BuiltinFunc::SetMinus => set(ContainerOpKind::SetMinus),
+            BuiltinFunc::SetSubset => {
+                let value = set(ContainerOpKind::SetSubset)?;
+                let value =
+                    lowerer.construct_constant_snapshot(app.get_type(), value, app.position)?;
+                self.ensure_bool_expression(lowerer, app.get_type(), value, expect_math_bool)
+            }
+            BuiltinFunc::SetContains => {
+                // The element comes first, as in Viper's `element in set`.
+                let value = vir_low::Expression::container_op(
+                    ContainerOpKind::SetContains,
+                    vir_low::Type::set(ty_args[0].clone()),
+                    vec![args[1].clone(), args[0].clone()],
+                    app.position,
+                );
+                let value =
+                    lowerer.construct_constant_snapshot(app.get_type(), value, app.position)?;
+                self.ensure_bool_expression(lowerer, app.get_type(), value, expect_math_bool)
+            }
+            BuiltinFunc::SetLen => {
+                let value = set(ContainerOpKind::SetCardinality)?;
+                lowerer.construct_constant_snapshot(app.get_type(), value, app.position)
+            }
+            BuiltinFunc::EmptyMultiSet | BuiltinFunc::SingleMultiSet => {
+                multiset(ContainerOpKind::MultiSetConstructor)
+            }
+            BuiltinFunc::MultiSetUnion => multiset(ContainerOpKind::MultiSetUnion),
+            BuiltinFunc::MultiSetIntersection => multiset(ContainerOpKind::MultiSetIntersection),
+            BuiltinFunc::MultiSetMinus => multiset(ContainerOpKind::MultiSetMinus),
+            BuiltinFunc::MultiSetSubset => {
+                let value = multiset(ContainerOpKind::MultiSetSubset)?;
+                let value =
+                    lowerer.construct_constant_snapshot(app.get_type(), value, app.position)?;
+                self.ensure_bool_expression(lowerer, app.get_type(), value, expect_math_bool)
+            }
+            BuiltinFunc::MultiSetContains => {
+                // In Viper, membership in a multiset yields the number of copies.
+                let count = multiset_count();
+                let value = vir_low::Expression::binary_op(
+                    vir_low::BinaryOpKind::LtCmp,
+                    0.into(),
+                    count,
+                    app.position,
+                );
+                let value =
+                    lowerer.construct_constant_snapshot(app.get_type(), value, app.position)?;
+                self.ensure_bool_expression(lowerer, app.get_type(), value, expect_math_bool)
+            }
+            BuiltinFunc::MultiSetCount => {
+                let value = multiset_count();
+                lowerer.construct_constant_snapshot(app.get_type(), value, app.position)
+            }
+            BuiltinFunc::MultiSetLen => {
+                let value = multiset(ContainerOpKind::MultiSetCardinality)?;
+                lowerer.construct_constant_snapshot(app.get_type(), value, app.position)
+            }
             BuiltinFunc::LifetimeIncluded => {
                 assert_eq!(args.len(), 2);
                 lowerer.encode_lifetime_included()?;
@@ -16,10 +16,6 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
     pub(super) fn encode_ghost_blocks(&mut self) -> SpannedEncodingResult<()> {
         let ghost_blocks = self.specification_blocks.ghost_blocks();
 
-        if ghost_blocks.is_empty() {
-            return Ok(());
-        }
-
         // Check that ghost blocks don't transfer control flow outside
         for &bb in ghost_blocks {
             let data = &self.mir.basic_blocks[bb];
@@ -39,19 +35,21 @@ impl<'p, 'v: 'p, 'tcx: 'v> super::ProcedureEncoder<'p, 'v, 'tcx> {
 
 struct GhostChecker<'a, 'p: 'a, 'v: 'p, 'tcx: 'v> {
     p: &'a ProcedureEncoder<'p, 'v, 'tcx>,
+    /// The body of a lemma is ghost code as a whole.
+    in_lemma: bool,
     normal_vars: FxHashSet<mir::Local>,
     violations: Vec<SpannedEncodingError>,
 }
 
 impl<'a, 'p, 'v, 'tcx> GhostChecker<'a, 'p, 'v, 'tcx> {
     fn is_ghost_place(&self, location: mir::Location) -> bool {
-        self.p.specification_blocks.is_ghost_block(location.block)
+        self.in_lemma || self.p.specification_blocks.is_ghost_block(location.block)
     }
     fn is_ghost_local(&self, local: &mir::Local) -> bool {
         let ty = &self.p.mir.local_decls[*local].ty;
         let ty_str = format!("{:?}", ty);
 
-        let ghost_tys = ["Ghost", "Int", "Seq", "Map"];
+        let ghost_tys = ["Ghost", "Int", "Seq", "Map", "Set", "Multiset"];
 
         for ghost in ghost_tys {
             if ty_str.starts_with(&format!("prusti_contracts::{ghost}")) {
@@ -65,6 +63,7 @@ impl<'a, 'p, 'v, 'tcx> GhostChecker<'a, 'p, 'v, 'tcx> {
     fn find_violations(p: &ProcedureEncoder<'p, 'v, 'tcx>) -> Vec<SpannedEncodingError> {
         let mut checker = GhostChecker {
             p,
+            in_lemma: p.encoder.is_lemma(p.def_id),
             normal_vars: Default::default(),
             violations: Default::default(),
         };
@@ -103,21 +102,42 @@ impl<'a, 'p, 'v, 'tcx> Visitor<'tcx> for GhostChecker<'a, 'p, 'v, 'tcx> {
                     ..
                 } => {
                     if let ty::TyKind::FnDef(def_id, _call_substs) = literal.ty().kind() {
-                        if !self.p.encoder.is_pure(*def_id, None) {
+                        if !self.p.encoder.is_pure(*def_id, None)
+                            && !self.p.encoder.is_lemma(*def_id)
+                        {
                             self.violations.push(SpannedEncodingError::incorrect(
                                 "Only pure function calls are allowed in ghost blocks.",
                                 term.source_info.span,
                             ));
                         }
                     } else {
-                        unimplemented!();
+                        self.violations.push(SpannedEncodingError::unsupported(
+                            "calls of closures and function pointers are not supported in ghost code",
+                            term.source_info.span,
+                        ));
                     }
                 }
                 mir::TerminatorKind::Call { .. } => {
-                    unimplemented!();
+                    self.violations.push(SpannedEncodingError::unsupported(
+                        "calls of closures and function pointers are not supported in ghost code",
+                        term.source_info.span,
+                    ));
                 }
                 _ => (),
             }
+        } else if let mir::TerminatorKind::Call {
+            func: mir::Operand::Constant(box mir::Constant { literal, .. }),
+            ..
+        } = &term.kind
+        {
+            if let ty::TyKind::FnDef(def_id, _call_substs) = literal.ty().kind() {
+                if self.p.encoder.is_lemma(*def_id) {
+                    self.violations.push(SpannedEncodingError::incorrect(
+                        "Lemmas can only be applied in ghost code, for example with `prusti_apply!`.",
+                        term.source_info.span,
+                    ));
+                }
+            }
         }
         self.super_terminator(term, location);
     }
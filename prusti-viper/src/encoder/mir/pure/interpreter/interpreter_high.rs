@@ -562,7 +562,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
 
             let key_type = type_arguments[0].clone();
             let val_type = type_arguments[1].clone();
-            let map_type = Type::map(key_type, val_type.clone(), lifetimes);
+            let map_type = Type::map(key_type.clone(), val_type.clone(), lifetimes.clone());
 
             return builtin(match proc_name {
                 "empty" => (EmptyMap, map_type),
@@ -571,6 +571,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
                 "lookup" => (LookupMap, val_type),
                 "delete" => unimplemented!(),
                 "contains" => (MapContains, Type::bool()),
+                "keys" => (MapKeys, Type::set(key_type, lifetimes)),
+                "values" => (MapValues, Type::set(val_type, lifetimes)),
                 _ => unreachable!("no further Map functions"),
             });
         } else if let Some(proc_name) = proc_name.strip_prefix("prusti_contracts::Seq::<T>::") {
@@ -585,8 +587,50 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
                 "len" => (SeqLen, Type::Int(vir_high::ty::Int::Unbounded)),
                 "lookup" => (LookupSeq, elem_type),
                 "concat" => (ConcatSeq, seq_type),
+                "update" => (UpdateSeq, seq_type),
+                "take" => (TakeSeq, seq_type),
+                "drop" => (DropSeq, seq_type),
+                "subsequence" => (SubSeq, seq_type),
+                "contains" => (SeqContains, Type::bool()),
+                "index_of" => (SeqIndexOf, Type::Int(Int::Unbounded)),
                 _ => unreachable!("no further Seq functions"),
             });
+        } else if let Some(proc_name) = proc_name.strip_prefix("prusti_contracts::Set::<T>::") {
+            assert_eq!(type_arguments.len(), 1);
+
+            let elem_type = type_arguments[0].clone();
+            let set_type = Type::set(elem_type, lifetimes);
+
+            return builtin(match proc_name {
+                "empty" => (EmptySet, set_type),
+                "single" => (SingleSet, set_type),
+                "union" => (SetUnion, set_type),
+                "intersection" => (SetIntersection, set_type),
+                "difference" => (SetMinus, set_type),
+                "subset" => (SetSubset, Type::bool()),
+                "contains" => (SetContains, Type::bool()),
+                "len" => (SetLen, Type::Int(Int::Unbounded)),
+                _ => unreachable!("no further Set functions"),
+            });
+        } else if let Some(proc_name) = proc_name.strip_prefix("prusti_contracts::Multiset::<T>::")
+        {
+            assert_eq!(type_arguments.len(), 1);
+
+            let elem_type = type_arguments[0].clone();
+            let multiset_type = Type::multi_set(elem_type, lifetimes);
+
+            return builtin(match proc_name {
+                "empty" => (EmptyMultiSet, multiset_type),
+                "single" => (SingleMultiSet, multiset_type),
+                "union" => (MultiSetUnion, multiset_type),
+                "intersection" => (MultiSetIntersection, multiset_type),
+                "difference" => (MultiSetMinus, multiset_type),
+                "subset" => (MultiSetSubset, Type::bool()),
+                "contains" => (MultiSetContains, Type::bool()),
+                "count" => (MultiSetCount, Type::Int(Int::Unbounded)),
+                "len" => (MultiSetLen, Type::Int(Int::Unbounded)),
+                _ => unreachable!("no further Multiset functions"),
+            });
         } else if let Some(proc_name) = proc_name.strip_prefix("prusti_contracts::Int::") {
             assert!(type_arguments.is_empty());
             return match proc_name {
@@ -599,6 +643,33 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
                 "new" => subst_with(encoded_args[0].clone()),
                 _ => unreachable!("no further Ghost functions."),
             };
+        } else if proc_name == "prusti_contracts::AsSeq::as_seq" {
+            assert_eq!(encoded_args.len(), 1);
+            // Arrays and slices are modelled directly. Other implementations
+            // (for example, `Vec`) are encoded as ordinary pure calls below.
+            if let Type::Reference(Reference {
+                target_type:
+                    box (Type::Array(Array {
+                        element_type,
+                        lifetimes,
+                        ..
+                    })
+                    | Type::Slice(Slice {
+                        element_type,
+                        lifetimes,
+                    })),
+                ..
+            }) = encoded_args[0].get_type()
+            {
+                let elem_type = (**element_type).clone();
+                let seq_type = Type::sequence(elem_type.clone(), lifetimes.clone());
+                return subst_with(vir_high::Expression::builtin_func_app_no_pos(
+                    AsSeq,
+                    vec![elem_type],
+                    encoded_args.into(),
+                    seq_type,
+                ));
+            }
         }
 
         // replace all the operations on Ints
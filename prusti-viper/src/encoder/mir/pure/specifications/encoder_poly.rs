@@ -8,7 +8,13 @@ use crate::encoder::{
     errors::{EncodingError, EncodingResult, SpannedEncodingResult, WithSpan},
     high::types::HighTypeEncoderInterface,
     mir::{
-        pure::{specifications::utils::extract_closure_from_ty, PureFunctionEncoderInterface},
+        pure::{
+            specifications::{
+                triggers::{find_matching_loop, has_function_applications_over, infer_triggers},
+                utils::extract_closure_from_ty,
+            },
+            PureFunctionEncoderInterface,
+        },
         types::MirTypeEncoderInterface,
     },
     mir_encoder::{MirEncoder, PlaceEncoder},
@@ -16,6 +22,7 @@ use crate::encoder::{
     Encoder,
 };
 use prusti_common::config;
+use prusti_interface::PrustiError;
 use prusti_rustc_interface::{
     errors::MultiSpan,
     hir::def_id::DefId,
@@ -126,7 +133,7 @@ pub(super) fn inline_spec_item<'tcx>(
 
 pub(super) fn encode_quantifier<'tcx>(
     encoder: &Encoder<'_, 'tcx>,
-    _span: Span,
+    span: Span,
     encoded_args: Vec<vir_crate::polymorphic::Expr>,
     is_exists: bool,
     parent_def_id: DefId,
@@ -232,6 +239,19 @@ pub(super) fn encode_quantifier<'tcx>(
         body_substs,
     )?;
 
+    let has_explicit_triggers = !encoded_trigger_sets.is_empty();
+    if !has_explicit_triggers && config::infer_triggers() {
+        encoded_trigger_sets = infer_triggers(&encoded_qvars, &encoded_body);
+    }
+    report_triggers(
+        encoder,
+        span,
+        &encoded_qvars,
+        &encoded_trigger_sets,
+        &encoded_body,
+        has_explicit_triggers,
+    );
+
     // replace qvars with a nicer name based on quantifier depth to ensure that
     // quantifiers remain stable for caching
     let quantifier_depth = find_quantifier_depth(&encoded_body);
@@ -280,6 +300,108 @@ pub(super) fn encode_quantifier<'tcx>(
     }
 }
 
+/// Records the triggers of a quantifier, which are printed with the
+/// type-checked specifications when `print_typeckd_specs` is enabled, and warns
+/// if the quantifier might cause a matching loop or, when inferring triggers,
+/// if no valid trigger exists for the function applications in its body. Every
+/// quantifier is reported only once, even if it is encoded several times.
+fn report_triggers(
+    encoder: &Encoder<'_, '_>,
+    span: Span,
+    bound_vars: &[vir_crate::polymorphic::LocalVar],
+    trigger_sets: &[vir_crate::polymorphic::Trigger],
+    body: &vir_crate::polymorphic::Expr,
+    has_explicit_triggers: bool,
+) {
+    if encoder.quantifier_triggers.borrow().contains_key(&span) {
+        return;
+    }
+    let rendered_trigger_sets = format!(
+        "[{}]",
+        trigger_sets
+            .iter()
+            .map(|trigger_set| {
+                let terms = trigger_set
+                    .elements()
+                    .iter()
+                    .map(|term| render_trigger_term(encoder, term))
+                    .collect::<Vec<_>>();
+                if terms.len() == 1 {
+                    format!("({},)", terms[0])
+                } else {
+                    format!("({})", terms.join(", "))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    encoder.quantifier_triggers.borrow_mut().insert(
+        span,
+        format!(
+            "{}: {}",
+            if has_explicit_triggers {
+                ""
+            } else if config::infer_triggers() {
+                " (inferred)"
+            } else {
+                " (left to the verifier)"
+            },
+            rendered_trigger_sets
+        ),
+    );
+    if trigger_sets.is_empty() {
+        // Without any function application over the bound variables there is
+        // nothing to warn about; the choice is left to the verifier.
+        if config::infer_triggers() && has_function_applications_over(bound_vars, body) {
+            PrustiError::warning(
+                "no valid trigger could be inferred for this quantifier, so it might never \
+                be instantiated",
+                span.into(),
+            )
+            .set_help(
+                "specify the triggers with `triggers = [...]`; the terms of a trigger set must be \
+                calls of pure functions that together mention all quantified variables",
+            )
+            .emit(&encoder.env().diagnostic);
+        }
+        return;
+    }
+    for trigger_set in trigger_sets {
+        if let Some((trigger_term, instance)) = find_matching_loop(bound_vars, trigger_set, body) {
+            PrustiError::warning(
+                format!(
+                    "the triggers {} of this quantifier might cause a matching loop",
+                    rendered_trigger_sets
+                ),
+                span.into(),
+            )
+            .add_note(
+                format!(
+                    "instantiating the quantifier produces the term `{}`, which matches the \
+                    trigger `{}` again",
+                    render_trigger_term(encoder, &instance),
+                    render_trigger_term(encoder, &trigger_term),
+                ),
+                None,
+            )
+            .emit(&encoder.env().diagnostic);
+            return;
+        }
+    }
+}
+
+/// Renders a trigger term as the source code from which it was encoded, if
+/// available.
+fn render_trigger_term(encoder: &Encoder<'_, '_>, term: &vir_crate::polymorphic::Expr) -> String {
+    let span = encoder
+        .error_manager()
+        .position_manager()
+        .get_span(term.pos())
+        .and_then(|span| span.primary_span());
+    span.and_then(|span| encoder.env().query.codemap().span_to_snippet(span).ok())
+        .unwrap_or_else(|| term.to_string())
+}
+
 fn find_quantifier_depth(expr: &vir_crate::polymorphic::Expr) -> usize {
     use vir_crate::polymorphic::ExprWalker;
     struct DepthChecker {
@@ -9,6 +9,7 @@
 mod encoder_high;
 mod encoder_poly;
 mod interface;
+mod triggers;
 mod utils;
 
 pub(crate) use interface::SpecificationEncoderInterface;
@@ -0,0 +1,242 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Inference of quantifier triggers and detection of potential matching
+//! loops.
+
+use vir_crate::polymorphic::{self as vir_poly, ExprWalker};
+
+/// Infers the trigger sets of a quantifier over `bound_vars` with the given
+/// body. Returns an empty vector if no valid trigger set could be found.
+///
+/// A trigger term is a function application that mentions at least one bound
+/// variable and only consists of function applications, fields, variables and
+/// constants. If some terms mention all bound variables, each of the smallest
+/// such terms becomes a trigger set of its own. Otherwise, a single trigger set
+/// covering all bound variables is built from several terms.
+pub(super) fn infer_triggers(
+    bound_vars: &[vir_poly::LocalVar],
+    body: &vir_poly::Expr,
+) -> Vec<vir_poly::Trigger> {
+    let candidates: Vec<_> = collect_function_applications(body)
+        .into_iter()
+        .filter(is_valid_trigger_term)
+        .map(|term| {
+            let mentioned_vars = mentioned_bound_vars(bound_vars, &term);
+            (term, mentioned_vars)
+        })
+        .filter(|(_, mentioned_vars)| !mentioned_vars.is_empty())
+        .collect();
+    // A term that contains a smaller term mentioning the same bound variables
+    // would only make the quantifier harder to instantiate.
+    let minimal_candidates: Vec<_> = candidates
+        .iter()
+        .filter(|(term, vars)| {
+            !candidates.iter().any(|(other, other_vars)| {
+                other != term && term.find(other) && vars.iter().all(|var| other_vars.contains(var))
+            })
+        })
+        .collect();
+
+    let single_term_triggers: Vec<_> = minimal_candidates
+        .iter()
+        .filter(|(_, vars)| vars.len() == bound_vars.len())
+        .map(|(term, _)| vir_poly::Trigger::new(vec![term.clone()]))
+        .collect();
+    if !single_term_triggers.is_empty() {
+        return single_term_triggers;
+    }
+
+    let mut sorted_candidates = minimal_candidates;
+    sorted_candidates.sort_by_key(|(_, vars)| std::cmp::Reverse(vars.len()));
+    let mut covered_vars = vec![];
+    let mut terms = vec![];
+    for (term, vars) in sorted_candidates {
+        if vars.iter().any(|var| !covered_vars.contains(var)) {
+            covered_vars.extend(vars.iter().cloned());
+            terms.push(term.clone());
+        }
+    }
+    if bound_vars.iter().all(|var| covered_vars.contains(&var)) {
+        vec![vir_poly::Trigger::new(terms)]
+    } else {
+        vec![]
+    }
+}
+
+/// Checks whether some function application in `body` mentions one of the
+/// bound variables, regardless of whether it is a valid trigger term.
+pub(super) fn has_function_applications_over(
+    bound_vars: &[vir_poly::LocalVar],
+    body: &vir_poly::Expr,
+) -> bool {
+    collect_function_applications(body)
+        .iter()
+        .any(|term| !mentioned_bound_vars(bound_vars, term).is_empty())
+}
+
+/// Checks whether instantiating a quantifier through `trigger_set` may produce
+/// new terms that match the trigger set again. Returns the trigger term and the
+/// term of the body that matches it, if any.
+///
+/// This is a heuristic: it reports every term of the body that is an instance
+/// of one of the trigger terms, but differs from all of them.
+pub(super) fn find_matching_loop(
+    bound_vars: &[vir_poly::LocalVar],
+    trigger_set: &vir_poly::Trigger,
+    body: &vir_poly::Expr,
+) -> Option<(vir_poly::Expr, vir_poly::Expr)> {
+    let body_terms = collect_function_applications(body);
+    for trigger_term in trigger_set.elements() {
+        for body_term in &body_terms {
+            if !trigger_set.elements().contains(body_term)
+                && !mentioned_bound_vars(bound_vars, body_term).is_empty()
+                && matches(bound_vars, trigger_term, body_term, &mut vec![])
+            {
+                return Some((trigger_term.clone(), body_term.clone()));
+            }
+        }
+    }
+    None
+}
+
+/// Collects the function applications of `expr` that could be instantiated
+/// with the bound variables of the enclosing quantifier, i.e. those that are
+/// not inside nested quantifiers, old expressions or unfoldings.
+fn collect_function_applications(expr: &vir_poly::Expr) -> Vec<vir_poly::Expr> {
+    struct Collector {
+        terms: Vec<vir_poly::Expr>,
+    }
+    impl ExprWalker for Collector {
+        fn walk(&mut self, expr: &vir_poly::Expr) {
+            match expr {
+                vir_poly::Expr::FuncApp(..) | vir_poly::Expr::DomainFuncApp(..) => {
+                    if !self.terms.contains(expr) {
+                        self.terms.push(expr.clone());
+                    }
+                }
+                vir_poly::Expr::ForAll(..)
+                | vir_poly::Expr::Exists(..)
+                | vir_poly::Expr::LabelledOld(..)
+                | vir_poly::Expr::Unfolding(..) => return,
+                _ => {}
+            }
+            vir_poly::default_walk_expr(self, expr)
+        }
+    }
+    let mut collector = Collector { terms: vec![] };
+    collector.walk(expr);
+    collector.terms
+}
+
+fn is_valid_trigger_term(term: &vir_poly::Expr) -> bool {
+    fn is_valid_argument(arg: &vir_poly::Expr) -> bool {
+        match arg {
+            vir_poly::Expr::Local(..) | vir_poly::Expr::Const(..) => true,
+            vir_poly::Expr::Field(vir_poly::FieldExpr { base, .. })
+            | vir_poly::Expr::Variant(vir_poly::Variant { base, .. })
+            | vir_poly::Expr::SnapApp(vir_poly::SnapApp { base, .. }) => is_valid_argument(base),
+            vir_poly::Expr::FuncApp(..) | vir_poly::Expr::DomainFuncApp(..) => {
+                is_valid_trigger_term(arg)
+            }
+            _ => false,
+        }
+    }
+    match term {
+        vir_poly::Expr::FuncApp(vir_poly::FuncApp { arguments, .. })
+        | vir_poly::Expr::DomainFuncApp(vir_poly::DomainFuncApp { arguments, .. }) => {
+            arguments.iter().all(is_valid_argument)
+        }
+        _ => false,
+    }
+}
+
+fn mentioned_bound_vars<'a>(
+    bound_vars: &'a [vir_poly::LocalVar],
+    term: &vir_poly::Expr,
+) -> Vec<&'a vir_poly::LocalVar> {
+    bound_vars
+        .iter()
+        .filter(|var| term.find(&vir_poly::Expr::local((*var).clone())))
+        .collect()
+}
+
+/// Checks whether `term` is an instance of `pattern`, in which the bound
+/// variables are the pattern variables.
+fn matches<'a>(
+    bound_vars: &[vir_poly::LocalVar],
+    pattern: &'a vir_poly::Expr,
+    term: &'a vir_poly::Expr,
+    bindings: &mut Vec<(&'a vir_poly::LocalVar, &'a vir_poly::Expr)>,
+) -> bool {
+    match (pattern, term) {
+        (vir_poly::Expr::Local(vir_poly::Local { variable, .. }), _)
+            if bound_vars.contains(variable) =>
+        {
+            if let Some((_, bound_term)) = bindings.iter().find(|(var, _)| *var == variable) {
+                *bound_term == term
+            } else {
+                bindings.push((variable, term));
+                true
+            }
+        }
+        (
+            vir_poly::Expr::FuncApp(vir_poly::FuncApp {
+                function_name: pattern_name,
+                arguments: pattern_args,
+                ..
+            }),
+            vir_poly::Expr::FuncApp(vir_poly::FuncApp {
+                function_name: term_name,
+                arguments: term_args,
+                ..
+            }),
+        ) => {
+            pattern_name == term_name && matches_all(bound_vars, pattern_args, term_args, bindings)
+        }
+        (
+            vir_poly::Expr::DomainFuncApp(vir_poly::DomainFuncApp {
+                domain_function: pattern_function,
+                arguments: pattern_args,
+                ..
+            }),
+            vir_poly::Expr::DomainFuncApp(vir_poly::DomainFuncApp {
+                domain_function: term_function,
+                arguments: term_args,
+                ..
+            }),
+        ) => {
+            pattern_function == term_function
+                && matches_all(bound_vars, pattern_args, term_args, bindings)
+        }
+        (
+            vir_poly::Expr::Field(vir_poly::FieldExpr {
+                base: pattern_base,
+                field: pattern_field,
+                ..
+            }),
+            vir_poly::Expr::Field(vir_poly::FieldExpr {
+                base: term_base,
+                field: term_field,
+                ..
+            }),
+        ) => pattern_field == term_field && matches(bound_vars, pattern_base, term_base, bindings),
+        _ => pattern == term,
+    }
+}
+
+fn matches_all<'a>(
+    bound_vars: &[vir_poly::LocalVar],
+    patterns: &'a [vir_poly::Expr],
+    terms: &'a [vir_poly::Expr],
+    bindings: &mut Vec<(&'a vir_poly::LocalVar, &'a vir_poly::Expr)>,
+) -> bool {
+    patterns.len() == terms.len()
+        && patterns
+            .iter()
+            .zip(terms)
+            .all(|(pattern, term)| matches(bound_vars, pattern, term, bindings))
+}
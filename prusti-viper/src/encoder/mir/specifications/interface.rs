@@ -85,6 +85,9 @@ pub(crate) trait SpecificationsInterface<'tcx> {
 
     fn terminates(&self, def_id: DefId, substs: Option<SubstsRef<'tcx>>) -> bool;
 
+    /// Is the procedure a lemma, i.e. marked with `#[lemma]`?
+    fn is_lemma(&self, def_id: DefId) -> bool;
+
     /// Get the loop invariant attached to a function with a
     /// `prusti::loop_body_invariant_spec` attribute.
     fn get_loop_specs(&self, def_id: DefId) -> Option<typed::LoopSpecification>;
@@ -138,6 +141,9 @@ impl<'v, 'tcx: 'v> SpecificationsInterface<'tcx> for super::super::super::Encode
         let func_name = self.env().name.get_unique_item_name(def_id);
         if func_name.starts_with("prusti_contracts::prusti_contracts::Map")
             || func_name.starts_with("prusti_contracts::prusti_contracts::Seq")
+            || func_name.starts_with("prusti_contracts::prusti_contracts::AsSeq")
+            || func_name.starts_with("prusti_contracts::prusti_contracts::Set")
+            || func_name.starts_with("prusti_contracts::prusti_contracts::Multiset")
             || func_name.starts_with("prusti_contracts::prusti_contracts::Ghost")
             || func_name.starts_with("prusti_contracts::prusti_contracts::Int")
         {
@@ -209,6 +215,10 @@ impl<'v, 'tcx: 'v> SpecificationsInterface<'tcx> for super::super::super::Encode
         result
     }
 
+    fn is_lemma(&self, def_id: DefId) -> bool {
+        self.env().query.has_prusti_attribute(def_id, "lemma")
+    }
+
     fn get_loop_specs(&self, def_id: DefId) -> Option<typed::LoopSpecification> {
         self.specifications_state
             .specs
@@ -21,6 +21,7 @@ mod procedure_encoder;
 mod stub_function_encoder;
 mod stub_procedure_encoder;
 mod utils;
+pub(crate) mod vacuity;
 mod snapshot;
 mod mirror_function_encoder;
 mod mir;
@@ -80,7 +80,8 @@ use crate::encoder::mir::{
     type_invariants::TypeInvariantEncoderInterface,
 };
 use super::high::generics::HighGenericsEncoderInterface;
-use super::counterexamples::DiscriminantsStateInterface;
+use super::counterexamples::{DiscriminantsStateInterface, ProgramPoint, ProgramPointsStateInterface};
+use super::vacuity::{VacuityProbe, VacuityProbeKind, VacuityStateInterface};
 use prusti_interface::environment::mir_utils::SliceOrArrayRef;
 
 pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
@@ -237,9 +238,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, cl_substs), _),
             )) = stmt.kind
             {
-                if self.encoder.get_prusti_assumption(cl_def_id.to_def_id()).is_none() {
-                    return Ok(false);
-                }
+                let assumption = match self.encoder.get_prusti_assumption(cl_def_id.to_def_id()) {
+                    Some(spec) => spec,
+                    None => return Ok(false),
+                };
                 let assume_expr = self.encoder.encode_invariant(self.mir, bb, self.proc_def_id, cl_substs)?;
 
                 let assume_stmt = vir::Stmt::Inhale(
@@ -250,6 +252,16 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
                 encoded_statements.push(assume_stmt);
 
+                let span = self
+                    .encoder
+                    .get_definition_span(assumption.assumption.to_def_id());
+                encoded_statements.extend(self.encode_vacuity_probe(
+                    "this assumption contradicts the preceding code or specifications, \
+                    so the code after it is unreachable and verified vacuously",
+                    span,
+                    VacuityProbeKind::Assumption,
+                ));
+
                 return Ok(true);
             }
         }
@@ -362,6 +374,15 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         trace!("Encode procedure {}", self.cfg_method.name());
         let mir_span = self.mir.span;
 
+        // The bodies of lemmas are checked to be ghost code that terminates
+        // only by the core proof encoding.
+        if self.encoder.is_lemma(self.proc_def_id) {
+            return Err(SpannedEncodingError::unsupported(
+                "lemmas are supported only with `unsafe_core_proof` enabled",
+                mir_span,
+            ));
+        }
+
         // Retrieve the contract
         let procedure_contract = self.encoder
             .get_procedure_contract_for_def(self.proc_def_id, self.substs)
@@ -422,6 +443,13 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         );
         self.cfg_method
             .set_successor(return_cfg_block, Successor::Return);
+        if let Some(probe_stmt) = self.encode_vacuity_probe(
+            "the end of this function is unreachable, so its postcondition is verified vacuously",
+            self.encoder.env().query.get_def_span(self.proc_def_id),
+            VacuityProbeKind::End,
+        ) {
+            self.cfg_method.add_stmt(return_cfg_block, probe_stmt);
+        }
 
         // Encode a flag that becomes true the first time a block is executed
         for bbi in self.procedure.get_reachable_nonspec_cfg_blocks() {
@@ -936,6 +964,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 self.encode_loop_invariant_inhale_fnspec_stmts(loop_head, before_invariant_block, false)?;
             self.cfg_method.add_stmts(inv_post_block_fnspc, stmts); fnspec_span
         };
+        self.encode_program_point_label(
+            inv_post_block_fnspc,
+            format!("{}_start_trace", loop_label_prefix),
+            "at the start of a loop iteration",
+            self.get_loop_span(loop_head),
+        );
         {
             let stmts =
                 self.encode_loop_invariant_inhale_perm_stmts(loop_head, before_invariant_block, false).with_span(fnspec_span)?;
@@ -1028,6 +1062,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 loop_label_prefix
             ))],
         );
+        self.encode_program_point_label(
+            end_body_block,
+            format!("{}_end_trace", loop_label_prefix),
+            "at the end of the loop iteration",
+            self.get_loop_span(loop_head),
+        );
         {
             let stmts = self.encode_loop_invariant_exhale_stmts(
                 loop_head,
@@ -1224,6 +1264,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
 
         self.encode_execution_flag(bbi, curr_block)?;
+        self.encode_program_point_label(
+            curr_block,
+            format!("{}{:?}_trace", label_prefix, bbi),
+            "before executing this code",
+            self.mir_encoder.get_span_of_basic_block(bbi),
+        );
         let opt_successor = self.encode_block_statements(bbi, curr_block)?;
         let mir_successor: MirSuccessor = if let Some(successor) = opt_successor {
             // In case of unsupported statements, we do not encode the terminator
@@ -1292,6 +1338,54 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(())
     }
 
+    /// Labels the state at a program point, so that the counterexample trace
+    /// reports the values of the local variables at that point.
+    fn encode_program_point_label(
+        &mut self,
+        cfg_block: CfgBlockIndex,
+        label: String,
+        description: &str,
+        span: Span,
+    ) {
+        if config::counterexample() && config::counterexample_trace() {
+            self.cfg_method.add_stmt(cfg_block, vir::Stmt::label(label.clone()));
+            self.encoder.add_program_point(
+                self.proc_def_id,
+                label,
+                ProgramPoint {
+                    description: description.to_string(),
+                    span,
+                },
+            );
+        }
+    }
+
+    /// Labels a point of the procedure whose reachability is checked by
+    /// vacuity detection, which reports the given message if the point is
+    /// unreachable. Returns the label statement, if vacuity detection is
+    /// enabled.
+    fn encode_vacuity_probe(
+        &mut self,
+        message: &str,
+        span: impl Into<MultiSpan>,
+        kind: VacuityProbeKind,
+    ) -> Option<vir::Stmt> {
+        if !config::detect_vacuity() {
+            return None;
+        }
+        let label = self.cfg_method.get_fresh_label_name();
+        self.encoder.add_vacuity_probe(
+            self.proc_def_id,
+            VacuityProbe {
+                label: label.clone(),
+                message: message.to_string(),
+                span: span.into(),
+                kind,
+            },
+        );
+        Some(vir::Stmt::label(label))
+    }
+
     /// Encode the statements of the block.
     /// In case of unsupported statements, this function will return `MirSuccessor::Kill`.
     fn encode_block_statements(
@@ -3172,6 +3266,13 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             .get_absolute_item_name(called_def_id);
         debug!("Encoding non-pure function call '{}' with args {:?} and substs {:?}", full_func_proc_name, mir_args, substs);
 
+        if self.encoder.is_lemma(called_def_id) {
+            return Err(SpannedEncodingError::unsupported(
+                "applying lemmas is supported only with `unsafe_core_proof` enabled",
+                call_site_span,
+            ));
+        }
+
         // Spans for fake exprs that cannot be encoded in viper
         let mut fake_expr_spans: FxHashMap<Local, Span> = FxHashMap::default();
 
@@ -3534,6 +3635,15 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         // Emit the label and magic wands
         stmts.push(vir::Stmt::label(post_label.clone()));
 
+        if target.is_some() && self.encoder.is_trusted(called_def_id, Some(substs)) {
+            stmts.extend(self.encode_vacuity_probe(
+                "the postcondition of this trusted function contradicts the preceding code \
+                or specifications, so the code after the call is unreachable and verified vacuously",
+                call_site_span,
+                VacuityProbeKind::Assumption,
+            ));
+        }
+
         stmts.extend(stmts_after);
 
         self.procedure_contracts
@@ -4126,6 +4236,22 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             start_cfg_block,
             vir::Stmt::label(PRECONDITION_LABEL),
         );
+        let precondition_spans: Vec<_> = self
+            .procedure_contract()
+            .functional_precondition(self.encoder.env(), self.substs)
+            .iter()
+            .map(|(ts, _)| self.encoder.env().query.get_def_span(ts))
+            .collect();
+        if !precondition_spans.is_empty() {
+            if let Some(probe_stmt) = self.encode_vacuity_probe(
+                "the preconditions of this function contradict each other, \
+                so the function is verified vacuously",
+                MultiSpan::from_spans(precondition_spans),
+                VacuityProbeKind::Assumption,
+            ) {
+                self.cfg_method.add_stmt(start_cfg_block, probe_stmt);
+            }
+        }
         Ok(())
     }
 
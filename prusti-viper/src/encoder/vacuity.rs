@@ -0,0 +1,86 @@
+use prusti_interface::data::ProcedureDefId;
+use prusti_rustc_interface::errors::MultiSpan;
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use vir_crate::polymorphic as vir;
+
+/// A labelled point of a procedure that should be reachable. Vacuity
+/// detection checks whether `false` can be proved at that point.
+#[derive(Clone, Debug)]
+pub(crate) struct VacuityProbe {
+    pub(crate) label: String,
+    /// The warning reported if the point turns out to be unreachable.
+    pub(crate) message: String,
+    /// The span of the code that makes the point unreachable, e.g. the
+    /// contradicting assumption.
+    pub(crate) span: MultiSpan,
+    pub(crate) kind: VacuityProbeKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VacuityProbeKind {
+    /// The point right after something is assumed: the preconditions, a
+    /// `prusti_assume!` or the postcondition of a trusted callee.
+    Assumption,
+    /// The end of the procedure. If it is unreachable, the warning is
+    /// reported at the unreachable assumptions, if any, since they explain
+    /// why.
+    End,
+}
+
+/// The probes checked by a program encoded for vacuity detection.
+#[derive(Clone, Debug)]
+pub(crate) struct VacuityCheck {
+    /// The name of the program verifying the procedure. The probes are
+    /// meaningful only if that program verifies.
+    pub(crate) verified_program: String,
+    /// Each probe, with the position of the `assert false` checking it.
+    pub(crate) probes: Vec<(VacuityProbe, vir::Position)>,
+}
+
+#[derive(Default)]
+pub(crate) struct VacuityState {
+    /// The probes of each procedure.
+    probes: RefCell<FxHashMap<ProcedureDefId, Vec<VacuityProbe>>>,
+    /// The encoded vacuity checks, by program name.
+    checks: RefCell<FxHashMap<String, VacuityCheck>>,
+}
+
+pub(crate) trait VacuityStateInterface {
+    fn add_vacuity_probe(&self, proc_def_id: ProcedureDefId, probe: VacuityProbe);
+    fn vacuity_probes(&self, proc_def_id: ProcedureDefId) -> Vec<VacuityProbe>;
+    fn add_vacuity_check(&self, program_name: String, check: VacuityCheck);
+    fn get_vacuity_check(&self, program_name: &str) -> Option<VacuityCheck>;
+}
+
+impl<'v, 'tcx: 'v> VacuityStateInterface for super::Encoder<'v, 'tcx> {
+    fn add_vacuity_probe(&self, proc_def_id: ProcedureDefId, probe: VacuityProbe) {
+        self.vacuity_state
+            .probes
+            .borrow_mut()
+            .entry(proc_def_id)
+            .or_default()
+            .push(probe);
+    }
+    fn vacuity_probes(&self, proc_def_id: ProcedureDefId) -> Vec<VacuityProbe> {
+        self.vacuity_state
+            .probes
+            .borrow()
+            .get(&proc_def_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+    fn add_vacuity_check(&self, program_name: String, check: VacuityCheck) {
+        self.vacuity_state
+            .checks
+            .borrow_mut()
+            .insert(program_name, check);
+    }
+    fn get_vacuity_check(&self, program_name: &str) -> Option<VacuityCheck> {
+        self.vacuity_state
+            .checks
+            .borrow()
+            .get(program_name)
+            .cloned()
+    }
+}
@@ -22,4 +22,5 @@
 
 pub mod encoder;
 mod utils;
+mod verification_report;
 pub mod verifier;
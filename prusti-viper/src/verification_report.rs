@@ -0,0 +1,180 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Collects the verification outcome of each item of a crate for the report
+//! enabled by the `REPORT_FORMAT` flag and the summary of `cargo-prusti`.
+
+use crate::encoder::Encoder;
+use prusti_common::report::verification::{
+    CacheUsage, ErrorReport, ItemReport, ItemStatus, SourceSpan, VerificationReport,
+};
+use prusti_interface::{data::ProcedureDefId, environment::Environment, PrustiError};
+use prusti_rustc_interface::errors::MultiSpan;
+use prusti_server::VerificationStats;
+use rustc_hash::FxHashMap;
+use std::time::Duration;
+
+#[derive(Default)]
+struct ItemOutcome {
+    programs: usize,
+    failed_programs: usize,
+    cache_hits: usize,
+    partial_cache_hits: usize,
+    time: Duration,
+    backends: Vec<String>,
+    errors: Vec<ErrorReport>,
+}
+
+pub(crate) struct ReportBuilder<'v, 'tcx> {
+    env: &'v Environment<'tcx>,
+    procedures: Vec<ProcedureDefId>,
+    /// The procedure verified by each Viper program, by program name.
+    program_procedures: FxHashMap<String, ProcedureDefId>,
+    outcomes: FxHashMap<ProcedureDefId, ItemOutcome>,
+}
+
+impl<'v, 'tcx> ReportBuilder<'v, 'tcx> {
+    pub fn new(env: &'v Environment<'tcx>, procedures: &[ProcedureDefId]) -> Self {
+        // The programs of the legacy encoding are named after the unique name
+        // of their procedure, the ones of the core proof after its absolute
+        // name.
+        let mut program_procedures = FxHashMap::default();
+        for &proc_def_id in procedures {
+            program_procedures.insert(env.name.get_unique_item_name(proc_def_id), proc_def_id);
+            program_procedures.insert(env.name.get_absolute_item_name(proc_def_id), proc_def_id);
+        }
+        ReportBuilder {
+            env,
+            procedures: procedures.to_vec(),
+            program_procedures,
+            outcomes: FxHashMap::default(),
+        }
+    }
+
+    /// Records the verification of a Viper program with the given backend, or
+    /// with the configuration of the portfolio that produced its result.
+    /// Programs that do not verify a procedure, e.g. the ones checking types,
+    /// are ignored.
+    pub fn record_program(
+        &mut self,
+        program_name: &str,
+        backend: &str,
+        stats: &VerificationStats,
+        success: bool,
+    ) {
+        if let Some(outcome) = self.program_outcome(program_name) {
+            outcome.programs += 1;
+            outcome.failed_programs += usize::from(!success);
+            match stats.cache_usage {
+                CacheUsage::Hit => outcome.cache_hits += 1,
+                CacheUsage::PartialHit => outcome.partial_cache_hits += 1,
+                CacheUsage::Miss => {}
+            }
+            outcome.time += stats.duration;
+            let backend = stats.configuration.as_deref().unwrap_or(backend);
+            if !outcome.backends.iter().any(|used| used == backend) {
+                outcome.backends.push(backend.to_string());
+            }
+        }
+    }
+
+    /// Records an error reported when verifying a Viper program.
+    pub fn record_error(&mut self, program_name: &str, kind: String, error: &PrustiError) {
+        let span = source_span(self.env, error.span());
+        if let Some(outcome) = self.program_outcome(program_name) {
+            outcome.errors.push(ErrorReport {
+                kind,
+                message: error.message().to_string(),
+                span,
+            });
+        }
+    }
+
+    fn program_outcome(&mut self, program_name: &str) -> Option<&mut ItemOutcome> {
+        let proc_def_id = self.program_procedures.get(program_name)?;
+        Some(self.outcomes.entry(*proc_def_id).or_default())
+    }
+
+    pub fn finish(mut self, encoder: &Encoder<'v, 'tcx>) -> VerificationReport {
+        let items = self
+            .procedures
+            .iter()
+            .map(|&proc_def_id| {
+                let mut outcome = self.outcomes.remove(&proc_def_id).unwrap_or_default();
+                let encoding_errors = encoder.get_procedure_encoding_errors(proc_def_id);
+                let status = if encoder.is_trusted_procedure(proc_def_id) {
+                    ItemStatus::Trusted
+                } else if outcome.failed_programs > 0 || !outcome.errors.is_empty() {
+                    ItemStatus::Failed
+                } else if !encoding_errors.is_empty() {
+                    if encoding_errors.iter().all(|error| error.is_unsupported()) {
+                        ItemStatus::Unsupported
+                    } else {
+                        ItemStatus::Failed
+                    }
+                } else if outcome.programs > 0 {
+                    ItemStatus::Verified
+                } else {
+                    ItemStatus::Skipped
+                };
+                for encoding_error in encoding_errors {
+                    let kind = encoding_error.kind_name().to_string();
+                    let error: PrustiError = encoding_error.into();
+                    outcome.errors.push(ErrorReport {
+                        kind,
+                        message: error.message().to_string(),
+                        span: source_span(self.env, error.span()),
+                    });
+                }
+                let cache = if outcome.programs == 0 {
+                    None
+                } else if outcome.cache_hits == outcome.programs {
+                    Some(CacheUsage::Hit)
+                } else if outcome.cache_hits + outcome.partial_cache_hits > 0 {
+                    Some(CacheUsage::PartialHit)
+                } else {
+                    Some(CacheUsage::Miss)
+                };
+                let def_span = self.env.query.get_def_span(proc_def_id);
+                ItemReport {
+                    name: self.env.name.get_unique_item_name(proc_def_id),
+                    def_path: self.env.name.get_item_def_path(proc_def_id),
+                    span: source_span(self.env, &MultiSpan::from(def_span)),
+                    status,
+                    errors: outcome.errors,
+                    time_ms: outcome.time.as_millis() as u64,
+                    cache,
+                    backends: outcome.backends,
+                }
+            })
+            .collect();
+        VerificationReport {
+            crate_name: self.env.name.local_crate_name(),
+            items,
+        }
+    }
+}
+
+pub(crate) fn source_span(env: &Environment, span: &MultiSpan) -> Option<SourceSpan> {
+    let primary_span = span.primary_span()?;
+    if primary_span.is_dummy() {
+        return None;
+    }
+    let lines_info = env
+        .query
+        .codemap()
+        .span_to_lines(primary_span.source_callsite())
+        .ok()?;
+    let first_line = lines_info.lines.first()?;
+    let last_line = lines_info.lines.last()?;
+    Some(SourceSpan {
+        file: lines_info.file.name.prefer_local().to_string(),
+        line: first_line.line_index + 1,
+        column: first_line.start_col.0 + 1,
+        end_line: last_line.line_index + 1,
+        end_column: last_line.end_col.0 + 1,
+    })
+}
@@ -10,18 +10,29 @@ use prusti_common::{
 };
 use vir_crate::common::check_mode::CheckMode;
 use crate::encoder::Encoder;
+use crate::encoder::errors::ErrorCtxt;
 use crate::encoder::counterexamples::counterexample_translation;
+use crate::encoder::counterexamples::counterexample::Counterexample;
 use crate::encoder::counterexamples::counterexample_translation_refactored;
+use crate::encoder::vacuity::{VacuityCheck, VacuityProbeKind, VacuityStateInterface};
 use prusti_interface::data::VerificationResult;
 use prusti_interface::data::VerificationTask;
 use prusti_interface::environment::Environment;
 use prusti_interface::PrustiError;
-use viper::{self, PersistentCache, Viper};
+use viper::{self, Viper};
 use prusti_interface::specs::typed;
 use ::log::{info, debug, error};
-use prusti_server::{VerificationRequest, PrustiClient, process_verification_request, spawn_server_thread, ViperBackendConfig};
-use prusti_rustc_interface::span::DUMMY_SP;
+use prusti_server::{
+    VerificationRequest, PrustiClient, process_verification_request, spawn_server_thread,
+    load_persistent_cache,
+    StreamedVerificationResult, VerificationStats, ViperBackendConfig,
+};
+use crate::verification_report::{source_span, ReportBuilder};
+use prusti_common::report::verification::ReportFormat;
+use prusti_rustc_interface::{errors::MultiSpan, span::DUMMY_SP};
 use prusti_server::tokio::runtime::Builder;
+use std::path::Path;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 /// A verifier is an object for verifying a single crate, potentially
 /// many times.
@@ -74,6 +85,14 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         }
         self.encoder.process_encoding_queue();
 
+        // The triggers are only known once the quantifiers are encoded, so
+        // they follow the type-checked specifications.
+        if config::print_typeckd_specs() {
+            for quantifier_triggers in self.encoder.get_quantifier_triggers() {
+                println!("{}", quantifier_triggers);
+            }
+        }
+
         let encoding_errors_count = self.encoder.count_encoding_errors();
 
         let polymorphic_programs = self.encoder.get_viper_programs();
@@ -92,56 +111,197 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         programs.extend(self.encoder.get_core_proof_programs());
 
         stopwatch.start_next("verifying Viper program");
-        let verification_results = verify_programs(self.env, programs);
-        stopwatch.finish();
-
-        // Group verification results
-        let mut verification_errors : Vec<_> = vec![];
-        let mut consistency_errors : Vec<_> = vec![];
-        let mut java_exceptions : Vec<_> = vec![];
-        for (method_name, result) in verification_results.into_iter() {
-            match result {
-                viper::VerificationResult::Success => {}
-                viper::VerificationResult::ConsistencyErrors(errors) => {
-                    for error in errors.into_iter() {
-                        consistency_errors.push((method_name.clone(), error));
-                    }
-                }
-                viper::VerificationResult::Failure(errors) => {
-                    for error in errors.into_iter() {
-                        verification_errors.push((method_name.clone(), error));
-                    }
+        let mut result = VerificationResult::Success;
+        let report_format = config::report_format().unwrap_or_else(|error| {
+            PrustiError::configuration(
+                format!("invalid value of the `REPORT_FORMAT` flag: {error}"),
+                DUMMY_SP.into(),
+            ).emit(&self.env.diagnostic);
+            result = VerificationResult::Failure;
+            None
+        });
+        let summary_dir = config::summary_dir();
+        let mut report = if report_format.is_some() || summary_dir.is_some() {
+            Some(ReportBuilder::new(self.env, &task.procedures))
+        } else {
+            None
+        };
+        // Whether each program verified; only the vacuity checks of the
+        // programs that verified are reported.
+        let mut program_successes = FxHashMap::default();
+        // The vacuity checks whose results arrived before the result of the
+        // program they check.
+        let mut pending_vacuity_checks = vec![];
+        verify_programs(self.env, programs, |method_name, backend, program_result, stats| {
+            if let Some(precondition_span) = self.encoder.get_precondition_lint_span(&method_name) {
+                // The program verifies only if the precondition is unsatisfiable.
+                if matches!(program_result, viper::VerificationResult::Success) {
+                    PrustiError::warning(
+                        "the precondition of this function is unsatisfiable, so the function \
+                        is verified vacuously and cannot be called",
+                        precondition_span,
+                    ).emit(&self.env.diagnostic);
                 }
-                viper::VerificationResult::JavaException(exception) => {
-                    java_exceptions.push((method_name, exception));
+                return;
+            }
+            if let Some(vacuity_check) = self.encoder.get_vacuity_check(&method_name) {
+                match program_successes.get(&vacuity_check.verified_program) {
+                    Some(true) => self.report_vacuity_check(vacuity_check, program_result),
+                    Some(false) => {}
+                    None => pending_vacuity_checks.push((vacuity_check, program_result)),
                 }
+                return;
+            }
+            let success = matches!(program_result, viper::VerificationResult::Success);
+            program_successes.insert(method_name.clone(), success);
+            if let Some(report) = &mut report {
+                report.record_program(&method_name, &backend, &stats, success);
+            }
+            if !self.report_program_result(&method_name, program_result, report.as_mut()) {
+                result = VerificationResult::Failure;
+            }
+        });
+        for (vacuity_check, program_result) in pending_vacuity_checks {
+            if program_successes.get(&vacuity_check.verified_program) == Some(&true) {
+                self.report_vacuity_check(vacuity_check, program_result);
             }
         }
+        stopwatch.finish();
 
-        // Convert verification results to Prusti errors
-        let error_manager = self.encoder.error_manager();
-        let mut result = VerificationResult::Success;
-
-        for (method, error) in consistency_errors.into_iter() {
-            PrustiError::internal(
-                format!("consistency error in {}: {}", method, error), DUMMY_SP.into()
-            ).emit(&self.env.diagnostic);
+        if encoding_errors_count != 0 {
             result = VerificationResult::Failure;
         }
 
-        for (method, exception) in java_exceptions.into_iter() {
-            error!("Java exception: {}", exception.get_stack_trace());
-            PrustiError::internal(
-                format!("in {}: {}", method, exception), DUMMY_SP.into()
-            ).emit(&self.env.diagnostic);
-            result = VerificationResult::Failure;
+        if let Some(report) = report {
+            let report = report.finish(&self.encoder);
+            let file_stem = self.env.name.local_crate_filename();
+            // The summary printed by cargo-prusti is built from JSON reports.
+            let outputs = report_format
+                .map(|report_format| (config::report_dir(), report_format))
+                .into_iter()
+                .chain(summary_dir.map(|summary_dir| (summary_dir, ReportFormat::Json)));
+            for (report_dir, report_format) in outputs {
+                if let Err(error) = report.write(&report_dir, &file_stem, report_format) {
+                    error!("Failed to write the verification report to {:?}: {}", report_dir, error);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Warns about the probes of a vacuity check that are unreachable, i.e.
+    /// whose `assert false` did not fail. An unreachable end of the procedure
+    /// is reported at the unreachable assumptions that explain it, if any.
+    fn report_vacuity_check(&self, vacuity_check: VacuityCheck, result: viper::VerificationResult) {
+        let failed_positions: FxHashSet<u64> = match result {
+            viper::VerificationResult::Success => FxHashSet::default(),
+            viper::VerificationResult::Failure(errors) => errors
+                .iter()
+                .filter_map(|error| error.offending_pos_id.as_ref())
+                .filter_map(|pos_id| pos_id.parse().ok())
+                .collect(),
+            // The check is inconclusive.
+            _ => return,
+        };
+        let unreachable_probes: Vec<_> = vacuity_check
+            .probes
+            .into_iter()
+            .filter(|(_, position)| !failed_positions.contains(&position.id()))
+            .map(|(probe, _)| probe)
+            .collect();
+        let unreachable_assumption_spans: Vec<_> = unreachable_probes
+            .iter()
+            .filter(|probe| probe.kind == VacuityProbeKind::Assumption)
+            .flat_map(|probe| probe.span.primary_spans().to_vec())
+            .collect();
+        for probe in unreachable_probes {
+            let warning = if probe.kind == VacuityProbeKind::End
+                && !unreachable_assumption_spans.is_empty()
+            {
+                PrustiError::warning(
+                    "the end of the function is unreachable because of this, \
+                    so its postcondition is verified vacuously",
+                    MultiSpan::from_spans(unreachable_assumption_spans.clone()),
+                ).add_note("the function is defined here", probe.span.primary_span())
+            } else {
+                PrustiError::warning(probe.message, probe.span)
+            };
+            warning.emit(&self.env.diagnostic);
         }
+    }
 
-        // Report verification errors
+    /// Emits the errors of a verified program as diagnostics, and records them
+    /// in the verification report, if any.
+    /// Returns whether the program verified successfully.
+    fn report_program_result(
+        &self,
+        method_name: &str,
+        result: viper::VerificationResult,
+        mut report: Option<&mut ReportBuilder>,
+    ) -> bool {
+        let mut emit = |kind: &str, prusti_error: PrustiError| {
+            if let Some(report) = report.as_mut() {
+                report.record_error(method_name, kind.to_string(), &prusti_error);
+            }
+            prusti_error.emit(&self.env.diagnostic);
+        };
+        let verification_errors = match result {
+            viper::VerificationResult::Success => return true,
+            viper::VerificationResult::ConsistencyErrors(errors) => {
+                for error in errors.into_iter() {
+                    emit("ConsistencyError", PrustiError::internal(
+                        format!("consistency error in {}: {}", method_name, error), DUMMY_SP.into()
+                    ));
+                }
+                return false;
+            }
+            viper::VerificationResult::JavaException(exception) => {
+                error!("Java exception: {}", exception.get_stack_trace());
+                emit("JavaException", PrustiError::internal(
+                    format!("in {}: {}", method_name, exception), DUMMY_SP.into()
+                ));
+                return false;
+            }
+            viper::VerificationResult::Cancelled => {
+                emit("Cancelled", PrustiError::verification(
+                    format!("the verification request of {} was cancelled before it finished", method_name),
+                    DUMMY_SP.into(),
+                ));
+                return false;
+            }
+            viper::VerificationResult::TimedOut => {
+                emit("TimedOut", PrustiError::verification(
+                    format!("the verification of {} did not finish within the server request timeout", method_name),
+                    DUMMY_SP.into(),
+                ).add_note(
+                    "increase the timeout with the `SERVER_REQUEST_TIMEOUT` configuration flag",
+                    None,
+                ));
+                return false;
+            }
+            viper::VerificationResult::BackendTimedOut => {
+                emit("BackendTimedOut", PrustiError::verification(
+                    format!("the verification of {} did not finish within the timeout of the backend", method_name),
+                    DUMMY_SP.into(),
+                ).add_note(
+                    "increase the timeout with the `CARBON_TIMEOUT` configuration flag",
+                    None,
+                ));
+                return false;
+            }
+            viper::VerificationResult::Failure(errors) => errors,
+        };
+
+        // Convert verification errors to Prusti errors
+        let error_manager = self.encoder.error_manager();
         let mut prusti_errors: Vec<_> = vec![];
-        for (method, verification_error) in verification_errors.into_iter() {
-            debug!("Verification error in {}: {:?}", method, verification_error);
+        for (error_index, verification_error) in verification_errors.into_iter().enumerate() {
+            debug!("Verification error in {}: {:?}", method_name, verification_error);
             let mut prusti_error = error_manager.translate_verification_error(&verification_error);
+            let error_kind = error_manager
+                .get_error_ctxt(&verification_error)
+                .map_or("Unregistered", ErrorCtxt::kind);
 
             // annotate with counterexample, if requested
             if config::counterexample() {
@@ -154,12 +314,18 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                                 def_id,
                                 silicon_counterexample,
                             );
+                            prusti_error = self.write_counterexample_json(
+                                |span_to_json| counterexample.to_json(span_to_json),
+                                method_name,
+                                error_index,
+                                prusti_error,
+                            );
                             prusti_error = counterexample.annotate_error(prusti_error);
                         } else {
                             prusti_error = prusti_error.add_note(
                                 format!(
                                     "the verifier produced a counterexample for {}, but it could not be mapped to source code",
-                                    method
+                                    method_name
                                 ),
                                 None,
                             );
@@ -172,12 +338,21 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                             def_id,
                             silicon_counterexample,
                         );
+                        prusti_error = self.write_counterexample_json(
+                            |span_to_json| counterexample.to_json(span_to_json),
+                            method_name,
+                            error_index,
+                            prusti_error,
+                        );
                         prusti_error = counterexample.annotate_error(prusti_error);
+                        if let Some(tests_dir) = config::counterexample_tests_dir() {
+                            prusti_error = self.write_counterexample_test(&counterexample, &tests_dir, prusti_error);
+                        }
                     } else {
                         prusti_error = prusti_error.add_note(
                             format!(
                                 "the verifier produced a counterexample for {}, but it could not be mapped to source code",
-                                method
+                                method_name
                             ),
                             None,
                         );
@@ -185,33 +360,100 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                 }
             }
 
-            prusti_errors.push(prusti_error);
+            prusti_errors.push((prusti_error, error_kind));
         }
         prusti_errors.sort();
 
-        for prusti_error in prusti_errors {
+        for (prusti_error, error_kind) in prusti_errors {
             debug!("Prusti error: {:?}", prusti_error);
             if prusti_error.is_disabled() {
                 prusti_error.cancel();
             } else {
-                prusti_error.emit(&self.env.diagnostic);
+                emit(error_kind, prusti_error);
             }
-            result = VerificationResult::Failure;
         }
 
-        if encoding_errors_count != 0 {
-            result = VerificationResult::Failure;
+        false
+    }
+
+    /// If `COUNTEREXAMPLE_JSON_DIR` is set, writes the counterexample of an
+    /// error, serialized by the given function, together with the message and
+    /// span of the error, as JSON to that directory, and annotates the error
+    /// with the path of the file.
+    fn write_counterexample_json(
+        &self,
+        counterexample_to_json: impl FnOnce(&dyn Fn(&MultiSpan) -> serde_json::Value) -> serde_json::Value,
+        method_name: &str,
+        error_index: usize,
+        prusti_error: PrustiError,
+    ) -> PrustiError {
+        let Some(json_dir) = config::counterexample_json_dir() else {
+            return prusti_error;
+        };
+        let mut counterexample =
+            counterexample_to_json(&|span| serde_json::json!(source_span(self.env, span)));
+        let file_name = format!("{}-{}", method_name, error_index);
+        counterexample["message"] = serde_json::json!(prusti_error.message());
+        counterexample["span"] = serde_json::json!(source_span(self.env, prusti_error.span()));
+        let path = json_dir.join(format!("{}.json", log::to_legal_file_name(&file_name)));
+        let result = std::fs::create_dir_all(&json_dir).and_then(|()| {
+            let contents = serde_json::to_string_pretty(&counterexample)?;
+            std::fs::write(&path, contents)
+        });
+        match result {
+            Ok(()) => prusti_error.add_note(
+                format!("the counterexample was written to {}", path.display()),
+                None,
+            ),
+            Err(e) => {
+                error!("Failed to write the counterexample to {}: {}", path.display(), e);
+                prusti_error
+            }
         }
+    }
 
-        result
+    /// Writes the unit test replaying the counterexample of an error to the
+    /// given directory, and annotates the error with the path of the test.
+    fn write_counterexample_test(
+        &self,
+        counterexample: &Counterexample,
+        tests_dir: &Path,
+        prusti_error: PrustiError,
+    ) -> PrustiError {
+        let test = match counterexample.test() {
+            Some(test) => test,
+            None => return prusti_error,
+        };
+        // The tests of the errors of a function are told apart by their line.
+        let suffix = match prusti_error.span().primary_span() {
+            Some(span) => {
+                let position = self.env.query.codemap().lookup_char_pos(span.source_callsite().lo());
+                format!("line_{}", position.line)
+            }
+            None => "unknown_line".to_string(),
+        };
+        match test.write(tests_dir, &suffix) {
+            Ok(path) => prusti_error.add_note(
+                format!("a test replaying the counterexample was written to {}", path.display()),
+                None,
+            ),
+            Err(e) => {
+                error!("Failed to write the counterexample test: {}", e);
+                prusti_error
+            }
+        }
     }
 }
 
 /// Verify a list of programs.
-/// Returns a list of (program_name, verification_result) tuples.
-fn verify_programs(env: &Environment, programs: Vec<Program>)
-    -> Vec<(String, viper::VerificationResult)>
-{
+/// Reports the name, backend, verification result and statistics of each
+/// program as soon as its result is available. With the server, the results
+/// are thus not necessarily reported in the order of the programs.
+fn verify_programs(
+    env: &Environment,
+    programs: Vec<Program>,
+    mut report_result: impl FnMut(String, String, viper::VerificationResult, VerificationStats),
+) {
     let source_path = env.name.source_path();
     let rust_program_name = source_path
         .file_name()
@@ -229,11 +471,8 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
         } else {
             config::viper_backend()
         }.parse().unwrap();
-        let request = VerificationRequest {
-            program,
-            backend_config: ViperBackendConfig::new(backend),
-        };
-        (program_name, request)
+        let request = VerificationRequest::new(program, ViperBackendConfig::new(backend));
+        ((program_name, backend.to_string()), request)
     });
     if let Some(server_address) = config::server_address() {
         let server_address = if server_address == "MOCK" {
@@ -248,35 +487,54 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
                 server_address, error
             )
         });
-        // Here we construct a Tokio runtime to block until completion of the futures returned by
-        // `client.verify`. However, to report verification errors as early as possible,
-        // `verify_programs` should return an asynchronous stream of verification results.
+        // Here we construct a Tokio runtime to block until all the results
+        // streamed back by `client.verify_stream` have been received.
         let runtime = Builder::new_current_thread()
             .thread_name("prusti-viper")
             .enable_all()
             .build()
             .expect("failed to construct Tokio runtime");
-        verification_requests.map(|(program_name, request)| {
-            let remote_result = runtime.block_on(client.verify(request));
-            let result = remote_result.unwrap_or_else(|error| {
-                panic!(
-                    "Verification request of program {} failed: {:?}",
-                    program_name,
-                    error
-                )
+        let (program_names, requests): (Vec<_>, Vec<_>) = verification_requests.unzip();
+        runtime.block_on(async {
+            let mut results = client.verify_stream(requests).await.unwrap_or_else(|error| {
+                panic!("Verification requests failed: {:?}", error)
             });
-            (program_name, result)
-        }).collect()
+            let mut reported_results = 0;
+            while let Some(streamed_result) = results.next().await.unwrap_or_else(|error| {
+                panic!("Receiving verification results failed: {:?}", error)
+            }) {
+                let StreamedVerificationResult {
+                    request_index,
+                    program_name,
+                    result,
+                    stats,
+                } = streamed_result;
+                let (result, stats) = result.zip(stats).unwrap_or_else(|| {
+                    panic!(
+                        "Verification request of program {} was aborted by the server",
+                        program_name
+                    )
+                });
+                let (method_name, backend) = program_names[request_index].clone();
+                report_result(method_name, backend, result, stats);
+                reported_results += 1;
+            }
+            assert_eq!(
+                reported_results,
+                program_names.len(),
+                "the server did not send the results of all verification requests"
+            );
+        });
     } else {
         let mut stopwatch = Stopwatch::start("prusti-viper", "JVM startup");
         let viper = Viper::new_with_args(&config::viper_home(), config::extra_jvm_args());
         stopwatch.start_next("attach current thread to the JVM");
         let viper_thread = viper.attach_current_thread();
         stopwatch.finish();
-        let mut cache = PersistentCache::load_cache(config::cache_path());
-        verification_requests.map(|(program_name, request)| {
-            let result = process_verification_request(&viper_thread, request, &mut cache);
-            (program_name, result)
-        }).collect()
+        let mut cache = load_persistent_cache();
+        for ((program_name, backend), request) in verification_requests {
+            let (result, stats) = process_verification_request(&viper_thread, request, &mut cache);
+            report_result(program_name, backend, result, stats);
+        }
     }
 }
@@ -109,6 +109,21 @@ impl prusti_rustc_interface::driver::Callbacks for PrustiCompilerCalls {
                     println!("{}", value);
                 }
             }
+            if config::spec_coverage() || config::spec_coverage_json().is_some() {
+                let coverage = specs::coverage::SpecCoverage::collect(&env, &def_spec);
+                if config::spec_coverage() {
+                    print!("{}", coverage);
+                }
+                if let Some(path) = config::spec_coverage_json() {
+                    if let Err(e) = coverage.write_json(&path) {
+                        compiler.session().err(format!(
+                            "failed to write the specification coverage to {}: {}",
+                            path.display(),
+                            e
+                        ));
+                    }
+                }
+            }
             CrossCrateSpecs::import_export_cross_crate(&mut env, &mut def_spec);
             if !config::no_verify() {
                 verify(env, def_spec);
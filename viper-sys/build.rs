@@ -731,6 +731,20 @@ fn main() {
             java_class!("viper.silicon.interfaces.SiliconMappedCounterexample", vec![
                 method!("converter")
             ]),
+            java_class!("viper.silver.verifier.Model", vec![
+                method!("entries"),
+            ]),
+            java_class!("viper.silver.verifier.ConstantEntry", vec![
+                method!("value"),
+            ]),
+            java_class!("viper.silver.verifier.ApplicationEntry", vec![
+                method!("name"),
+                method!("arguments"),
+            ]),
+            java_class!("viper.silver.verifier.MapEntry", vec![
+                method!("options"),
+                method!("default"),
+            ]),
             java_class!("viper.silver.verifier.ErrorReason", vec![
                 method!("id"),
                 method!("pos"),
@@ -1305,6 +1305,14 @@ impl<'a> AstFactory<'a> {
         build_ast_node!(self, Expr, ast::MapCardinality, map.to_jobject())
     }
 
+    pub fn map_domain(&self, map: Expr) -> Expr<'a> {
+        build_ast_node!(self, Expr, ast::MapDomain, map.to_jobject())
+    }
+
+    pub fn map_range(&self, map: Expr) -> Expr<'a> {
+        build_ast_node!(self, Expr, ast::MapRange, map.to_jobject())
+    }
+
     pub fn range_seq(&self, low: Expr, high: Expr) -> Expr<'a> {
         build_ast_node!(
             self,
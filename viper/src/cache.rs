@@ -4,37 +4,182 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use log::{error, info, warn};
+use filetime::FileTime;
+use log::{debug, error, info, warn};
 
 use crate::verification_result::VerificationResult;
 use std::{
     collections::HashMap,
     fs, io,
-    ops::DerefMut,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 pub trait Cache {
-    fn get(&self, request: u64) -> Option<VerificationResult>;
-    fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult>;
+    fn get(&mut self, request: u64) -> Option<VerificationResult>;
+    /// Like `get`, but also returns the configuration of the verifier that
+    /// produced the result, if it was recorded.
+    fn get_with_configuration(
+        &mut self,
+        request: u64,
+    ) -> Option<(VerificationResult, Option<String>)> {
+        self.get(request).map(|result| (result, None))
+    }
+    fn insert(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+    ) -> Option<VerificationResult>;
+    /// Like `insert`, but also records the configuration of the verifier that
+    /// produced the result, e.g. the winner of a portfolio.
+    fn insert_with_configuration(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+        _configuration: &str,
+    ) -> Option<VerificationResult> {
+        self.insert(request, program_name, result)
+    }
 }
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CacheEntry {
+    pub result: VerificationResult,
+    /// The name of the verified program, or an empty string if unknown.
+    pub program_name: String,
+    /// When the entry was created, in seconds since the Unix epoch.
+    pub created: u64,
+    /// When the entry was last used, in seconds since the Unix epoch.
+    pub last_used: u64,
+    /// The configuration of the verifier that produced the result, if it was
+    /// chosen among several ones.
+    pub configuration: Option<String>,
+}
+
 // We can change the representation here (e.g. adding fields):
 #[derive(Debug, Clone)]
 pub struct PersistentCache {
     updated: bool,
     load_loc: PathBuf,
-    data: HashMap<u64, VerificationResult>,
+    data: HashMap<u64, CacheEntry>,
+    /// The least recently used entries are evicted beyond this number.
+    max_entries: Option<usize>,
+    /// When set, entries are stored in this directory as soon as they change,
+    /// instead of in the file at `load_loc` when the cache is saved.
+    directory: Option<CacheDirectory>,
+    /// A read-only directory consulted for the requests missing in the cache.
+    seed_directory: Option<CacheDirectory>,
+}
+
+/// A directory holding one file per cached request, named after the hash of
+/// the request. Files are replaced atomically, so that the directory can be
+/// shared by several processes, and two directories can be merged by copying
+/// the files of one into the other. The last use of an entry is tracked by the
+/// modification time of its file, so that using an entry does not rewrite it.
+#[derive(Debug, Clone)]
+struct CacheDirectory {
+    path: PathBuf,
 }
 
-const RESULT_CACHE_VERSION: u64 = 2;
+/// The content of a file of a cache directory.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct StoredEntry {
+    version: u64,
+    entry: CacheEntry,
+}
 
+/// The version of the format of cache files and cache directory entries.
+pub const RESULT_CACHE_VERSION: u64 = 4;
+
+/// The version comes first, so that it can be checked before the data.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct ResultCache {
+    version: u64,
+    data: HashMap<u64, CacheEntry>,
+}
+
+/// The format of version 2, which did not record any metadata.
+#[derive(Debug, serde::Deserialize)]
+struct ResultCacheV2 {
     data: HashMap<u64, VerificationResult>,
     version: u64,
 }
 
+impl From<ResultCacheV2> for ResultCache {
+    fn from(old_cache: ResultCacheV2) -> Self {
+        let now = now();
+        let data = old_cache
+            .data
+            .into_iter()
+            .map(|(request, result)| {
+                let entry = CacheEntry {
+                    result,
+                    program_name: String::new(),
+                    created: now,
+                    last_used: now,
+                    configuration: None,
+                };
+                (request, entry)
+            })
+            .collect();
+        ResultCache {
+            version: RESULT_CACHE_VERSION,
+            data,
+        }
+    }
+}
+
+/// The format of the entries of version 3, which did not record the
+/// configuration of the verifier.
+#[derive(Debug, serde::Deserialize)]
+struct CacheEntryV3 {
+    result: VerificationResult,
+    program_name: String,
+    created: u64,
+    last_used: u64,
+}
+
+impl From<CacheEntryV3> for CacheEntry {
+    fn from(old_entry: CacheEntryV3) -> Self {
+        CacheEntry {
+            result: old_entry.result,
+            program_name: old_entry.program_name,
+            created: old_entry.created,
+            last_used: old_entry.last_used,
+            configuration: None,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ResultCacheV3 {
+    version: u64,
+    data: HashMap<u64, CacheEntryV3>,
+}
+
+impl From<ResultCacheV3> for ResultCache {
+    fn from(old_cache: ResultCacheV3) -> Self {
+        let data = old_cache
+            .data
+            .into_iter()
+            .map(|(request, entry)| (request, entry.into()))
+            .collect();
+        ResultCache {
+            version: RESULT_CACHE_VERSION,
+            data,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct StoredEntryV3 {
+    version: u64,
+    entry: CacheEntryV3,
+}
+
 impl From<(PathBuf, ResultCache)> for PersistentCache {
     /// Used when loading cache from disk
     fn from((load_loc, rc): (PathBuf, ResultCache)) -> Self {
@@ -44,6 +189,9 @@ impl From<(PathBuf, ResultCache)> for PersistentCache {
             updated: false,
             load_loc,
             data,
+            max_entries: None,
+            directory: None,
+            seed_directory: None,
         }
     }
 }
@@ -51,34 +199,212 @@ impl From<&PersistentCache> for ResultCache {
     /// Used when saving cache to disk
     fn from(cache: &PersistentCache) -> Self {
         ResultCache {
+            version: RESULT_CACHE_VERSION,
             data: cache.data.clone(),
+        }
+    }
+}
+
+/// The current time, in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Reads a cache file of the current or of an older version, migrating the
+/// entries of the latter. Also returns the version of the file.
+fn read_cache_file(cache_loc: &Path) -> Result<(ResultCache, u64), String> {
+    let bytes = fs::read(cache_loc).map_err(|e| e.to_string())?;
+    if let Ok(data) = bincode::deserialize::<ResultCache>(&bytes) {
+        if data.version == RESULT_CACHE_VERSION {
+            return Ok((data, RESULT_CACHE_VERSION));
+        }
+    }
+    if let Ok(data) = bincode::deserialize::<ResultCacheV3>(&bytes) {
+        if data.version == 3 {
+            info!(
+                "Migrating cache \"{}\" from version 3 to version {}",
+                cache_loc.display(),
+                RESULT_CACHE_VERSION
+            );
+            return Ok((data.into(), 3));
+        }
+    }
+    match bincode::deserialize::<ResultCacheV2>(&bytes) {
+        Ok(data) if data.version == 2 => {
+            info!(
+                "Migrating cache \"{}\" from version 2 to version {}",
+                cache_loc.display(),
+                RESULT_CACHE_VERSION
+            );
+            Ok((data.into(), 2))
+        }
+        _ => Err(format!(
+            "unknown cache version, expected version {}, 3 or 2",
+            RESULT_CACHE_VERSION
+        )),
+    }
+}
+
+impl CacheDirectory {
+    const EXTENSION: &'static str = "bin";
+
+    fn entry_path(&self, request: u64) -> PathBuf {
+        self.path
+            .join(format!("{:016x}.{}", request, Self::EXTENSION))
+    }
+
+    fn read(&self, request: u64) -> Option<CacheEntry> {
+        let path = self.entry_path(request);
+        let bytes = fs::read(&path).ok()?;
+        let mut entry = match bincode::deserialize::<StoredEntry>(&bytes) {
+            Ok(stored) if stored.version == RESULT_CACHE_VERSION => stored.entry,
+            _ => match bincode::deserialize::<StoredEntryV3>(&bytes) {
+                Ok(stored) if stored.version == 3 => stored.entry.into(),
+                _ => {
+                    debug!("Ignoring invalid cache entry \"{}\"", path.display());
+                    return None;
+                }
+            },
+        };
+        let modified = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+        if let Some(modified) = modified {
+            entry.last_used = entry.last_used.max(modified.as_secs());
+        }
+        Some(entry)
+    }
+
+    fn read_all(&self) -> HashMap<u64, CacheEntry> {
+        let dir_entries = match fs::read_dir(&self.path) {
+            Ok(dir_entries) => dir_entries,
+            Err(e) => {
+                error!(
+                    "Failed to read cache directory \"{}\": {e}",
+                    self.path.display()
+                );
+                return HashMap::new();
+            }
+        };
+        dir_entries
+            .filter_map(|dir_entry| {
+                let path = dir_entry.ok()?.path();
+                // Skips temporary files, among others.
+                if path.extension()? != Self::EXTENSION {
+                    return None;
+                }
+                let request = u64::from_str_radix(path.file_stem()?.to_str()?, 16).ok()?;
+                Some((request, self.read(request)?))
+            })
+            .collect()
+    }
+
+    /// Writes the entry to a temporary file first, so that other processes
+    /// never observe a partially written entry.
+    fn write(&self, request: u64, entry: &CacheEntry) {
+        let stored = StoredEntry {
             version: RESULT_CACHE_VERSION,
+            entry: entry.clone(),
+        };
+        let temp_path = self
+            .path
+            .join(format!(".{:016x}.{}.tmp", request, uuid::Uuid::new_v4()));
+        let written = bincode::serialize(&stored)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| fs::write(&temp_path, bytes).map_err(|e| e.to_string()))
+            .and_then(|()| {
+                fs::rename(&temp_path, self.entry_path(request)).map_err(|e| e.to_string())
+            });
+        if let Err(e) = written {
+            error!("Failed to write cache entry: {e}");
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    /// Records that the entry was used now, by updating the modification time
+    /// of its file.
+    fn touch(&self, request: u64) {
+        match filetime::set_file_mtime(self.entry_path(request), FileTime::now()) {
+            Ok(()) => {}
+            // Another process might have removed it in the meantime.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => error!("Failed to update the last use of cache entry: {e}"),
+        }
+    }
+
+    fn remove(&self, request: u64) {
+        match fs::remove_file(self.entry_path(request)) {
+            Ok(()) => {}
+            // Another process might have removed it already.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => error!("Failed to remove cache entry: {e}"),
         }
     }
 }
 
 impl PersistentCache {
+    /// Loads a cache stored in a directory, with one file per cached request.
+    /// The directory is created if it does not exist yet. Changes are written
+    /// to the directory immediately, so that processes sharing it see each
+    /// other's results.
+    pub fn load_directory_cache(cache_dir: PathBuf) -> Self {
+        if let Err(e) = fs::create_dir_all(&cache_dir) {
+            error!(
+                "Failed to create cache directory \"{}\": {e}",
+                cache_dir.display()
+            );
+        }
+        let directory = CacheDirectory { path: cache_dir };
+        let data = directory.read_all();
+        info!(
+            "Loaded {} cached results from \"{}\"",
+            data.len(),
+            directory.path.display()
+        );
+        PersistentCache {
+            updated: false,
+            load_loc: directory.path.clone(),
+            data,
+            max_entries: None,
+            directory: Some(directory),
+            seed_directory: None,
+        }
+    }
+
+    /// Loads a directory cache if the path is a directory, and a cache file
+    /// otherwise.
+    pub fn load(cache_loc: PathBuf) -> Self {
+        if cache_loc.is_dir() {
+            Self::load_directory_cache(cache_loc)
+        } else {
+            Self::load_cache(cache_loc)
+        }
+    }
+
+    /// Sets a cache directory from which the results missing in this cache are
+    /// taken. The seed directory is never modified.
+    pub fn set_seed_directory(&mut self, seed_dir: Option<PathBuf>) {
+        self.seed_directory = seed_dir.map(|path| CacheDirectory { path });
+    }
+
+    /// Whether the cache is stored in a directory.
+    pub fn is_directory(&self) -> bool {
+        self.directory.is_some()
+    }
+
     pub fn load_cache(cache_loc: PathBuf) -> Self {
         let mut data_res: Option<ResultCache> = None;
-        if !cache_loc.as_os_str().is_empty() {
-            if let Ok(f) = fs::File::open(&cache_loc) {
-                match bincode::deserialize_from::<_, ResultCache>(&mut io::BufReader::new(f)) {
-                    Ok(data) => {
-                        if data.version == RESULT_CACHE_VERSION {
-                            info!("Loaded cache from \"{}\"", cache_loc.display());
-                            data_res = Some(data);
-                        } else {
-                            error!(
-                                "Cache version mismatch when reading from \"{}\": \
-                                    expected={} actual={}",
-                                cache_loc.display(),
-                                RESULT_CACHE_VERSION,
-                                data.version
-                            )
-                        }
-                    }
-                    Err(e) => error!("Failed to read cache from \"{}\": {e}", cache_loc.display()),
+        if !cache_loc.as_os_str().is_empty() && cache_loc.exists() {
+            match read_cache_file(&cache_loc) {
+                Ok((data, _)) => {
+                    info!("Loaded cache from \"{}\"", cache_loc.display());
+                    data_res = Some(data);
                 }
+                Err(e) => error!("Failed to read cache from \"{}\": {e}", cache_loc.display()),
             }
         }
         PersistentCache::from((
@@ -86,12 +412,22 @@ impl PersistentCache {
             data_res.unwrap_or_else(|| {
                 info!("Cache file doesn't exist or is invalid. Using fresh cache.");
                 ResultCache {
-                    data: HashMap::new(),
                     version: RESULT_CACHE_VERSION,
+                    data: HashMap::new(),
                 }
             }),
         ))
     }
+
+    /// Loads a cache file like `load_cache`, but fails if the file cannot be
+    /// read, e.g. because it does not exist or has an unknown version. Also
+    /// returns the version of the file, which is older than
+    /// `RESULT_CACHE_VERSION` if its entries were migrated.
+    pub fn try_load_cache(cache_loc: PathBuf) -> Result<(Self, u64), String> {
+        let (data, version) = read_cache_file(&cache_loc)?;
+        Ok((PersistentCache::from((cache_loc, data)), version))
+    }
+
     pub fn save_cache(&self, cache_loc: &Path) {
         match fs::File::create(cache_loc) {
             Ok(f) => {
@@ -103,8 +439,9 @@ impl PersistentCache {
         }
     }
     pub fn save(&mut self) {
-        // Save cache to disk, if changed and save path is valid
-        if self.updated {
+        // Save cache to disk, if changed and save path is valid. Directory
+        // caches are always up to date.
+        if self.updated && self.directory.is_none() {
             if self.load_loc.as_os_str().is_empty() {
                 warn!("Cannot save cache, because the cache path is empty.")
             } else {
@@ -117,6 +454,117 @@ impl PersistentCache {
             }
         }
     }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The entries of the cache, by request hash.
+    pub fn entries(&self) -> impl Iterator<Item = (u64, &CacheEntry)> {
+        self.data.iter().map(|(&request, entry)| (request, entry))
+    }
+
+    /// Keeps only the entries for which the predicate holds.
+    pub fn retain(&mut self, mut predicate: impl FnMut(u64, &CacheEntry) -> bool) {
+        let removed: Vec<u64> = self
+            .data
+            .iter()
+            .filter(|(&request, entry)| !predicate(request, entry))
+            .map(|(&request, _)| request)
+            .collect();
+        for request in removed {
+            self.remove_entry(request);
+        }
+    }
+
+    /// Adds the entries of another cache. Of two entries for the same request,
+    /// the most recently used one is kept.
+    pub fn merge(&mut self, other: &PersistentCache) {
+        for (&request, entry) in &other.data {
+            let is_newer = self
+                .data
+                .get(&request)
+                .map_or(true, |current| current.last_used < entry.last_used);
+            if is_newer {
+                self.store_entry(request, entry.clone());
+            }
+        }
+        self.evict_least_recently_used();
+    }
+
+    /// Limits the number of entries, evicting the least recently used ones.
+    pub fn set_max_entries(&mut self, max_entries: Option<usize>) {
+        self.max_entries = max_entries;
+        self.evict_least_recently_used();
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let excess = match self.max_entries {
+            Some(max_entries) if self.data.len() > max_entries => self.data.len() - max_entries,
+            _ => return,
+        };
+        let mut by_last_use: Vec<_> = self
+            .data
+            .iter()
+            .map(|(&request, entry)| (entry.last_used, request))
+            .collect();
+        by_last_use.select_nth_unstable(excess - 1);
+        for &(_, request) in &by_last_use[..excess] {
+            self.remove_entry(request);
+        }
+    }
+
+    fn store_entry(&mut self, request: u64, entry: CacheEntry) -> Option<CacheEntry> {
+        if let Some(directory) = &self.directory {
+            directory.write(request, &entry);
+        }
+        self.updated = true;
+        self.data.insert(request, entry)
+    }
+
+    fn insert_entry(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+        configuration: Option<String>,
+    ) -> Option<VerificationResult> {
+        let now = now();
+        let entry = CacheEntry {
+            result,
+            program_name: program_name.to_string(),
+            created: now,
+            last_used: now,
+            configuration,
+        };
+        let previous = self.store_entry(request, entry);
+        self.evict_least_recently_used();
+        previous.map(|entry| entry.result)
+    }
+
+    fn remove_entry(&mut self, request: u64) {
+        if let Some(directory) = &self.directory {
+            directory.remove(request);
+        }
+        self.updated = true;
+        self.data.remove(&request);
+    }
+
+    /// Records the use of an entry that is in memory.
+    fn use_entry(&mut self, request: u64) -> Option<(VerificationResult, Option<String>)> {
+        let entry = self.data.get_mut(&request)?;
+        entry.last_used = now();
+        let used = (entry.result.clone(), entry.configuration.clone());
+        if let Some(directory) = &self.directory {
+            directory.touch(request);
+        }
+        self.updated = true;
+        Some(used)
+    }
 }
 
 impl Drop for PersistentCache {
@@ -125,23 +573,114 @@ impl Drop for PersistentCache {
     }
 }
 
-impl Cache for &mut PersistentCache {
-    fn get(&self, request: u64) -> Option<VerificationResult> {
-        self.data.get(&request).cloned()
+impl Cache for PersistentCache {
+    fn get(&mut self, request: u64) -> Option<VerificationResult> {
+        self.get_with_configuration(request)
+            .map(|(result, _)| result)
     }
-    fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult> {
-        self.updated = true;
-        self.data.insert(request, result)
+    fn get_with_configuration(
+        &mut self,
+        request: u64,
+    ) -> Option<(VerificationResult, Option<String>)> {
+        if let Some(used) = self.use_entry(request) {
+            return Some(used);
+        }
+        // Other processes might have stored the entry in the cache directory.
+        if let Some(entry) = self
+            .directory
+            .as_ref()
+            .and_then(|directory| directory.read(request))
+        {
+            self.data.insert(request, entry);
+        } else {
+            // Entries of the seed directory are copied into the cache.
+            let entry = self.seed_directory.as_ref()?.read(request)?;
+            self.store_entry(request, entry);
+        }
+        let used = self.use_entry(request);
+        self.evict_least_recently_used();
+        used
+    }
+    fn insert(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+    ) -> Option<VerificationResult> {
+        self.insert_entry(request, program_name, result, None)
+    }
+    fn insert_with_configuration(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+        configuration: &str,
+    ) -> Option<VerificationResult> {
+        self.insert_entry(
+            request,
+            program_name,
+            result,
+            Some(configuration.to_string()),
+        )
+    }
+}
+
+impl<C: Cache> Cache for &mut C {
+    fn get(&mut self, request: u64) -> Option<VerificationResult> {
+        (**self).get(request)
+    }
+    fn get_with_configuration(
+        &mut self,
+        request: u64,
+    ) -> Option<(VerificationResult, Option<String>)> {
+        (**self).get_with_configuration(request)
+    }
+    fn insert(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+    ) -> Option<VerificationResult> {
+        (**self).insert(request, program_name, result)
+    }
+    fn insert_with_configuration(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+        configuration: &str,
+    ) -> Option<VerificationResult> {
+        (**self).insert_with_configuration(request, program_name, result, configuration)
     }
 }
 
 impl Cache for &Arc<Mutex<PersistentCache>> {
-    fn get(&self, request: u64) -> Option<VerificationResult> {
-        let mut cache = self.lock().unwrap();
-        cache.deref_mut().get(request)
+    fn get(&mut self, request: u64) -> Option<VerificationResult> {
+        self.lock().unwrap().get(request)
+    }
+    fn get_with_configuration(
+        &mut self,
+        request: u64,
+    ) -> Option<(VerificationResult, Option<String>)> {
+        self.lock().unwrap().get_with_configuration(request)
+    }
+    fn insert(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+    ) -> Option<VerificationResult> {
+        self.lock().unwrap().insert(request, program_name, result)
     }
-    fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult> {
-        let mut cache = self.lock().unwrap();
-        cache.insert(request, result)
+    fn insert_with_configuration(
+        &mut self,
+        request: u64,
+        program_name: &str,
+        result: VerificationResult,
+        configuration: &str,
+    ) -> Option<VerificationResult> {
+        self.lock()
+            .unwrap()
+            .insert_with_configuration(request, program_name, result, configuration)
     }
 }
@@ -0,0 +1,420 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Counterexamples of Carbon, which are the models of the SMT solver used by
+//! Boogie. They are converted to `SiliconCounterexample`s, so that their
+//! translation back to Rust does not depend on the backend.
+//!
+//! Carbon is run with `--counterexample native`, which reports the whole
+//! Boogie model: each Viper variable appears once per incarnation (e.g. `x`,
+//! `x@0`, `x@1`), and the values of fields have to be read from the heap maps,
+//! e.g. `Heap@2`. Carbon's `variables` mode would only report the variables.
+
+use crate::{jni_utils::JniUtils, silicon_counterexample::*};
+use jni::{objects::JObject, JNIEnv};
+use rustc_hash::FxHashMap;
+use viper_sys::wrappers::viper::silver;
+
+/// An entry of a Boogie model.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BoogieModelEntry {
+    /// A literal, or an element of an uninterpreted type, e.g. `T@U!val!3`.
+    Constant(String),
+    /// An operator applied to values, e.g. `(- 5)`.
+    Application(String, Vec<BoogieModelEntry>),
+    /// The interpretation of a function or of a map.
+    Map {
+        options: Vec<(Vec<BoogieModelEntry>, BoogieModelEntry)>,
+        default: Option<Box<BoogieModelEntry>>,
+    },
+    Unspecified,
+}
+
+/// The model reported by Carbon for a verification error.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoogieModel {
+    pub entries: FxHashMap<String, BoogieModelEntry>,
+}
+
+/// The Boogie variable of the current heap.
+const HEAP: &str = "Heap";
+/// The suffix Carbon appends to the names of Viper domains to obtain the names
+/// of the corresponding Boogie types.
+const DOMAIN_TYPE_SUFFIX: &str = "DomainType";
+/// The label of the model of the pre-state, as in Silicon's counterexamples.
+const OLD_LABEL: &str = "old";
+
+impl BoogieModel {
+    pub fn new<'a>(
+        env: &'a JNIEnv<'a>,
+        jni: JniUtils<'a>,
+        counterexample: JObject<'a>,
+    ) -> BoogieModel {
+        let model = jni
+            .unwrap_result(silver::verifier::Counterexample::with(env).call_model(counterexample));
+        let entries_scala =
+            jni.unwrap_result(silver::verifier::Model::with(env).call_entries(model));
+        let entries = jni
+            .stringmap_to_hashmap(entries_scala)
+            .into_iter()
+            .map(|(name, entry)| (name, unwrap_model_entry(env, jni, entry)))
+            .collect();
+        BoogieModel { entries }
+    }
+
+    /// Converts the model to a counterexample with the values of the Viper
+    /// variables in their last incarnation. Besides the current model, it has
+    /// one model per state label of Prusti's encoding (`l0`, `l1`, ...) and
+    /// one for the pre-state, in which the values of the fields are read from
+    /// the heap of the corresponding state.
+    pub fn to_counterexample(&self) -> SiliconCounterexample {
+        let mut incarnations: FxHashMap<&str, (Option<u32>, &BoogieModelEntry)> =
+            FxHashMap::default();
+        for (name, entry) in &self.entries {
+            if matches!(entry, BoogieModelEntry::Map { .. }) || !is_variable_name(name) {
+                continue;
+            }
+            let (variable, incarnation) = split_incarnation(name);
+            match incarnations.get(variable) {
+                Some((last, _)) if *last >= incarnation => {}
+                _ => {
+                    incarnations.insert(variable, (incarnation, entry));
+                }
+            }
+        }
+
+        // The values of the heaps and masks, in any incarnation.
+        let state_values: Vec<&BoogieModelEntry> = self
+            .entries
+            .iter()
+            .filter(|(name, _)| {
+                is_variable_name(name) && is_state_variable(split_incarnation(name).0)
+            })
+            .map(|(_, entry)| entry)
+            .collect();
+        let selects: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(name, _)| name.ends_with("Select"))
+            .filter_map(|(_, entry)| match entry {
+                BoogieModelEntry::Map { options, .. } => Some(options.as_slice()),
+                _ => None,
+            })
+            .collect();
+        // Fields are constants that are used to read from a heap.
+        let fields: FxHashMap<BoogieModelEntry, String> = incarnations
+            .iter()
+            .filter(|(_, (_, entry))| {
+                selects.iter().any(|options| {
+                    options
+                        .iter()
+                        .any(|(args, _)| args.len() == 3 && &args[2] == *entry)
+                })
+            })
+            .map(|(variable, (_, entry))| ((**entry).clone(), variable.to_string()))
+            .collect();
+        let variables: Vec<(&str, &BoogieModelEntry)> = incarnations
+            .iter()
+            .filter(|(variable, (_, entry))| {
+                !is_state_variable(variable) && !fields.contains_key(*entry)
+            })
+            .map(|(variable, (_, entry))| (*variable, *entry))
+            .collect();
+        let converter = EntryConverter {
+            selects,
+            fields,
+            null: incarnations.get("null").map(|(_, entry)| *entry),
+        };
+
+        let convert_model = |heap: Option<&BoogieModelEntry>| Model {
+            entries: variables
+                .iter()
+                .filter_map(|&(variable, entry)| {
+                    Some((
+                        variable.to_string(),
+                        converter.convert(entry, heap, &mut vec![])?,
+                    ))
+                })
+                .collect(),
+        };
+
+        let model = convert_model(incarnations.get(HEAP).map(|(_, heap)| *heap));
+        let mut labels: Vec<(u32, &str, &BoogieModelEntry)> = incarnations
+            .iter()
+            .filter_map(|(variable, (_, heap))| {
+                let label = variable.strip_suffix(HEAP)?;
+                let index = label.strip_prefix('l')?.parse().ok()?;
+                Some((index, label, *heap))
+            })
+            .collect();
+        labels.sort_unstable_by_key(|&(index, ..)| index);
+        let mut label_order = vec![];
+        let mut old_models = FxHashMap::default();
+        // `old(Heap)` is the initial value of the heap variable.
+        if let Some(old_heap) = self.entries.get(HEAP) {
+            label_order.push(OLD_LABEL.to_string());
+            old_models.insert(OLD_LABEL.to_string(), convert_model(Some(old_heap)));
+        }
+        for (_, label, heap) in labels {
+            label_order.push(label.to_string());
+            old_models.insert(label.to_string(), convert_model(Some(heap)));
+        }
+
+        let mut functions = FxHashMap::default();
+        let mut domains: FxHashMap<String, DomainEntry> = FxHashMap::default();
+        for (name, entry) in &self.entries {
+            if let BoogieModelEntry::Map { options, default } = entry {
+                if name.ends_with("Select") || name.ends_with("Store") {
+                    continue;
+                }
+                let function = FunctionEntry {
+                    options: options
+                        .iter()
+                        .map(|(args, result)| {
+                            let args = args
+                                .iter()
+                                // Like Silicon's snapshots, the heaps and masks
+                                // passed to functions are omitted.
+                                .filter(|arg| !state_values.contains(arg))
+                                .map(|arg| converter.convert(arg, None, &mut vec![]))
+                                .collect();
+                            (args, converter.convert(result, None, &mut vec![]))
+                        })
+                        .collect(),
+                    default: default
+                        .as_ref()
+                        .and_then(|default| converter.convert(default, None, &mut vec![])),
+                };
+                // Domain functions are grouped by the domains of their
+                // arguments and results.
+                let mut function_domains: Vec<&str> = function
+                    .options
+                    .iter()
+                    .flat_map(|(args, result)| args.iter().chain(std::iter::once(result)))
+                    .filter_map(|entry| match entry {
+                        Some(ModelEntry::DomainValue(domain, _)) => Some(domain.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                function_domains.sort_unstable();
+                function_domains.dedup();
+                for domain in function_domains {
+                    domains
+                        .entry(domain.to_string())
+                        .or_insert_with(|| DomainEntry {
+                            functions: Functions {
+                                entries: FxHashMap::default(),
+                            },
+                        })
+                        .functions
+                        .entries
+                        .insert(name.clone(), function.clone());
+                }
+                functions.insert(name.clone(), function);
+            }
+        }
+
+        SiliconCounterexample {
+            model,
+            functions: Functions { entries: functions },
+            domains: Domains { entries: domains },
+            old_models,
+            label_order,
+        }
+    }
+}
+
+fn unwrap_model_entry<'a>(
+    env: &'a JNIEnv<'a>,
+    jni: JniUtils<'a>,
+    entry: JObject<'a>,
+) -> BoogieModelEntry {
+    match jni.class_name(entry).as_str() {
+        "viper.silver.verifier.ConstantEntry" => {
+            let constant_wrapper = silver::verifier::ConstantEntry::with(env);
+            let value = jni.get_string(jni.unwrap_result(constant_wrapper.call_value(entry)));
+            BoogieModelEntry::Constant(value)
+        }
+        "viper.silver.verifier.ApplicationEntry" => {
+            let application_wrapper = silver::verifier::ApplicationEntry::with(env);
+            let name = jni.get_string(jni.unwrap_result(application_wrapper.call_name(entry)));
+            let arguments_scala = jni.unwrap_result(application_wrapper.call_arguments(entry));
+            let arguments = jni
+                .seq_to_vec(arguments_scala)
+                .into_iter()
+                .map(|argument| unwrap_model_entry(env, jni, argument))
+                .collect();
+            BoogieModelEntry::Application(name, arguments)
+        }
+        "viper.silver.verifier.MapEntry" => {
+            let map_wrapper = silver::verifier::MapEntry::with(env);
+            let options_scala = jni.unwrap_result(map_wrapper.call_options(entry));
+            let options = jni
+                .map_to_vec(options_scala)
+                .into_iter()
+                .map(|(args_scala, result_scala)| {
+                    let args = jni
+                        .seq_to_vec(args_scala)
+                        .into_iter()
+                        .map(|arg| unwrap_model_entry(env, jni, arg))
+                        .collect();
+                    (args, unwrap_model_entry(env, jni, result_scala))
+                })
+                .collect();
+            let default_scala = jni.unwrap_result(map_wrapper.call_default(entry));
+            let default = match unwrap_model_entry(env, jni, default_scala) {
+                BoogieModelEntry::Unspecified => None,
+                default => Some(Box::new(default)),
+            };
+            BoogieModelEntry::Map { options, default }
+        }
+        _ => BoogieModelEntry::Unspecified,
+    }
+}
+
+/// Splits the name of a Boogie variable into the name of the Viper variable
+/// and its incarnation, which is `None` for the initial value.
+fn split_incarnation(name: &str) -> (&str, Option<u32>) {
+    if let Some((variable, incarnation)) = name.rsplit_once('@') {
+        if let Ok(incarnation) = incarnation.parse() {
+            return (variable.trim_end_matches('@'), Some(incarnation));
+        }
+    }
+    (name, None)
+}
+
+/// Whether the name is the one of a variable or constant, rather than of a
+/// Boogie type, a label marker, or a value of an uninterpreted type.
+fn is_variable_name(name: &str) -> bool {
+    !name.starts_with("T@") && !name.starts_with('%') && !name.contains('!')
+}
+
+/// Whether the Boogie variable encodes the heap or the permission mask of a
+/// state, e.g. `Heap`, `QPMask` or `l0Heap`.
+fn is_state_variable(variable: &str) -> bool {
+    variable.ends_with(HEAP) || variable.ends_with("Mask")
+}
+
+struct EntryConverter<'m> {
+    /// The interpretations of the `Select` functions of Boogie maps, which are
+    /// used to read the heaps.
+    selects: Vec<&'m [(Vec<BoogieModelEntry>, BoogieModelEntry)]>,
+    /// The names of the fields, by value.
+    fields: FxHashMap<BoogieModelEntry, String>,
+    null: Option<&'m BoogieModelEntry>,
+}
+
+impl<'m> EntryConverter<'m> {
+    /// Converts a value, reading the fields of references from the given
+    /// heap. `visited` contains the references that are being converted.
+    fn convert(
+        &self,
+        entry: &BoogieModelEntry,
+        heap: Option<&BoogieModelEntry>,
+        visited: &mut Vec<String>,
+    ) -> Option<ModelEntry> {
+        Some(match entry {
+            BoogieModelEntry::Constant(value) => {
+                if let Ok(value) = value.parse::<bool>() {
+                    ModelEntry::LitBool(value)
+                } else if is_integer(value) {
+                    ModelEntry::LitInt(value.clone())
+                } else if is_decimal(value) {
+                    ModelEntry::LitPerm(value.clone())
+                } else if self.null == Some(entry) {
+                    ModelEntry::NullRef(value.clone())
+                } else if let Some(typ) = uninterpreted_type(value) {
+                    if let Some(domain) = typ.strip_suffix(DOMAIN_TYPE_SUFFIX) {
+                        ModelEntry::DomainValue(domain.to_string(), value.clone())
+                    } else if typ == "Ref" || typ == "U" {
+                        if visited.contains(value) {
+                            ModelEntry::RecursiveRef(value.clone())
+                        } else {
+                            visited.push(value.clone());
+                            let fields = heap
+                                .map(|heap| self.read_fields(entry, heap, visited))
+                                .unwrap_or_default();
+                            visited.pop();
+                            ModelEntry::Ref(value.clone(), fields)
+                        }
+                    } else {
+                        ModelEntry::Other(value.clone(), format!("value of Boogie type {}", typ))
+                    }
+                } else {
+                    ModelEntry::Other(value.clone(), "unknown value".to_string())
+                }
+            }
+            BoogieModelEntry::Application(operator, args) => {
+                match (operator.as_str(), args.as_slice()) {
+                    ("-", [arg]) => match self.convert(arg, heap, visited)? {
+                        ModelEntry::LitInt(value) => ModelEntry::LitInt(format!("-{}", value)),
+                        ModelEntry::LitPerm(value) => ModelEntry::LitPerm(format!("-{}", value)),
+                        _ => return None,
+                    },
+                    ("/", [numerator, denominator]) => {
+                        match (
+                            self.convert(numerator, heap, visited)?,
+                            self.convert(denominator, heap, visited)?,
+                        ) {
+                            (
+                                ModelEntry::LitInt(numerator) | ModelEntry::LitPerm(numerator),
+                                ModelEntry::LitInt(denominator) | ModelEntry::LitPerm(denominator),
+                            ) => ModelEntry::LitPerm(format!("{}/{}", numerator, denominator)),
+                            _ => return None,
+                        }
+                    }
+                    _ => ModelEntry::Other(
+                        format!("{:?}", entry),
+                        "unsupported application".to_string(),
+                    ),
+                }
+            }
+            BoogieModelEntry::Map { .. } | BoogieModelEntry::Unspecified => return None,
+        })
+    }
+
+    fn read_fields(
+        &self,
+        receiver: &BoogieModelEntry,
+        heap: &BoogieModelEntry,
+        visited: &mut Vec<String>,
+    ) -> FxHashMap<String, ModelEntry> {
+        let mut fields = FxHashMap::default();
+        for options in &self.selects {
+            for (args, value) in options.iter() {
+                if let [heap_arg, receiver_arg, field] = args.as_slice() {
+                    if heap_arg != heap || receiver_arg != receiver {
+                        continue;
+                    }
+                    if let Some(field_name) = self.fields.get(field) {
+                        if let Some(value) = self.convert(value, Some(heap), visited) {
+                            fields.insert(field_name.clone(), value);
+                        }
+                    }
+                }
+            }
+        }
+        fields
+    }
+}
+
+fn is_integer(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_decimal(value: &str) -> bool {
+    match value.split_once('.') {
+        Some((integer, fraction)) => is_integer(integer) && is_integer(fraction),
+        None => false,
+    }
+}
+
+/// Returns the type of a value of an uninterpreted Boogie type, which is of
+/// the form `T@<type>!val!<index>`.
+fn uninterpreted_type(value: &str) -> Option<&str> {
+    let (typ, _index) = value.strip_prefix("T@")?.rsplit_once("!val!")?;
+    Some(typ)
+}
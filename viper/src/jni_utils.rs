@@ -168,6 +168,21 @@ impl<'a> JniUtils<'a> {
             .collect::<HashMap<_, _>>()
     }
 
+    /// Converts a Scala Map to a Rust Vec of its key-value pairs
+    pub fn map_to_vec(&self, map: JObject<'a>) -> Vec<(JObject<'a>, JObject<'a>)> {
+        let iter_wrapper = scala::collection::Iterable::with(self.env);
+        let product_wrapper = scala::Product::with(self.env);
+        let seq = self.unwrap_result(iter_wrapper.call_toSeq(map));
+        self.seq_to_vec(seq)
+            .into_iter()
+            .map(|item| {
+                let item1 = self.unwrap_result(product_wrapper.call_productElement(item, 0));
+                let item2 = self.unwrap_result(product_wrapper.call_productElement(item, 1));
+                (item1, item2)
+            })
+            .collect()
+    }
+
     /// Converts a Scala Map to a Vec of its keys. The order of keys is
     /// maintained for map types with a consistent iteration order.
     pub fn stringmap_to_keyvec(&self, map: JObject<'a>) -> Vec<String> {
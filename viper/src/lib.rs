@@ -13,6 +13,7 @@ mod jni_utils;
 #[macro_use]
 pub mod utils;
 mod cache;
+pub mod carbon_counterexample;
 mod java_exception;
 pub mod silicon_counterexample;
 pub mod smt_manager;
@@ -23,7 +24,7 @@ mod verifier;
 mod viper;
 
 pub use crate::{
-    ast_factory::*, ast_utils::*, cache::*, java_exception::*, silicon_counterexample::*,
-    verification_backend::*, verification_context::*, verification_result::*, verifier::*,
-    viper::*,
+    ast_factory::*, ast_utils::*, cache::*, carbon_counterexample::*, java_exception::*,
+    silicon_counterexample::*, verification_backend::*, verification_context::*,
+    verification_result::*, verifier::*, viper::*,
 };
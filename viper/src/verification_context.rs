@@ -6,6 +6,7 @@
 
 use crate::{
     ast_factory::*, ast_utils::*, verification_backend::VerificationBackend, verifier::Verifier,
+    viper::Viper,
 };
 use jni::AttachGuard;
 use log::{debug, info};
@@ -33,6 +34,16 @@ impl<'a> VerificationContext<'a> {
         AstUtils::new(&self.env)
     }
 
+    /// Returns a handle to the JVM of this context, with which other threads
+    /// can attach to the same JVM.
+    pub fn viper(&self) -> Viper {
+        let jvm = self
+            .env
+            .get_java_vm()
+            .expect("failed to get the jvm of the current thread");
+        Viper::from_jvm(jvm)
+    }
+
     /// Should be used only by tests.
     pub fn new_verifier_with_default_smt(&self, backend: VerificationBackend) -> Verifier {
         self.new_verifier_with_default_smt_and_extra_args(backend, vec![])
@@ -17,12 +17,28 @@ pub enum VerificationResult {
     ConsistencyErrors(Vec<String>),
     /// The verification raised a Java exception.
     JavaException(JavaException),
+    /// The verification was cancelled before it finished.
+    Cancelled,
+    /// The verification did not finish before its deadline.
+    TimedOut,
+    /// The backend verifier, i.e. Boogie for Carbon, did not finish before
+    /// its own timeout.
+    BackendTimedOut,
 }
 
 impl VerificationResult {
     pub fn is_success(&self) -> bool {
         matches!(self, Self::Success)
     }
+
+    /// Whether the verification was interrupted, in which case the result
+    /// says nothing about the program.
+    pub fn is_interrupted(&self) -> bool {
+        matches!(
+            self,
+            Self::Cancelled | Self::TimedOut | Self::BackendTimedOut
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -7,13 +7,17 @@
 use crate::{
     ast_factory::*,
     ast_utils::AstUtils,
+    carbon_counterexample::BoogieModel,
     jni_utils::JniUtils,
     silicon_counterexample::SiliconCounterexample,
     smt_manager::SmtManager,
     verification_backend::VerificationBackend,
     verification_result::{VerificationError, VerificationResult},
 };
-use jni::{objects::JObject, JNIEnv};
+use jni::{
+    objects::{GlobalRef, JObject},
+    JNIEnv, JavaVM,
+};
 use log::{debug, error, info};
 use std::path::PathBuf;
 use viper_sys::wrappers::{scala, viper::*};
@@ -25,6 +29,7 @@ pub struct Verifier<'a> {
     jni: JniUtils<'a>,
     ast_utils: AstUtils<'a>,
     smt_manager: SmtManager,
+    backend: VerificationBackend,
 }
 
 impl<'a> Verifier<'a> {
@@ -74,6 +79,7 @@ impl<'a> Verifier<'a> {
             jni,
             ast_utils,
             smt_manager,
+            backend,
         }
     }
 
@@ -103,6 +109,17 @@ impl<'a> Verifier<'a> {
         self
     }
 
+    /// Returns a handle that can stop this verifier from another thread,
+    /// aborting a running verification.
+    pub fn stopper(&self) -> VerifierStopper {
+        VerifierStopper {
+            jvm: self.jni.unwrap_result(self.env.get_java_vm()),
+            verifier_instance: self
+                .jni
+                .unwrap_result(self.env.new_global_ref(self.verifier_instance)),
+        }
+    }
+
     pub fn verify(&mut self, program: Program) -> VerificationResult {
         self.ast_utils.with_local_frame(16, || {
             debug!(
@@ -172,6 +189,15 @@ impl<'a> Verifier<'a> {
                         .is_instance_of(viper_error, "viper/silver/verifier/VerificationError");
 
                     if !is_verification_error {
+                        // Carbon reports that Boogie timed out as an error.
+                        if self
+                            .jni
+                            .is_instance_of(viper_error, "viper/silver/verifier/TimeoutOccurred")
+                        {
+                            debug!("The verification timed out: {}", self.jni.to_string(viper_error));
+                            return VerificationResult::BackendTimedOut;
+                        }
+
                         let is_aborted_exceptionally = self
                             .jni
                             .is_instance_of(viper_error, "viper/silver/verifier/AbortedExceptionally");
@@ -226,6 +252,16 @@ impl<'a> Verifier<'a> {
                                         self.jni,
                                         original_counterexample,
                                     ))
+                                } else if self.backend == VerificationBackend::Carbon {
+                                    // Carbon reports the native Boogie model
+                                    Some(
+                                        BoogieModel::new(
+                                            self.env,
+                                            self.jni,
+                                            original_counterexample,
+                                        )
+                                        .to_counterexample(),
+                                    )
                                 } else {
                                     None
                                 }
@@ -346,3 +382,24 @@ impl<'a> Drop for Verifier<'a> {
             .unwrap_result(self.env.delete_local_ref(self.verifier_instance));
     }
 }
+
+/// Stops a verifier from a thread other than the one using it.
+pub struct VerifierStopper {
+    jvm: JavaVM,
+    verifier_instance: GlobalRef,
+}
+
+impl VerifierStopper {
+    /// Tells the verifier to stop its threads. A verification that is running
+    /// at the same time fails, after which the verifier cannot be used anymore.
+    pub fn stop(&self) {
+        let env = self
+            .jvm
+            .attach_current_thread()
+            .expect("failed to attach jvm thread");
+        let jni = JniUtils::new(&env);
+        jni.unwrap_result(
+            silver::verifier::Verifier::with(&env).call_stop(self.verifier_instance.as_obj()),
+        );
+    }
+}
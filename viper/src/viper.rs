@@ -96,6 +96,11 @@ impl Viper {
         Viper { jvm }
     }
 
+    /// Wraps a JVM that has already been set up by `Viper::new_with_args`.
+    pub(crate) fn from_jvm(jvm: JavaVM) -> Self {
+        Viper { jvm }
+    }
+
     pub fn attach_current_thread(&self) -> VerificationContext {
         let env_guard = self
             .jvm
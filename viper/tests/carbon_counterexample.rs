@@ -0,0 +1,160 @@
+use viper::*;
+
+fn constant(value: &str) -> BoogieModelEntry {
+    BoogieModelEntry::Constant(value.to_string())
+}
+
+/// The interpretation of the function used to read from heaps.
+fn heap_select(options: &[(&str, &str, &str, BoogieModelEntry)]) -> BoogieModelEntry {
+    BoogieModelEntry::Map {
+        options: options
+            .iter()
+            .map(|(heap, receiver, field, value)| {
+                (
+                    vec![constant(heap), constant(receiver), constant(field)],
+                    value.clone(),
+                )
+            })
+            .collect(),
+        default: None,
+    }
+}
+
+fn model(entries: Vec<(&str, BoogieModelEntry)>) -> BoogieModel {
+    BoogieModel {
+        entries: entries
+            .into_iter()
+            .map(|(name, entry)| (name.to_string(), entry))
+            .collect(),
+    }
+}
+
+#[test]
+fn last_incarnation_of_variables() {
+    let counterexample = model(vec![
+        ("x", constant("0")),
+        ("x@0", constant("5")),
+        ("x@1", constant("6")),
+        ("b@@2", constant("true")),
+        (
+            "y@0",
+            BoogieModelEntry::Application("-".to_string(), vec![constant("3")]),
+        ),
+    ])
+    .to_counterexample();
+
+    let entries = &counterexample.model.entries;
+    assert_eq!(entries.get("x"), Some(&ModelEntry::LitInt("6".to_string())));
+    assert_eq!(entries.get("b"), Some(&ModelEntry::LitBool(true)));
+    assert_eq!(
+        entries.get("y"),
+        Some(&ModelEntry::LitInt("-3".to_string()))
+    );
+    assert!(counterexample.label_order.is_empty());
+}
+
+#[test]
+fn fields_are_read_from_the_heap_of_each_state() {
+    let counterexample = model(vec![
+        ("Heap", constant("T@U!val!0")),
+        ("Heap@1", constant("T@U!val!1")),
+        ("l0Heap@0", constant("T@U!val!0")),
+        ("null", constant("T@U!val!2")),
+        ("val_int", constant("T@U!val!3")),
+        ("f$next", constant("T@U!val!4")),
+        ("_1", constant("T@U!val!5")),
+        (
+            "MapType0Select",
+            heap_select(&[
+                ("T@U!val!0", "T@U!val!5", "T@U!val!3", constant("1")),
+                ("T@U!val!1", "T@U!val!5", "T@U!val!3", constant("2")),
+                ("T@U!val!1", "T@U!val!5", "T@U!val!4", constant("T@U!val!5")),
+            ]),
+        ),
+    ])
+    .to_counterexample();
+
+    let current = counterexample.model.entries.get("_1");
+    let mut fields = match current {
+        Some(ModelEntry::Ref(name, fields)) => {
+            assert_eq!(name, "T@U!val!5");
+            fields.clone()
+        }
+        other => panic!("unexpected entry {:?}", other),
+    };
+    assert_eq!(
+        fields.remove("val_int"),
+        Some(ModelEntry::LitInt("2".to_string()))
+    );
+    assert_eq!(
+        fields.remove("f$next"),
+        Some(ModelEntry::RecursiveRef("T@U!val!5".to_string()))
+    );
+    assert!(fields.is_empty());
+    // Field constants and heaps are not variables.
+    assert!(!counterexample.model.entries.contains_key("val_int"));
+    assert!(!counterexample.model.entries.contains_key("Heap"));
+
+    assert_eq!(counterexample.label_order, vec!["old", "l0"]);
+    for label in ["old", "l0"] {
+        match counterexample.old_models[label].entries.get("_1") {
+            Some(ModelEntry::Ref(_, fields)) => assert_eq!(
+                fields.get("val_int"),
+                Some(&ModelEntry::LitInt("1".to_string()))
+            ),
+            other => panic!("unexpected entry {:?} at label {}", other, label),
+        }
+    }
+    assert_eq!(
+        counterexample.model.entries.get("null"),
+        Some(&ModelEntry::NullRef("T@U!val!2".to_string()))
+    );
+}
+
+#[test]
+fn functions_are_grouped_by_domain() {
+    let snapshot = "T@Snap$i32DomainType!val!0";
+    let counterexample = model(vec![
+        ("Heap@0", constant("T@U!val!0")),
+        ("_1$snapshot$0", constant(snapshot)),
+        (
+            "destructor$Snap$i32$$value",
+            BoogieModelEntry::Map {
+                options: vec![(vec![constant(snapshot)], constant("42"))],
+                default: None,
+            },
+        ),
+        (
+            "get_value",
+            BoogieModelEntry::Map {
+                options: vec![(vec![constant("T@U!val!0"), constant("1")], constant("7"))],
+                default: Some(Box::new(constant("0"))),
+            },
+        ),
+    ])
+    .to_counterexample();
+
+    assert_eq!(
+        counterexample.model.entries.get("_1$snapshot$0"),
+        Some(&ModelEntry::DomainValue(
+            "Snap$i32".to_string(),
+            snapshot.to_string()
+        ))
+    );
+    let destructor =
+        &counterexample.domains.entries["Snap$i32"].functions.entries["destructor$Snap$i32$$value"];
+    assert_eq!(
+        destructor.get_function_value(&vec![Some(ModelEntry::DomainValue(
+            "Snap$i32".to_string(),
+            snapshot.to_string()
+        ))]),
+        &Some(ModelEntry::LitInt("42".to_string()))
+    );
+    // The heap argument of a Viper function is omitted.
+    let function = &counterexample.functions.entries["get_value"];
+    assert_eq!(
+        function.get_function_value(&vec![Some(ModelEntry::LitInt("1".to_string()))]),
+        &Some(ModelEntry::LitInt("7".to_string()))
+    );
+    assert_eq!(function.default, Some(ModelEntry::LitInt("0".to_string())));
+}
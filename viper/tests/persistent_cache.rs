@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+use viper::{Cache, PersistentCache, VerificationResult, RESULT_CACHE_VERSION};
+
+#[test]
+fn max_entries_bound_the_cache() {
+    let mut cache = PersistentCache::load_cache(PathBuf::new());
+    for request in 0..5 {
+        cache.insert(request, "program", VerificationResult::Success);
+    }
+    assert_eq!(cache.len(), 5);
+
+    cache.set_max_entries(Some(3));
+    assert_eq!(cache.len(), 3);
+
+    cache.insert(5, "program", VerificationResult::Success);
+    assert_eq!(cache.len(), 3);
+    assert!(cache.get(5).is_some());
+}
+
+#[test]
+fn merge_adds_missing_entries() {
+    let mut cache = PersistentCache::load_cache(PathBuf::new());
+    cache.insert(1, "first", VerificationResult::Success);
+    let mut other = PersistentCache::load_cache(PathBuf::new());
+    other.insert(1, "first", VerificationResult::Success);
+    other.insert(2, "second", VerificationResult::Failure(vec![]));
+
+    cache.merge(&other);
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(2), Some(VerificationResult::Failure(vec![])));
+}
+
+#[test]
+fn saved_cache_keeps_metadata() {
+    let cache_path =
+        std::env::temp_dir().join(format!("prusti-cache-test-{}.bin", std::process::id()));
+    {
+        let mut cache = PersistentCache::load_cache(cache_path.clone());
+        cache.insert(42, "program", VerificationResult::Success);
+        // The cache is saved when dropped.
+    }
+
+    let cache = PersistentCache::load_cache(cache_path.clone());
+    let entries: Vec<_> = cache.entries().collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].0, 42);
+    assert_eq!(entries[0].1.program_name, "program");
+    assert_eq!(entries[0].1.result, VerificationResult::Success);
+    drop(cache);
+
+    std::fs::remove_file(cache_path).unwrap();
+}
+
+#[test]
+fn loading_an_unreadable_cache_fails() {
+    let cache_path =
+        std::env::temp_dir().join(format!("prusti-cache-try-test-{}.bin", std::process::id()));
+    assert!(PersistentCache::try_load_cache(cache_path.clone()).is_err());
+
+    std::fs::write(&cache_path, b"not a cache").unwrap();
+    assert!(PersistentCache::try_load_cache(cache_path.clone()).is_err());
+
+    let mut cache = PersistentCache::load_cache(PathBuf::new());
+    cache.insert(7, "program", VerificationResult::Success);
+    cache.save_cache(&cache_path);
+    let (cache, version) = PersistentCache::try_load_cache(cache_path.clone()).unwrap();
+    assert_eq!(version, RESULT_CACHE_VERSION);
+    assert_eq!(cache.len(), 1);
+    drop(cache);
+
+    std::fs::remove_file(cache_path).unwrap();
+}
+
+#[test]
+fn cache_records_the_winning_configuration() {
+    let cache_dir =
+        std::env::temp_dir().join(format!("prusti-cache-config-test-{}", std::process::id()));
+    {
+        let mut cache = PersistentCache::load_directory_cache(cache_dir.clone());
+        cache.insert(1, "first", VerificationResult::Success);
+        cache.insert_with_configuration(2, "second", VerificationResult::Success, "Carbon");
+    }
+
+    let mut cache = PersistentCache::load_directory_cache(cache_dir.clone());
+    let mut entries: Vec<_> = cache.entries().collect();
+    entries.sort_by_key(|(request, _)| *request);
+    assert_eq!(entries[0].1.configuration, None);
+    assert_eq!(entries[1].1.configuration.as_deref(), Some("Carbon"));
+    assert_eq!(
+        cache.get_with_configuration(2),
+        Some((VerificationResult::Success, Some("Carbon".to_string())))
+    );
+    drop(cache);
+
+    std::fs::remove_dir_all(cache_dir).unwrap();
+}
+
+#[test]
+fn directory_caches_share_results() {
+    let cache_dir =
+        std::env::temp_dir().join(format!("prusti-cache-dir-test-{}", std::process::id()));
+    let seed_dir =
+        std::env::temp_dir().join(format!("prusti-cache-seed-test-{}", std::process::id()));
+    let mut seed = PersistentCache::load_directory_cache(seed_dir.clone());
+    seed.insert(3, "seeded", VerificationResult::Success);
+
+    let mut first = PersistentCache::load_directory_cache(cache_dir.clone());
+    let mut second = PersistentCache::load_directory_cache(cache_dir.clone());
+    first.insert(1, "first", VerificationResult::Success);
+    // Results are visible to other processes without saving the cache.
+    assert_eq!(second.get(1), Some(VerificationResult::Success));
+
+    second.set_seed_directory(Some(seed_dir.clone()));
+    assert_eq!(second.get(3), Some(VerificationResult::Success));
+    // Results taken from the seed directory are stored in the cache.
+    assert_eq!(first.get(3), Some(VerificationResult::Success));
+    assert_eq!(
+        PersistentCache::load_directory_cache(cache_dir.clone()).len(),
+        2
+    );
+
+    first.set_max_entries(Some(1));
+    assert_eq!(
+        PersistentCache::load_directory_cache(cache_dir.clone()).len(),
+        1
+    );
+
+    std::fs::remove_dir_all(cache_dir).unwrap();
+    std::fs::remove_dir_all(seed_dir).unwrap();
+}
+
+#[test]
+fn using_a_directory_entry_does_not_rewrite_it() {
+    let cache_dir =
+        std::env::temp_dir().join(format!("prusti-cache-use-test-{}", std::process::id()));
+    let mut cache = PersistentCache::load_directory_cache(cache_dir.clone());
+    cache.insert(1, "first", VerificationResult::Success);
+    let entry_path = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let contents = std::fs::read(&entry_path).unwrap();
+    filetime::set_file_mtime(&entry_path, filetime::FileTime::zero()).unwrap();
+
+    assert_eq!(cache.get(1), Some(VerificationResult::Success));
+
+    assert_eq!(std::fs::read(&entry_path).unwrap(), contents);
+    let modified = std::fs::metadata(&entry_path).unwrap().modified().unwrap();
+    assert!(modified > std::time::UNIX_EPOCH);
+    drop(cache);
+
+    std::fs::remove_dir_all(cache_dir).unwrap();
+}
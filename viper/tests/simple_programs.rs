@@ -258,3 +258,96 @@ fn success_with_complex_post_condition() {
 
     assert!(verification_result.is_success());
 }
+
+#[test]
+fn failure_with_assign_if_and_assert_on_carbon() {
+    setup();
+
+    let verification_context: VerificationContext = VIPER.attach_current_thread();
+    let ast = verification_context.new_ast_factory();
+
+    let local_var = ast.local_var("x", ast.bool_type());
+
+    let assignment = ast.local_var_assign(local_var, ast.true_lit());
+
+    let if_stmt = ast.if_stmt(
+        local_var,
+        ast.seqn(
+            &[ast.assert(ast.false_lit(), ast.identifier_position(3, 0, "then"))],
+            &[],
+        ),
+        ast.seqn(
+            &[ast.assert(local_var, ast.identifier_position(5, 0, "else"))],
+            &[],
+        ),
+    );
+
+    let method_body = ast.seqn(
+        &[assignment, if_stmt],
+        &[ast.local_var_decl("x", ast.bool_type()).into()],
+    );
+
+    let method = ast.method("foo", &[], &[], &[], &[], Some(method_body));
+
+    let program = ast.program(&[], &[], &[], &[], &[method]);
+
+    let mut verifier =
+        verification_context.new_verifier_with_default_smt(viper::VerificationBackend::Carbon);
+
+    let verification_result = verifier.verify(program);
+
+    if let VerificationResult::Failure(errors) = verification_result {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].full_id,
+            "assert.failed:assertion.false".to_string()
+        );
+        assert_eq!(errors[0].offending_pos_id, Some("then".to_string()));
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn counterexample_on_carbon() {
+    setup();
+
+    let verification_context: VerificationContext = VIPER.attach_current_thread();
+    let ast = verification_context.new_ast_factory();
+
+    let local_var = ast.local_var("x", ast.int_type());
+
+    let assignment = ast.local_var_assign(local_var, ast.int_lit(5));
+
+    let assertion = ast.assert(
+        ast.eq_cmp(local_var, ast.int_lit(6)),
+        ast.identifier_position(2, 0, "assertion"),
+    );
+
+    let method_body = ast.seqn(
+        &[assignment, assertion],
+        &[ast.local_var_decl("x", ast.int_type()).into()],
+    );
+
+    let method = ast.method("foo", &[], &[], &[], &[], Some(method_body));
+
+    let program = ast.program(&[], &[], &[], &[], &[method]);
+
+    let mut verifier = verification_context.new_verifier_with_default_smt_and_extra_args(
+        viper::VerificationBackend::Carbon,
+        vec!["--counterexample".to_string(), "native".to_string()],
+    );
+
+    let verification_result = verifier.verify(program);
+
+    if let VerificationResult::Failure(errors) = verification_result {
+        assert_eq!(errors.len(), 1);
+        let counterexample = errors[0].counterexample.as_ref().unwrap();
+        assert_eq!(
+            counterexample.model.entries.get("x"),
+            Some(&ModelEntry::LitInt("5".to_string()))
+        );
+    } else {
+        unreachable!()
+    }
+}
@@ -241,11 +241,45 @@ pub enum BuiltinFunc {
     MapContains,
     LookupMap,
     MapLen,
+    /// The set of keys of the map.
+    MapKeys,
+    /// The set of values of the map.
+    MapValues,
     EmptySeq,
     SingleSeq,
     LookupSeq,
     ConcatSeq,
     SeqLen,
+    UpdateSeq,
+    TakeSeq,
+    DropSeq,
+    /// The elements from the first index (inclusive) to the second index
+    /// (exclusive).
+    SubSeq,
+    SeqContains,
+    /// The index of the first occurrence of the element, or -1.
+    SeqIndexOf,
+    /// The sequence of the elements of an array or a slice.
+    AsSeq,
+    EmptySet,
+    SingleSet,
+    SetUnion,
+    SetIntersection,
+    SetMinus,
+    SetSubset,
+    SetContains,
+    SetLen,
+    EmptyMultiSet,
+    SingleMultiSet,
+    MultiSetUnion,
+    MultiSetIntersection,
+    MultiSetMinus,
+    MultiSetSubset,
+    /// Whether the multiset contains at least one copy of the element.
+    MultiSetContains,
+    /// The number of copies of the element in the multiset.
+    MultiSetCount,
+    MultiSetLen,
     NewInt,
     Index,
     Len,
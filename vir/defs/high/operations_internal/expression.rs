@@ -444,7 +444,11 @@ impl Expression {
                                     lifetime: _,
                                     uniqueness: ty::Uniqueness::Shared,
                                     target_type:
-                                        box Type::Map(_) | box Type::Sequence(_) | box Type::Int(_),
+                                        box Type::Map(_)
+                                        | box Type::Set(_)
+                                        | box Type::MultiSet(_)
+                                        | box Type::Sequence(_)
+                                        | box Type::Int(_),
                                 }),
                             ..
                         }),
@@ -456,7 +460,11 @@ impl Expression {
                                     lifetime: _,
                                     uniqueness: ty::Uniqueness::Shared,
                                     target_type:
-                                        box Type::Map(_) | box Type::Sequence(_) | box Type::Int(_),
+                                        box Type::Map(_)
+                                        | box Type::Set(_)
+                                        | box Type::MultiSet(_)
+                                        | box Type::Sequence(_)
+                                        | box Type::Int(_),
                                 }),
                             ..
                         }),
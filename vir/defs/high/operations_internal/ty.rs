@@ -176,6 +176,8 @@ impl Type {
     pub fn contains_type_variables(&self) -> bool {
         match self {
             Self::Sequence(Sequence { element_type, .. })
+            | Self::Set(Set { element_type, .. })
+            | Self::MultiSet(MultiSet { element_type, .. })
             | Self::Array(Array { element_type, .. })
             | Self::Slice(Slice { element_type, .. }) => element_type.is_type_var(),
             Self::Reference(Reference { target_type, .. })
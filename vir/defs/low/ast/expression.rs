@@ -178,11 +178,21 @@ pub enum ContainerOpKind {
     SeqIndex,
     SeqConcat,
     SeqLen,
+    SeqUpdate,
+    SeqTake,
+    SeqDrop,
+    SeqContains,
     MapEmpty,
     MapUpdate,
     MapContains,
     MapLookup,
     MapLen,
+    /// The set of keys of a map. The `container_type` of the operation is the
+    /// type of the resulting set.
+    MapDomain,
+    /// The set of values of a map. The `container_type` of the operation is
+    /// the type of the resulting set.
+    MapRange,
     SetEmpty,
     SetConstructor,
     SetUnion,
@@ -158,6 +158,10 @@ impl CfgMethod {
         self.method_name.clone()
     }
 
+    pub fn set_name(&mut self, method_name: String) {
+        self.method_name = method_name;
+    }
+
     pub fn labels(&self) -> &FxHashSet<String> {
         &self.labels
     }
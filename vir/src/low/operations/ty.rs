@@ -181,6 +181,9 @@ impl Typed for ContainerOp {
             ContainerOpKind::SeqEmpty
             | ContainerOpKind::SeqConstructor
             | ContainerOpKind::SeqConcat
+            | ContainerOpKind::SeqUpdate
+            | ContainerOpKind::SeqTake
+            | ContainerOpKind::SeqDrop
             | ContainerOpKind::MapEmpty
             | ContainerOpKind::MapUpdate
             | ContainerOpKind::SetEmpty
@@ -192,16 +195,20 @@ impl Typed for ContainerOp {
             | ContainerOpKind::MultiSetConstructor
             | ContainerOpKind::MultiSetUnion
             | ContainerOpKind::MultiSetIntersection
-            | ContainerOpKind::MultiSetMinus => &self.container_type,
+            | ContainerOpKind::MultiSetMinus
+            | ContainerOpKind::MapDomain
+            | ContainerOpKind::MapRange => &self.container_type,
             ContainerOpKind::SeqLen
             | ContainerOpKind::MapLen
             | ContainerOpKind::SetCardinality
-            | ContainerOpKind::MultiSetCardinality => &Type::Int,
+            | ContainerOpKind::MultiSetCardinality
+            // In Viper, membership in a multiset yields the number of copies.
+            | ContainerOpKind::MultiSetContains => &Type::Int,
             ContainerOpKind::MapContains
+            | ContainerOpKind::SeqContains
             | ContainerOpKind::SetSubset
             | ContainerOpKind::SetContains
-            | ContainerOpKind::MultiSetSubset
-            | ContainerOpKind::MultiSetContains => &Type::Bool,
+            | ContainerOpKind::MultiSetSubset => &Type::Bool,
             ContainerOpKind::SeqIndex => match &self.container_type {
                 Type::Seq(ty::Seq { element_type, .. }) => element_type,
                 _ => unreachable!("Expected Seq type, got {:?}", self.container_type),